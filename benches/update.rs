@@ -0,0 +1,110 @@
+//! Cost of one game update in crowded states, as a baseline for collision
+//! and drawing optimizations. Every iteration updates a fresh copy of the state.
+use cl_games::game::Game;
+use cl_games::input::FrameInput;
+use cl_games::point::{GameBasis, Line, PlayArea, Point};
+use cl_games::snake::SnakeGame;
+use cl_games::space_invaders::{EnemyPreset, PropsPreset, SpaceInvadersGame};
+use cl_games::tetris::{Board, Color, TetrisGame};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::Duration;
+
+/// Wide screen, so the enemy formations get hundreds of enemies.
+const SCREEN_SIZE: (u16, u16) = (400, 60);
+const FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+/// Longer than the invaders update interval, so enemies and bullets move in every update
+const INVADERS_TICK: Duration = Duration::from_millis(101);
+const SEED: u64 = 7;
+
+fn bench_update<G: Game + Clone>(c: &mut Criterion, name: &str, game: &G, delta_time: Duration) {
+    c.bench_function(name, |b| {
+        b.iter_batched_ref(
+            || game.clone(),
+            |game| game.update(&FrameInput::default(), &delta_time),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+/// Snake winding over the screen in rows, moving right at the end.
+fn long_snake() -> SnakeGame {
+    let rng = StdRng::seed_from_u64(SEED);
+    let area = PlayArea::screen(SCREEN_SIZE.0, SCREEN_SIZE.1);
+    let mut game = SnakeGame::new(Point::new(5.0, 2.0), area, rng);
+    let (left, right) = (5.0, SCREEN_SIZE.0 as f32 / 2.0 - 5.0);
+    // Odd, so the last row goes right
+    let rows = ((SCREEN_SIZE.1 as usize - 4) / 2) | 1;
+    let mut segments: Vec<Line<GameBasis>> = vec![];
+    for row in 0..rows {
+        let y = 2.0 + row as f32 * 2.0;
+        let (from, to) = if row % 2 == 0 {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        if row > 0 {
+            segments.push(Line::new(Point::new(from, y - 2.0), Point::new(from, y)));
+        }
+        segments.push(Line::new(Point::new(from, y), Point::new(to, y)));
+    }
+    game.snake.segments = segments;
+    game
+}
+
+/// Bottom four lines full, cleared on the next update.
+fn tetris_before_clear() -> TetrisGame {
+    let mut game = TetrisGame::new(StdRng::seed_from_u64(SEED));
+    for y in Board::HEIGHT - 4..Board::HEIGHT {
+        for x in 0..Board::WIDTH {
+            game.board.set(x, y, Some(Color::Cyan));
+        }
+    }
+    game
+}
+
+/// Formation shooting for a few seconds, so the screen fills with bullets.
+fn invaders_with_bullets() -> SpaceInvadersGame {
+    let (width, height) = SCREEN_SIZE;
+    let rng = StdRng::seed_from_u64(SEED);
+    let mut game = SpaceInvadersGame::new(
+        height,
+        width,
+        EnemyPreset::RandomFire,
+        PropsPreset::Wall,
+        rng,
+    );
+    for _ in 0..30 {
+        game.update(&FrameInput::default(), &INVADERS_TICK);
+    }
+    game
+}
+
+fn updates(c: &mut Criterion) {
+    let (width, height) = SCREEN_SIZE;
+
+    bench_update(c, "snake long body", &long_snake(), FRAME_TIME);
+    bench_update(
+        c,
+        "tetris four lines cleared",
+        &tetris_before_clear(),
+        FRAME_TIME,
+    );
+    let formation = SpaceInvadersGame::new(
+        height,
+        width,
+        EnemyPreset::CheckeredLeftRight,
+        PropsPreset::Wall,
+        StdRng::seed_from_u64(SEED),
+    );
+    bench_update(c, "invaders many enemies", &formation, INVADERS_TICK);
+    bench_update(
+        c,
+        "invaders many bullets",
+        &invaders_with_bullets(),
+        INVADERS_TICK,
+    );
+}
+
+criterion_group!(benches, updates);
+criterion_main!(benches);
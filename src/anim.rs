@@ -0,0 +1,88 @@
+//! Timed visual behavior: easing curves, tweens and oscillators.
+use std::time::Duration;
+
+/// Easing curve mapping linear progress `0..=1` to eased progress.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ease {
+    Linear,
+    /// Starts slow
+    InQuad,
+    /// Ends slow
+    OutQuad,
+    InOutQuad,
+}
+
+impl Ease {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::InQuad => t * t,
+            Ease::OutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Ease::InOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+        }
+    }
+}
+
+pub fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+/// Animation running for a fixed time.
+#[derive(Clone, Copy, Debug)]
+pub struct Tween {
+    elapsed: Duration,
+    duration: Duration,
+    ease: Ease,
+}
+
+impl Tween {
+    pub fn new(duration: Duration, ease: Ease) -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            duration,
+            ease,
+        }
+    }
+
+    pub fn advance(&mut self, delta_time: Duration) {
+        self.elapsed = (self.elapsed + delta_time).min(self.duration);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Eased progress from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        self.ease
+            .apply(self.elapsed.as_secs_f32() / self.duration.as_secs_f32())
+    }
+
+    /// Value between `from` and `to` at the current progress.
+    pub fn value(&self, from: f32, to: f32) -> f32 {
+        lerp(from, to, self.progress())
+    }
+}
+
+/// Sine wave from -1 to 1 with the given period.
+pub fn oscillate(elapsed: Duration, period: Duration) -> f32 {
+    if period.is_zero() {
+        return 0.0;
+    }
+    let phase = elapsed.as_secs_f32() / period.as_secs_f32();
+    (phase * std::f32::consts::TAU).sin()
+}
@@ -0,0 +1,159 @@
+//! Challenge of the week: the game and its seed follow from the ISO week number,
+//! so everyone gets the same challenge and it changes without updates.
+//!
+//! Best scores are kept per week in `<data dir>/cl_games/challenges.toml`.
+use crate::registry::{GameEntry, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const FILE_NAME: &str = "challenges.toml";
+
+/// ISO 8601 week, weeks start on Monday and belong to the year of their Thursday.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Week {
+    pub year: i32,
+    /// 1 to 53
+    pub number: u32,
+}
+
+impl Week {
+    /// Current week in UTC.
+    pub fn current() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self::from_days((secs / (24 * 60 * 60)) as i64)
+    }
+
+    /// Week of the day counted from 1970-01-01.
+    pub fn from_days(days: i64) -> Self {
+        // 1970-01-01 was a Thursday
+        let weekday = (days + 3).rem_euclid(7);
+        let thursday = days - weekday + 3;
+
+        let mut year = 1970;
+        let mut day_of_year = thursday;
+        while day_of_year < 0 {
+            year -= 1;
+            day_of_year += days_in_year(year);
+        }
+        while day_of_year >= days_in_year(year) {
+            day_of_year -= days_in_year(year);
+            year += 1;
+        }
+
+        Self {
+            year,
+            number: (day_of_year / 7 + 1) as u32,
+        }
+    }
+
+    /// Same for every player in the week.
+    pub fn seed(&self) -> u64 {
+        // splitmix64, close weeks get unrelated seeds
+        let mut seed = (self.year as u64) << 8 | self.number as u64;
+        seed = seed.wrapping_add(0x9e3779b97f4a7c15);
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d049bb133111eb);
+        seed ^ (seed >> 31)
+    }
+}
+
+impl std::fmt::Display for Week {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-W{:02}", self.year, self.number)
+    }
+}
+
+fn days_in_year(year: i32) -> i64 {
+    if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 {
+        366
+    } else {
+        365
+    }
+}
+
+/// Game and seed of a week.
+#[derive(Clone, Copy, Debug)]
+pub struct Challenge {
+    pub week: Week,
+    /// Index in the games registry
+    pub game: usize,
+    pub seed: u64,
+}
+
+impl Challenge {
+    /// None without games.
+    pub fn of_week(week: Week, registry: &Registry) -> Option<Self> {
+        let count = registry.games().len();
+        if count == 0 {
+            return None;
+        }
+        let seed = week.seed();
+
+        Some(Self {
+            week,
+            game: (seed % count as u64) as usize,
+            seed,
+        })
+    }
+
+    pub fn entry<'a>(&self, registry: &'a Registry) -> &'a GameEntry {
+        registry
+            .get(self.game)
+            .expect("challenge game is registered")
+    }
+
+    /// Slot in the best scores, builds with other games get other challenges.
+    fn key(&self, registry: &Registry) -> String {
+        format!("{}-{}", self.week, self.entry(registry).id)
+    }
+}
+
+/// Best challenge scores by week and game.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BestScores {
+    scores: BTreeMap<String, i64>,
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+impl BestScores {
+    /// Missing or broken file means no scores.
+    pub fn load() -> Self {
+        path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path = path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+
+    pub fn get(&self, challenge: &Challenge, registry: &Registry) -> Option<i64> {
+        self.scores.get(&challenge.key(registry)).copied()
+    }
+
+    /// Returns true if the score is a new best of the challenge.
+    pub fn add(&mut self, challenge: &Challenge, registry: &Registry, score: i64) -> bool {
+        let key = challenge.key(registry);
+        if self.scores.get(&key).is_some_and(|&best| best >= score) {
+            return false;
+        }
+        self.scores.insert(key, score);
+        true
+    }
+}
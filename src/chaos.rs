@@ -0,0 +1,52 @@
+//! Chaos mode: random events fired during a run.
+//!
+//! Enabled with `chaos = true` in the config. The scheduler decides when an event
+//! happens and the game decides what happens (see `Game::chaos_event`).
+use rand::{rngs::StdRng, Rng};
+use std::time::Duration;
+
+/// Time between events is picked from this range.
+pub const MIN_PERIOD: Duration = Duration::from_secs(10);
+pub const MAX_PERIOD: Duration = Duration::from_secs(20);
+/// How long the event announcement is shown.
+pub const BANNER_TIME: Duration = Duration::from_secs(2);
+/// Announcement drops from the top of the screen during this time.
+pub const BANNER_DROP_TIME: Duration = Duration::from_millis(300);
+/// Announcement color switches with this period.
+pub const BANNER_BLINK_PERIOD: Duration = Duration::from_millis(400);
+/// How long events with an effect over time last.
+pub const EFFECT_TIME: Duration = Duration::from_secs(5);
+
+/// Fires events at random intervals of game time.
+#[derive(Clone)]
+pub struct Scheduler {
+    until_next: Duration,
+    rng: StdRng,
+}
+
+impl Scheduler {
+    pub fn new(mut rng: StdRng) -> Self {
+        Self {
+            until_next: Self::gen_period(&mut rng),
+            rng,
+        }
+    }
+
+    fn gen_period(rng: &mut StdRng) -> Duration {
+        rng.gen_range(MIN_PERIOD..=MAX_PERIOD)
+    }
+
+    /// Advance the time. Returns true when an event is due.
+    pub fn tick(&mut self, delta_time: Duration) -> bool {
+        match self.until_next.checked_sub(delta_time) {
+            Some(left) if !left.is_zero() => {
+                self.until_next = left;
+                false
+            }
+            _ => {
+                self.until_next = Self::gen_period(&mut self.rng);
+                true
+            }
+        }
+    }
+}
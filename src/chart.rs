@@ -0,0 +1,73 @@
+//! Text charts: sparklines and bar charts drawn into a frame buffer,
+//! for trends like scores over runs or accuracy over waves.
+//!
+//! Values are scaled between 0 and the highest one (the lowest one
+//! if it is negative), the latest values are on the right.
+use crate::render::{FrameBuffer, Layer};
+
+/// Lowest and highest value of the scale.
+fn bounds(values: &[f32]) -> (f32, f32) {
+    let low = values.iter().copied().fold(0.0, f32::min);
+    let high = values.iter().copied().fold(0.0, f32::max);
+    (low, high)
+}
+
+/// Value from 0 to 1 on the scale.
+fn scaled(value: f32, (low, high): (f32, f32)) -> f32 {
+    if high > low {
+        (value - low) / (high - low)
+    } else {
+        // All equal: half height, so the chart is not empty
+        0.5
+    }
+}
+
+/// Last `width` values, one level symbol each.
+pub fn sparkline(values: &[f32], width: u16) -> String {
+    let levels = crate::glyphs::get().levels;
+    let values = &values[values.len().saturating_sub(width as usize)..];
+    let bounds = bounds(values);
+
+    values
+        .iter()
+        .map(|&value| {
+            let level = (scaled(value, bounds) * (levels.len() - 1) as f32).round() as usize;
+            levels[level]
+        })
+        .collect()
+}
+
+/// Bars of the last `width` values in the area from (`x`, `y`)
+/// `width` symbols wide and `height` high. Bar tops show eighths of a row.
+pub fn draw_bars(
+    frame: &mut FrameBuffer,
+    layer: Layer,
+    (x, y): (u16, u16),
+    (width, height): (u16, u16),
+    values: &[f32],
+) {
+    let levels = crate::glyphs::get().levels;
+    let full = levels[levels.len() - 1].to_string();
+    let values = &values[values.len().saturating_sub(width as usize)..];
+    let bounds = bounds(values);
+
+    for (column, &value) in values.iter().enumerate() {
+        let column_x = x + column as u16;
+        // Bar height in level steps
+        let steps =
+            (scaled(value, bounds) * (height as usize * levels.len()) as f32).round() as usize;
+        let (full_rows, rest) = (steps / levels.len(), steps % levels.len());
+
+        for row in 0..full_rows as u16 {
+            frame.print(layer, column_x, y + height - 1 - row, &full);
+        }
+        if rest > 0 && (full_rows as u16) < height {
+            frame.print(
+                layer,
+                column_x,
+                y + height - 1 - full_rows as u16,
+                &levels[rest - 1].to_string(),
+            );
+        }
+    }
+}
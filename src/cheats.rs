@@ -0,0 +1,118 @@
+//! Cheat codes: key sequences typed in the main menu (see `config::CheatCodes`).
+//!
+//! What they unlock is kept in `<data dir>/cl_games/unlocks.toml`,
+//! typing a code again locks it back.
+use crate::input::SequenceMatcher;
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub const FILE_NAME: &str = "unlocks.toml";
+/// Up, up, down, down, left, right, left, right, b, a
+pub const KONAMI_CODE: [KeyCode; 10] = [
+    KeyCode::Up,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Char('b'),
+    KeyCode::Char('a'),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Unlock {
+    /// Chaos mode in every run, as with `chaos = true` in the config
+    Chaos,
+}
+
+impl Unlock {
+    pub fn name(self) -> &'static str {
+        match self {
+            Unlock::Chaos => "Chaos mode",
+        }
+    }
+}
+
+/// Codes from the config being typed.
+pub struct Codes {
+    matchers: Vec<(Unlock, SequenceMatcher)>,
+}
+
+impl Codes {
+    pub fn from_config() -> Self {
+        let cheats = &crate::config::get().cheats;
+        Self {
+            matchers: vec![(Unlock::Chaos, SequenceMatcher::new(cheats.chaos.clone()))],
+        }
+    }
+
+    /// Returns the unlock of the code completed by the key.
+    pub fn push(&mut self, code: KeyCode) -> Option<Unlock> {
+        let mut completed = None;
+        for (unlock, matcher) in self.matchers.iter_mut() {
+            if matcher.push(code) {
+                completed = Some(*unlock);
+            }
+        }
+        completed
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Unlocks {
+    unlocked: Vec<Unlock>,
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+impl Unlocks {
+    /// Missing or broken file means nothing unlocked.
+    pub fn load() -> Self {
+        path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path = path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+
+    pub fn has(&self, unlock: Unlock) -> bool {
+        self.unlocked.contains(&unlock)
+    }
+
+    /// Returns true if it is unlocked now.
+    pub fn toggle(&mut self, unlock: Unlock) -> bool {
+        if self.has(unlock) {
+            self.unlocked.retain(|&other| other != unlock);
+            false
+        } else {
+            self.unlocked.push(unlock);
+            true
+        }
+    }
+}
+
+/// Toggle the unlock and save it. Returns the message for the player.
+pub fn apply(unlock: Unlock) -> String {
+    let mut unlocks = Unlocks::load();
+    let is_unlocked = unlocks.toggle(unlock);
+    let state = if is_unlocked { "unlocked" } else { "locked" };
+    match unlocks.save() {
+        Ok(()) => format!("{} {}!", unlock.name(), state),
+        Err(err) => format!("{} not {}: {}", unlock.name(), state, err),
+    }
+}
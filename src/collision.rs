@@ -0,0 +1,66 @@
+//! Grid of buckets with the indices of the points in each cell, to find the points
+//! near a place without checking all of them. Dense waves and bullet storms
+//! check a few neighbors instead of every pair.
+use crate::point::Point;
+use std::collections::HashMap;
+
+/// Indices of points by the grid cell they are in.
+/// Buckets are kept when cleared, so rebuilding the grid every tick doesn't allocate.
+#[derive(Clone, Debug, Default)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    buckets: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    /// Grid of square cells of the size, best around the distance searched.
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0);
+        Self {
+            cell_size,
+            buckets: HashMap::new(),
+        }
+    }
+
+    fn cell_of(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x / self.cell_size).floor() as i32,
+            (y / self.cell_size).floor() as i32,
+        )
+    }
+
+    pub fn clear(&mut self) {
+        for bucket in self.buckets.values_mut() {
+            bucket.clear();
+        }
+    }
+
+    pub fn insert<Basis: Copy>(&mut self, ind: usize, point: Point<Basis>) {
+        let cell = self.cell_of(point.x, point.y);
+        self.buckets.entry(cell).or_default().push(ind);
+    }
+
+    /// Replace the contents with the points, indexed in order.
+    pub fn rebuild<Basis: Copy>(&mut self, points: impl IntoIterator<Item = Point<Basis>>) {
+        self.clear();
+        for (ind, point) in points.into_iter().enumerate() {
+            self.insert(ind, point);
+        }
+    }
+
+    /// Indices of the points that may be closer than `radius` on both axes,
+    /// in no particular order. Check them with `Point::compare`.
+    pub fn near<Basis: Copy>(
+        &self,
+        point: Point<Basis>,
+        radius: f32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let (left, top) = self.cell_of(point.x - radius, point.y - radius);
+        let (right, bottom) = self.cell_of(point.x + radius, point.y + radius);
+        (top..=bottom)
+            .flat_map(move |y| (left..=right).map(move |x| (x, y)))
+            .filter_map(|cell| self.buckets.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
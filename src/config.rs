@@ -0,0 +1,804 @@
+//! Settings read from `~/.config/cl_games/config.toml`:
+//!
+//! ```toml
+//! frame_rate = 30
+//!
+//! [keys]
+//! left = "a"
+//! right = "d"
+//! fire = "w"
+//!
+//! [tetris]
+//! descend_slow_ms = 300
+//! ```
+//!
+//! Missing values keep their defaults, so do values that can't be used;
+//! those are listed over the main menu. The file is read again when it changes,
+//! even during a run.
+//!
+//! ```text
+//! cl_games config check
+//! ```
+//!
+//! lists the problems of the config file without starting the games.
+use crate::input::Action;
+use crossterm::event::{KeyCode, KeyEvent};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+pub const COMMAND: &str = "config";
+/// Environment variable overriding the config file path.
+pub const CONFIG_PATH_VAR: &str = "CL_GAMES_CONFIG";
+pub const DEFAULT_FRAME_RATE: u32 = 60;
+/// Higher frame rates are lowered to this one.
+pub const MAX_FRAME_RATE: u32 = 240;
+/// Cell aspects out of this range are a typo rather than a font.
+const MIN_CELL_ASPECT: f32 = 1.0;
+const MAX_CELL_ASPECT: f32 = 4.0;
+
+static CONFIG: Lazy<RwLock<Arc<Config>>> = Lazy::new(Default::default);
+/// Modification time of the config file at the last read
+static MODIFIED: Mutex<Option<SystemTime>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Frames per second
+    pub frame_rate: u32,
+    /// Random events during runs, see src/chaos.rs
+    pub chaos: bool,
+    /// Floating numbers where points are earned
+    pub score_popups: bool,
+    /// Calmer screens for players bothered by motion:
+    /// no confetti on a new personal best
+    pub reduced_effects: bool,
+    /// Seconds of the main menu without input before a demo game starts, 0 for no demo
+    pub demo_after: u64,
+    /// Mouse input in menus and games. Takes the mouse from the terminal,
+    /// so text can't be selected. Read at the start only.
+    pub mouse: bool,
+    /// Sounds of game events: "off", "bell" or "audio" (with the rodio feature)
+    pub sound: crate::sound::Backend,
+    /// Colors: "classic", "monochrome" or "solarized".
+    /// The one picked in the settings menu wins
+    pub theme: crate::theme::Name,
+    /// Symbols: "unicode", "ascii" or "auto" (ASCII where Unicode looks unsupported)
+    pub glyphs: crate::glyphs::Mode,
+    /// Height of a terminal cell over its width, 2 in most fonts. Set it for fonts
+    /// with other cells, so the game worlds keep their proportions. Read at the start only.
+    pub cell_aspect: f32,
+    /// Patterns besides colors, see src/glyphs.rs.
+    /// The mode picked in the settings menu wins
+    pub colorblind: bool,
+    /// Language of the texts: "english" or "german".
+    /// The one picked in the settings menu wins
+    pub language: crate::i18n::Language,
+    /// Lines written to the log file, see src/logging.rs: "off", "error", "warn",
+    /// "info", "debug" or "trace". `--log <level>` wins. Read at the start only.
+    pub log_level: crate::logging::Level,
+    /// Controller input, see src/gamepad.rs. Read at the start only.
+    #[cfg(feature = "gamepad")]
+    pub gamepad: bool,
+    pub keys: KeyBindings,
+    pub cheats: CheatCodes,
+    pub difficulty: DifficultyConfig,
+    pub telemetry: TelemetryConfig,
+    #[cfg(feature = "snake")]
+    pub snake: SnakeConfig,
+    #[cfg(feature = "tetris")]
+    pub tetris: TetrisConfig,
+    #[cfg(feature = "invaders")]
+    pub space_invaders: SpaceInvadersConfig,
+    #[cfg(feature = "pong")]
+    pub pong: PongConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            frame_rate: DEFAULT_FRAME_RATE,
+            chaos: false,
+            score_popups: true,
+            reduced_effects: false,
+            demo_after: 60,
+            mouse: true,
+            sound: crate::sound::Backend::default(),
+            theme: crate::theme::Name::default(),
+            glyphs: crate::glyphs::Mode::default(),
+            cell_aspect: crate::point::DEFAULT_CELL_ASPECT,
+            colorblind: false,
+            language: crate::i18n::Language::default(),
+            log_level: crate::logging::Level::default(),
+            #[cfg(feature = "gamepad")]
+            gamepad: false,
+            keys: KeyBindings::default(),
+            cheats: CheatCodes::default(),
+            difficulty: DifficultyConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            #[cfg(feature = "snake")]
+            snake: SnakeConfig::default(),
+            #[cfg(feature = "tetris")]
+            tetris: TetrisConfig::default(),
+            #[cfg(feature = "invaders")]
+            space_invaders: SpaceInvadersConfig::default(),
+            #[cfg(feature = "pong")]
+            pong: PongConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Target frame time. Games with their own tick (tetris, invaders)
+    /// keep the same speed at any frame rate.
+    pub fn between_frames(&self) -> Duration {
+        Duration::from_secs(1) / self.frame_rate.clamp(1, MAX_FRAME_RATE)
+    }
+}
+
+/// Keys replacing the default ones. Default keys keep working.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub up: Option<KeyCode>,
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub down: Option<KeyCode>,
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub left: Option<KeyCode>,
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub right: Option<KeyCode>,
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fire: Option<KeyCode>,
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rotate: Option<KeyCode>,
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pause: Option<KeyCode>,
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub suspend: Option<KeyCode>,
+    #[serde(
+        deserialize_with = "deserialize_key",
+        serialize_with = "serialize_key",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exit: Option<KeyCode>,
+}
+
+impl KeyBindings {
+    /// Action of the key: the rebound one or the default one.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        let bindings = [
+            (self.up, Action::MoveUp),
+            (self.down, Action::MoveDown),
+            (self.left, Action::MoveLeft),
+            (self.right, Action::MoveRight),
+            (self.fire, Action::Fire),
+            (self.rotate, Action::Rotate),
+            (self.pause, Action::Pause),
+            (self.suspend, Action::Suspend),
+            (self.exit, Action::Quit),
+        ];
+
+        match bindings
+            .iter()
+            .find(|(binding, _)| *binding == Some(key.code))
+        {
+            Some(&(_, action)) => Some(action),
+            None => crate::input::default_action(key.code),
+        }
+    }
+}
+
+/// Key sequences typed in the main menu, see src/cheats.rs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheatCodes {
+    /// Toggles chaos mode. Empty to turn the code off
+    #[serde(
+        deserialize_with = "deserialize_keys",
+        serialize_with = "serialize_keys"
+    )]
+    pub chaos: Vec<KeyCode>,
+}
+
+impl Default for CheatCodes {
+    fn default() -> Self {
+        Self {
+            chaos: crate::cheats::KONAMI_CODE.to_vec(),
+        }
+    }
+}
+
+/// Dynamic difficulty, see src/difficulty.rs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifficultyConfig {
+    pub dynamic: bool,
+    /// Lowest level, the balance values are multiplied by the level
+    pub min: f32,
+    /// Highest level
+    pub max: f32,
+}
+
+impl Default for DifficultyConfig {
+    fn default() -> Self {
+        Self {
+            dynamic: false,
+            min: 0.5,
+            max: 1.5,
+        }
+    }
+}
+
+/// Local log of finished runs, see src/telemetry.rs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// Log file instead of `<data dir>/cl_games/telemetry.jsonl`
+    pub path: Option<PathBuf>,
+}
+
+#[cfg(feature = "snake")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SnakeConfig {
+    /// Cells per second
+    pub speed: f32,
+}
+
+#[cfg(feature = "snake")]
+impl Default for SnakeConfig {
+    fn default() -> Self {
+        Self {
+            speed: crate::snake::snakes::SPEED,
+        }
+    }
+}
+
+#[cfg(feature = "tetris")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TetrisConfig {
+    pub descend_slow_ms: u64,
+    pub descend_fast_ms: u64,
+}
+
+#[cfg(feature = "tetris")]
+impl TetrisConfig {
+    pub fn descend_slow(&self) -> Duration {
+        Duration::from_millis(self.descend_slow_ms)
+    }
+
+    pub fn descend_fast(&self) -> Duration {
+        Duration::from_millis(self.descend_fast_ms)
+    }
+}
+
+#[cfg(feature = "tetris")]
+impl Default for TetrisConfig {
+    fn default() -> Self {
+        Self {
+            descend_slow_ms: crate::tetris::TO_DESCEND_SLOW.as_millis() as u64,
+            descend_fast_ms: crate::tetris::TO_DESCEND_FAST.as_millis() as u64,
+        }
+    }
+}
+
+#[cfg(feature = "invaders")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpaceInvadersConfig {
+    pub player_speed: f32,
+    pub fire_rate_ms: u64,
+    /// Firing heats the cannon up, an overheated cannon can't fire for a while
+    pub overheat: bool,
+    /// Heat of a shot, the cannon overheats at 1
+    pub heat_per_shot: f32,
+}
+
+#[cfg(feature = "invaders")]
+impl SpaceInvadersConfig {
+    pub fn fire_rate(&self) -> Duration {
+        Duration::from_millis(self.fire_rate_ms)
+    }
+}
+
+#[cfg(feature = "invaders")]
+impl Default for SpaceInvadersConfig {
+    fn default() -> Self {
+        Self {
+            player_speed: crate::space_invaders::PLAYER_SPEED,
+            fire_rate_ms: crate::space_invaders::PLAYER_FIRE_RATE.as_millis() as u64,
+            overheat: false,
+            heat_per_shot: crate::space_invaders::HEAT_PER_SHOT,
+        }
+    }
+}
+
+#[cfg(feature = "pong")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PongConfig {
+    pub player_speed: f32,
+    pub enemy_speed: f32,
+}
+
+#[cfg(feature = "pong")]
+impl Default for PongConfig {
+    fn default() -> Self {
+        Self {
+            player_speed: crate::pong::planks::PLAYER_SPEED,
+            enemy_speed: crate::pong::planks::ENEMY_SPEED,
+        }
+    }
+}
+
+/// Key name as written in the config: `esc`, `space`, `left`, `f1`, `a`...
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let name = name.to_lowercase();
+    let code = match name.as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        _ => {
+            if let Some(num) = name.strip_prefix('f').and_then(|num| num.parse().ok()) {
+                KeyCode::F(num)
+            } else {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        }
+    };
+
+    Some(code)
+}
+
+/// Name of the key that `parse_key` reads back. None for keys without a name.
+pub fn key_name(code: KeyCode) -> Option<String> {
+    let name = match code {
+        KeyCode::Esc => "esc".to_owned(),
+        KeyCode::Enter => "enter".to_owned(),
+        KeyCode::Char(' ') => "space".to_owned(),
+        KeyCode::Tab => "tab".to_owned(),
+        KeyCode::Backspace => "backspace".to_owned(),
+        KeyCode::Up => "up".to_owned(),
+        KeyCode::Down => "down".to_owned(),
+        KeyCode::Left => "left".to_owned(),
+        KeyCode::Right => "right".to_owned(),
+        KeyCode::F(num) => format!("f{}", num),
+        KeyCode::Char(c) => c.to_string(),
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+fn deserialize_key<'de, D>(deserializer: D) -> Result<Option<KeyCode>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    parse_key(&name)
+        .map(Some)
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown key name `{}`", name)))
+}
+
+fn deserialize_keys<'de, D>(deserializer: D) -> Result<Vec<KeyCode>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|name| {
+            parse_key(name)
+                .ok_or_else(|| serde::de::Error::custom(format!("unknown key name `{}`", name)))
+        })
+        .collect()
+}
+
+fn serialize_key<S>(code: &Option<KeyCode>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match code.and_then(key_name) {
+        Some(name) => serializer.serialize_some(&name),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn serialize_keys<S>(codes: &[KeyCode], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(codes.iter().filter_map(|&code| key_name(code)))
+}
+
+/// Config file: `$CL_GAMES_CONFIG` or `<config dir>/cl_games/config.toml`.
+pub fn config_path() -> Option<PathBuf> {
+    std::env::var_os(CONFIG_PATH_VAR)
+        .map(PathBuf::from)
+        .or_else(|| dirs::config_dir().map(|dir| dir.join("cl_games").join("config.toml")))
+}
+
+/// Directory for saved data: `<data dir>/cl_games`.
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("cl_games"))
+}
+
+fn modified_time() -> Option<SystemTime> {
+    config_path()?.metadata().ok()?.modified().ok()
+}
+
+/// Entry of the config file that can't be used, its default is used instead.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    /// Path of the entry, like `keys.left`
+    pub key: String,
+    pub message: String,
+    /// Value used instead as written in the config, None for unknown entries
+    /// and entries without a default value
+    pub default: Option<String>,
+    pub is_unknown: bool,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.is_unknown, &self.default) {
+            (true, _) => write!(f, "`{}`: unknown setting, ignored", self.key),
+            (false, Some(default)) => {
+                write!(f, "`{}`: {}, using {}", self.key, self.message, default)
+            }
+            (false, None) => write!(f, "`{}`: {}, using the default", self.key, self.message),
+        }
+    }
+}
+
+/// Read the config from TOML text. Entries that can't be used are reported
+/// and replaced with their defaults, only broken TOML is an error.
+pub fn parse(text: &str) -> Result<(Config, Vec<Problem>), String> {
+    let mut table: toml::Table = toml::from_str(text).map_err(|err| err.to_string())?;
+    let defaults = toml::Table::try_from(Config::default()).expect("defaults are serializable");
+    let mut problems = vec![];
+
+    if toml::Value::Table(table.clone())
+        .try_into::<Config>()
+        .is_err()
+    {
+        remove_bad_entries(&mut table, &[], &defaults, &mut problems);
+    }
+    let mut config: Config = toml::Value::Table(table)
+        .try_into()
+        .map_err(|err: toml::de::Error| err.message().to_owned())?;
+    check_values(&mut config, &mut problems);
+
+    Ok((config, problems))
+}
+
+/// Config with only the entry at `path` set to `value`.
+fn try_entry(path: &[&str], value: toml::Value) -> Result<Config, toml::de::Error> {
+    let nested = path.iter().rev().fold(value, |value, key| {
+        toml::Value::Table(toml::Table::from_iter([(key.to_string(), value)]))
+    });
+    nested.try_into()
+}
+
+fn find_default<'a>(defaults: &'a toml::Table, path: &[&str]) -> Option<&'a toml::Value> {
+    let (last, sections) = path.split_last()?;
+    let mut table = defaults;
+    for key in sections {
+        table = table.get(*key)?.as_table()?;
+    }
+    table.get(*last)
+}
+
+/// Remove the entries of `table` (at `path` in the config) that don't deserialize alone.
+/// Sections are checked entry by entry.
+fn remove_bad_entries(
+    table: &mut toml::Table,
+    path: &[&str],
+    defaults: &toml::Table,
+    problems: &mut Vec<Problem>,
+) {
+    let keys: Vec<String> = table.keys().cloned().collect();
+    for key in keys {
+        let mut entry_path = path.to_vec();
+        entry_path.push(&key);
+
+        let value = &mut table[&key];
+        let error = match value {
+            // Known sections deserialize when empty
+            toml::Value::Table(section) => {
+                match try_entry(&entry_path, toml::Value::Table(Default::default())) {
+                    Ok(_) => {
+                        remove_bad_entries(section, &entry_path, defaults, problems);
+                        continue;
+                    }
+                    Err(err) => err,
+                }
+            }
+            value => match try_entry(&entry_path, value.clone()) {
+                Ok(_) => continue,
+                Err(err) => err,
+            },
+        };
+
+        problems.push(Problem {
+            key: entry_path.join("."),
+            message: error.message().to_owned(),
+            default: find_default(defaults, &entry_path).map(ToString::to_string),
+            is_unknown: error.message().starts_with("unknown field"),
+        });
+        table.remove(&key);
+    }
+}
+
+/// Replace values out of their ranges with the defaults.
+fn check_values(config: &mut Config, problems: &mut Vec<Problem>) {
+    fn check<T: Copy + std::fmt::Display>(
+        problems: &mut Vec<Problem>,
+        key: &str,
+        value: &mut T,
+        default: T,
+        is_valid: bool,
+        requirement: &str,
+    ) {
+        if !is_valid {
+            problems.push(Problem {
+                key: key.to_owned(),
+                message: format!("{} {}", value, requirement),
+                default: Some(default.to_string()),
+                is_unknown: false,
+            });
+            *value = default;
+        }
+    }
+    let defaults = Config::default();
+
+    let frame_rate = config.frame_rate;
+    check(
+        problems,
+        "frame_rate",
+        &mut config.frame_rate,
+        defaults.frame_rate,
+        (1..=MAX_FRAME_RATE).contains(&frame_rate),
+        &format!("is not from 1 to {}", MAX_FRAME_RATE),
+    );
+    let cell_aspect = config.cell_aspect;
+    check(
+        problems,
+        "cell_aspect",
+        &mut config.cell_aspect,
+        defaults.cell_aspect,
+        (MIN_CELL_ASPECT..=MAX_CELL_ASPECT).contains(&cell_aspect),
+        &format!("is not from {} to {}", MIN_CELL_ASPECT, MAX_CELL_ASPECT),
+    );
+    let min = config.difficulty.min;
+    check(
+        problems,
+        "difficulty.min",
+        &mut config.difficulty.min,
+        defaults.difficulty.min,
+        min > 0.0,
+        "is not above 0",
+    );
+    let (min, max) = (config.difficulty.min, config.difficulty.max);
+    check(
+        problems,
+        "difficulty.max",
+        &mut config.difficulty.max,
+        defaults.difficulty.max.max(min),
+        max >= min,
+        "is below `difficulty.min`",
+    );
+    #[cfg(feature = "snake")]
+    {
+        let speed = config.snake.speed;
+        check(
+            problems,
+            "snake.speed",
+            &mut config.snake.speed,
+            defaults.snake.speed,
+            speed > 0.0,
+            "is not above 0",
+        );
+    }
+    #[cfg(feature = "tetris")]
+    {
+        let tetris = &mut config.tetris;
+        let (slow, fast) = (tetris.descend_slow_ms, tetris.descend_fast_ms);
+        check(
+            problems,
+            "tetris.descend_slow_ms",
+            &mut tetris.descend_slow_ms,
+            defaults.tetris.descend_slow_ms,
+            slow > 0,
+            "is not above 0",
+        );
+        check(
+            problems,
+            "tetris.descend_fast_ms",
+            &mut tetris.descend_fast_ms,
+            defaults.tetris.descend_fast_ms,
+            fast > 0,
+            "is not above 0",
+        );
+    }
+    #[cfg(feature = "invaders")]
+    {
+        let invaders = &mut config.space_invaders;
+        let (speed, heat) = (invaders.player_speed, invaders.heat_per_shot);
+        check(
+            problems,
+            "space_invaders.player_speed",
+            &mut invaders.player_speed,
+            defaults.space_invaders.player_speed,
+            speed > 0.0,
+            "is not above 0",
+        );
+        check(
+            problems,
+            "space_invaders.heat_per_shot",
+            &mut invaders.heat_per_shot,
+            defaults.space_invaders.heat_per_shot,
+            heat > 0.0,
+            "is not above 0",
+        );
+    }
+    #[cfg(feature = "pong")]
+    {
+        let pong = &mut config.pong;
+        let (player, enemy) = (pong.player_speed, pong.enemy_speed);
+        check(
+            problems,
+            "pong.player_speed",
+            &mut pong.player_speed,
+            defaults.pong.player_speed,
+            player > 0.0,
+            "is not above 0",
+        );
+        check(
+            problems,
+            "pong.enemy_speed",
+            &mut pong.enemy_speed,
+            defaults.pong.enemy_speed,
+            enemy > 0.0,
+            "is not above 0",
+        );
+    }
+}
+
+/// Lines listing the problems of the config file.
+pub fn report(problems: &[Problem]) -> String {
+    let path = config_path().map_or_else(String::new, |path| path.display().to_string());
+    let mut report = match problems.len() {
+        1 => format!("1 problem in {}:", path),
+        count => format!("{} problems in {}:", count, path),
+    };
+    for problem in problems {
+        report += &format!("\n  {}", problem);
+    }
+    report
+}
+
+/// Read the config file. Must be called once before any game is created.
+/// Missing file means defaults; broken file keeps the current settings
+/// (defaults on the first read) and gives an error message.
+/// Entries that can't be used get their defaults and are listed in the error message.
+pub fn load() -> Result<(), String> {
+    *MODIFIED.lock().expect("not poisoned") = modified_time();
+
+    let (config, problems) = match config_path().map(std::fs::read_to_string) {
+        None => (Config::default(), vec![]),
+        Some(Err(err)) if err.kind() == std::io::ErrorKind::NotFound => (Config::default(), vec![]),
+        Some(Err(err)) => return Err(err.to_string()),
+        Some(Ok(text)) => parse(&text)?,
+    };
+    *CONFIG.write().expect("not poisoned") = Arc::new(config);
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(report(&problems))
+    }
+}
+
+/// Write the entries to the config file, replacing it, and read it.
+pub fn save(entries: &toml::Table) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+
+    let path =
+        config_path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no config directory"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let text = toml::to_string(entries).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+    std::fs::write(path, text)?;
+    load().map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Read the config file again if it was changed since the last read.
+/// Returns None if nothing changed.
+pub fn reload_if_changed() -> Option<Result<(), String>> {
+    if *MODIFIED.lock().expect("not poisoned") == modified_time() {
+        return None;
+    }
+    Some(load())
+}
+
+/// Current settings.
+pub fn get() -> Arc<Config> {
+    CONFIG.read().expect("not poisoned").clone()
+}
+
+/// Run `config` subcommand with the arguments following it.
+/// Problems found by `check` are an error too.
+pub fn run_command(args: &[String]) -> Result<(), String> {
+    match args {
+        [command] if command == "check" => {
+            let Some(path) = config_path() else {
+                return Err("no config directory".to_owned());
+            };
+            let text = match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    println!("{} doesn't exist, defaults are used", path.display());
+                    return Ok(());
+                }
+                Err(err) => return Err(format!("{}: {}", path.display(), err)),
+            };
+            let (_, problems) =
+                parse(&text).map_err(|err| format!("{}: {}", path.display(), err))?;
+            if problems.is_empty() {
+                println!("{}: no problems found", path.display());
+                Ok(())
+            } else {
+                Err(report(&problems))
+            }
+        }
+        _ => Err(format!("usage: cl_games {} check", COMMAND)),
+    }
+}
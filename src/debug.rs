@@ -0,0 +1,107 @@
+//! Frame time, FPS, entity counts and the time of each part of a frame
+//! over the game, toggled with F3, to see where the time goes on big boards.
+use crate::render::{FrameBuffer, Layer};
+use crossterm::event::KeyCode;
+use std::time::{Duration, Instant};
+
+/// Key showing and hiding the overlay.
+pub const TOGGLE_BUTTON: KeyCode = KeyCode::F(3);
+/// Weight of the newest frame in the averages, so the numbers don't jitter.
+const SMOOTHING: f32 = 0.1;
+
+/// Parts of a frame of the game loop, in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading the keys
+    Input,
+    /// Updating the game, effects and saves
+    Update,
+    /// Drawing the game and the overlays into the frame
+    Draw,
+    /// Handing the frame to the render thread
+    Output,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 4] = [Phase::Input, Phase::Update, Phase::Draw, Phase::Output];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Input => "input",
+            Phase::Update => "update",
+            Phase::Draw => "draw",
+            Phase::Output => "output",
+        }
+    }
+}
+
+/// Running averages of the frame time and of each phase, in seconds.
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    frame: Option<f32>,
+    phases: [Option<f32>; Phase::ALL.len()],
+}
+
+fn smoothed(average: &mut Option<f32>, time: Duration) {
+    let time = time.as_secs_f32();
+    *average = Some(match *average {
+        Some(average) => average + (time - average) * SMOOTHING,
+        None => time,
+    });
+}
+
+fn millis(seconds: Option<f32>) -> String {
+    format!("{:.2} ms", seconds.unwrap_or(0.0) * 1000.0)
+}
+
+impl Timings {
+    /// Time between the starts of two frames.
+    pub fn frame(&mut self, delta: Duration) {
+        smoothed(&mut self.frame, delta);
+    }
+
+    pub fn phase(&mut self, phase: Phase, time: Duration) {
+        smoothed(&mut self.phases[phase as usize], time);
+    }
+
+    /// Count the time since `start` for the phase.
+    /// Returns the end of the phase, the start of the next one.
+    pub fn lap(&mut self, phase: Phase, start: Instant) -> Instant {
+        let now = Instant::now();
+        self.phase(phase, now - start);
+        now
+    }
+
+    /// Frames per second, 0 before the first frame.
+    pub fn fps(&self) -> f32 {
+        match self.frame {
+            Some(frame) if frame > 0.0 => 1.0 / frame,
+            _ => 0.0,
+        }
+    }
+
+    /// Lines in the top left corner: timings, then the entities, then the notes.
+    pub fn draw(&self, frame: &mut FrameBuffer, entities: &[(&str, usize)], notes: &[String]) {
+        let mut lines = vec![
+            format!("Frame {}, {:.0} fps", millis(self.frame), self.fps()),
+            Phase::ALL
+                .iter()
+                .map(|&phase| format!("{} {}", phase.name(), millis(self.phases[phase as usize])))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ];
+        if !entities.is_empty() {
+            lines.push(
+                entities
+                    .iter()
+                    .map(|(name, count)| format!("{} {}", name, count))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+        lines.extend(notes.iter().cloned());
+        for (y, line) in lines.iter().enumerate() {
+            frame.print(Layer::Overlay, 0, y as u16, line);
+        }
+    }
+}
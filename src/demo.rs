@@ -0,0 +1,93 @@
+//! Attract mode: when the main menu sits idle, a random game plays itself
+//! with the random player of the headless mode, arcade cabinet style.
+//! Nothing of the demo is recorded: no scores, statistics or heatmaps.
+use crate::game::UpdateEvent;
+use crate::headless::RandomPlayer;
+use crate::registry::Registry;
+use crate::render::{FrameBuffer, Layer};
+use crossterm::event::Event;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Blink period of the hint at the top.
+const HINT_BLINK_PERIOD: Duration = Duration::from_secs(2);
+
+/// Idle time of the menu before the demo starts, None if it is off.
+pub fn idle_time() -> Option<Duration> {
+    match crate::config::get().demo_after {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// Play random games one after another until a key is pressed or the mouse clicked.
+/// The key only stops the demo.
+pub fn play(
+    out: &mut std::io::Stdout,
+    rx: &Receiver<Event>,
+    registry: &Registry,
+) -> crossterm::Result<()> {
+    use crossterm::style::Stylize;
+    use crossterm::terminal;
+
+    let mut rng = StdRng::from_entropy();
+    let mut frame = FrameBuffer::from_terminal()?;
+    let mut hud = crate::hud::Hud::new();
+    let started = Instant::now();
+
+    'demo: loop {
+        let Some(entry) = registry.get(rng.gen_range(0..registry.games().len().max(1))) else {
+            return Ok(());
+        };
+        let (width, height) = crate::util::screen_size();
+        let mut game = (entry.create)(
+            StdRng::from_rng(&mut rng).expect("StdRng seeding does not fail"),
+            crate::hud::play_area(width, height),
+        );
+        let mut player =
+            RandomPlayer::new(StdRng::from_rng(&mut rng).expect("StdRng seeding does not fail"));
+        let hint = crate::i18n::format(crate::i18n::Text::DemoHint, &[&entry.name]);
+        let mut prev_time = Instant::now();
+        let mut delta_filter = crate::pacing::DeltaFilter::default();
+
+        loop {
+            let delta = delta_filter.filter(prev_time.elapsed());
+            prev_time = Instant::now();
+            let input = player.next_key().map(crate::headless::key_input);
+            if let UpdateEvent::GameOver =
+                game.update(&crate::input::FrameInput::new(input), &delta)
+            {
+                continue 'demo;
+            }
+
+            let (width, height) = terminal::size()?;
+            frame.clear(width, height);
+            game.draw(&mut frame, &delta);
+            game.hud(hud.clear());
+            hud.draw(&mut frame);
+            let x = width.saturating_sub(hint.chars().count() as u16) / 2;
+            if crate::anim::oscillate(started.elapsed(), HINT_BLINK_PERIOD) >= 0.0 {
+                frame.print_styled(Layer::Overlay, x, 0, hint.as_str().reverse());
+            } else {
+                frame.print(Layer::Overlay, x, 0, &hint);
+            }
+            frame.flush(out)?;
+
+            // Waiting for the next frame is waiting for a key
+            let frame_time = crate::config::get()
+                .between_frames()
+                .saturating_sub(prev_time.elapsed());
+            match rx.recv_timeout(frame_time) {
+                Ok(Event::Key(_)) | Err(RecvTimeoutError::Disconnected) => return Ok(()),
+                Ok(Event::Mouse(mouse))
+                    if matches!(mouse.kind, crossterm::event::MouseEventKind::Down(_)) =>
+                {
+                    return Ok(())
+                }
+                _ if crate::term::is_quit_requested() => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
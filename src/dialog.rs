@@ -0,0 +1,136 @@
+use crate::i18n::{tr, Text};
+use crate::input::Action;
+use crate::layout::Rect;
+use crate::render::{FrameBuffer, Layer};
+use crate::ui::{CenteredText, Frame, Label, Lines};
+
+/// Width of the text field of `ask_text` in symbols, at least.
+const INPUT_WIDTH: u16 = 30;
+
+/// Draw a yes/no question in a box in the middle of the frame, over everything.
+/// Games draw it over the run while waiting for the answer.
+pub fn draw_question(frame: &mut FrameBuffer, question: &str) {
+    use crossterm::style::{Attribute, ContentStyle};
+
+    // Hint under the question
+    let mut bold = ContentStyle::new();
+    bold.attributes.set(Attribute::Bold);
+    let text = CenteredText::new()
+        .line(Label::styled(question, bold))
+        .line(Label::new(tr(Text::ConfirmHint)));
+    let (text_width, text_height) = text.size();
+    let area = Rect::of_frame(frame).centered(text_width + 4, text_height + 2);
+    // Whatever is under the box doesn't show through
+    for y in area.y..area.bottom() {
+        frame.print(Layer::Overlay, area.x, y, &" ".repeat(area.width as usize));
+    }
+    let inner = Frame::new()
+        .lines(Lines::Single)
+        .draw(frame, Layer::Overlay, area);
+    text.draw(frame, Layer::Overlay, inner);
+}
+
+/// Answer of the key to a yes/no question: `y` or Enter answer yes,
+/// `n` or Esc answer no, None for other keys.
+pub fn answer(key: &crossterm::event::KeyEvent) -> Option<bool> {
+    use crossterm::event::{KeyCode, KeyEventKind};
+
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+    if crate::config::get().keys.action(key) == Some(Action::Quit) {
+        return Some(false);
+    }
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => Some(true),
+        KeyCode::Char('n') => Some(false),
+        _ => None,
+    }
+}
+
+/// Ask a yes/no question in a box in the middle of the screen, see `answer`.
+pub fn confirm(
+    out: &mut std::io::Stdout,
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+    question: &str,
+) -> crossterm::Result<bool> {
+    use crossterm::{execute, terminal};
+
+    let mut frame = FrameBuffer::from_terminal()?;
+
+    let answer = loop {
+        let (width, height) = terminal::size()?;
+        frame.clear(width, height);
+        draw_question(&mut frame, question);
+        frame.flush(out)?;
+
+        if let Some(answer) = answer(&crate::input::recv_key(rx)) {
+            break answer;
+        }
+    };
+    execute!(
+        out,
+        terminal::Clear(terminal::ClearType::All),
+        crossterm::cursor::MoveTo(0, 0)
+    )?;
+
+    Ok(answer)
+}
+
+/// Ask for a line of text in a box in the middle of the screen, starting with `initial`.
+/// Enter answers with the text, Esc answers None.
+pub fn ask_text(
+    out: &mut std::io::Stdout,
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+    question: &str,
+    initial: &str,
+) -> crossterm::Result<Option<String>> {
+    use crossterm::event::KeyCode;
+    use crossterm::style::Stylize;
+    use crossterm::{execute, terminal};
+
+    let hint = tr(Text::TextHint);
+    let mut input = crate::text_input::TextInput::new(initial);
+    let mut frame = FrameBuffer::from_terminal()?;
+
+    let answer = loop {
+        let (width, height) = terminal::size()?;
+        frame.clear(width, height);
+        let text_width =
+            (question.chars().count().max(hint.chars().count()) as u16).max(INPUT_WIDTH);
+        let area = Rect::of_frame(&frame).centered(text_width + 4, 5);
+        let inner = Frame::new()
+            .lines(Lines::Single)
+            .draw(&mut frame, Layer::Overlay, area)
+            .shrink(1, 0, 1, 0);
+        frame.print_styled(Layer::Overlay, inner.x, inner.y, question.bold());
+        input.draw(
+            &mut frame,
+            Layer::Overlay,
+            (inner.x, inner.y + 1),
+            inner.width,
+        );
+        frame.print(Layer::Overlay, inner.x, inner.y + 2, hint);
+        frame.flush(out)?;
+
+        // Keys bound to actions are typed as text here
+        let key = crate::input::recv_key(rx);
+        if crate::term::is_quit_requested() {
+            break None;
+        }
+        match key.code {
+            KeyCode::Enter => break Some(input.text()),
+            KeyCode::Esc => break None,
+            _ => {
+                input.handle_key(&key);
+            }
+        }
+    };
+    execute!(
+        out,
+        terminal::Clear(terminal::ClearType::All),
+        crossterm::cursor::MoveTo(0, 0)
+    )?;
+
+    Ok(answer)
+}
@@ -0,0 +1,96 @@
+//! Dynamic difficulty, turned on with `dynamic = true` in the `[difficulty]`
+//! section of the config.
+//!
+//! The level starts at 1 and is nudged every few seconds: up while the player
+//! does well, down while they struggle, within the configured bounds.
+//! Games with rallies (pong) are judged by the rally length, the others by
+//! deaths per minute over the session. The level multiplies the invaders
+//! fire chance, the pong AI speed and the snake speed.
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Time between nudges.
+const NUDGE_PERIOD: Duration = Duration::from_secs(5);
+/// Level change of a nudge.
+const STEP: f32 = 0.05;
+/// Fewer deaths make the game harder.
+const TARGET_DEATHS_PER_MINUTE: f32 = 1.0;
+/// Longer rallies make the game harder.
+const TARGET_RALLY_LENGTH: f32 = 4.0;
+
+static LEVEL: Mutex<f32> = Mutex::new(1.0);
+
+/// Balance multiplier of the game being updated, 1 unless set with `with_level`.
+pub fn level() -> f32 {
+    *LEVEL.lock().expect("difficulty lock poisoned")
+}
+
+/// Run `f` with the given level. The previous level is restored afterwards.
+pub fn with_level<T>(level: f32, f: impl FnOnce() -> T) -> T {
+    let prev = std::mem::replace(&mut *LEVEL.lock().expect("difficulty lock poisoned"), level);
+    let result = f();
+    *LEVEL.lock().expect("difficulty lock poisoned") = prev;
+    result
+}
+
+/// Level of one game over the session.
+#[derive(Debug)]
+pub struct Tracker {
+    level: f32,
+    /// Change of the last nudge, shown in the debug overlay
+    last_step: f32,
+    since_nudge: Duration,
+    played: Duration,
+    deaths: u32,
+}
+
+impl Default for Tracker {
+    fn default() -> Self {
+        Self {
+            level: 1.0,
+            last_step: 0.0,
+            since_nudge: Duration::ZERO,
+            played: Duration::ZERO,
+            deaths: 0,
+        }
+    }
+}
+
+impl Tracker {
+    pub fn level(&self) -> f32 {
+        let config = &crate::config::get().difficulty;
+        self.level.clamp(config.min, config.max.max(config.min))
+    }
+
+    /// Count played time and nudge the level when it is time.
+    /// `rally_length` comes from `Game::rally_length`.
+    pub fn advance(&mut self, delta: Duration, rally_length: Option<f32>) {
+        self.played += delta;
+        self.since_nudge += delta;
+        if self.since_nudge < NUDGE_PERIOD {
+            return;
+        }
+        self.since_nudge = Duration::ZERO;
+
+        let is_too_easy = match rally_length {
+            Some(length) => length > TARGET_RALLY_LENGTH,
+            None => self.deaths_per_minute() < TARGET_DEATHS_PER_MINUTE,
+        };
+        let prev = self.level();
+        self.level = prev + if is_too_easy { STEP } else { -STEP };
+        self.last_step = self.level() - prev;
+    }
+
+    pub fn add_death(&mut self) {
+        self.deaths += 1;
+    }
+
+    fn deaths_per_minute(&self) -> f32 {
+        self.deaths as f32 / (self.played.as_secs_f32() / 60.0).max(1.0)
+    }
+
+    /// Line for the debug overlay.
+    pub fn describe(&self) -> String {
+        format!("Difficulty: {:.2} ({:+.2})", self.level(), self.last_step)
+    }
+}
@@ -0,0 +1,179 @@
+//! Short-lived visuals drawn over the game: floating score numbers
+//! and the confetti of a new personal best.
+use crate::anim::{Ease, Tween};
+use crate::point::{GameBasis, Point, ScreenBasis};
+use crate::render::{FrameBuffer, Layer};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long a score popup floats.
+pub const POPUP_TIME: Duration = Duration::from_secs(1);
+/// How many rows a popup rises.
+pub const POPUP_RISE: f32 = 3.0;
+/// Popup color at the start and at the end, it fades out on the way.
+const POPUP_COLOR_FROM: (u8, u8, u8) = (255, 255, 0);
+const POPUP_COLOR_TO: (u8, u8, u8) = (60, 60, 60);
+/// How long the confetti falls.
+pub const CONFETTI_TIME: Duration = Duration::from_millis(2500);
+/// Confetti pieces per screen column.
+const CONFETTI_DENSITY: f32 = 0.75;
+/// Symbols of the pieces, the same with any glyph set.
+const CONFETTI_SYMBOLS: [char; 5] = ['*', '+', 'o', '~', '.'];
+/// Sideways drift of the pieces in columns per second, either way.
+const CONFETTI_DRIFT: f32 = 4.0;
+
+/// Points earned at a screen position.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ScoreEvent {
+    pub points: i64,
+    pub x: u16,
+    pub y: u16,
+}
+
+impl ScoreEvent {
+    /// Event at a game world position.
+    pub fn at(points: i64, position: Point<GameBasis>) -> Self {
+        // Positions off the top or the left pop up at the edge
+        let position = Point::<ScreenBasis>::from(position);
+        let cell = Point::<ScreenBasis>::new(position.x.max(0.0), position.y.max(0.0))
+            .to_cell()
+            .unwrap_or_default();
+        Self {
+            points,
+            x: cell.x,
+            y: cell.y,
+        }
+    }
+}
+
+struct Popup {
+    text: String,
+    x: u16,
+    y: u16,
+    tween: Tween,
+}
+
+#[derive(Default)]
+pub struct Effects {
+    popups: Vec<Popup>,
+}
+
+impl Effects {
+    /// Show a floating `+N` (or `-N`) at the event position.
+    pub fn spawn(&mut self, event: ScoreEvent) {
+        let text = format!("{:+}", event.points);
+        self.popups.push(Popup {
+            // centered over the event
+            x: event.x.saturating_sub(text.len() as u16 / 2),
+            y: event.y,
+            text,
+            tween: Tween::new(POPUP_TIME, Ease::OutQuad),
+        });
+    }
+
+    pub fn advance(&mut self, delta_time: Duration) {
+        for popup in self.popups.iter_mut() {
+            popup.tween.advance(delta_time);
+        }
+        self.popups.retain(|popup| !popup.tween.is_finished());
+    }
+
+    pub fn draw(&self, frame: &mut FrameBuffer) {
+        use crossterm::style::{Color, Stylize};
+
+        for popup in self.popups.iter() {
+            let rise = popup.tween.value(0.0, POPUP_RISE).round() as u16;
+            let channel = |from: u8, to: u8| popup.tween.value(from as f32, to as f32) as u8;
+            let color = Color::Rgb {
+                r: channel(POPUP_COLOR_FROM.0, POPUP_COLOR_TO.0),
+                g: channel(POPUP_COLOR_FROM.1, POPUP_COLOR_TO.1),
+                b: channel(POPUP_COLOR_FROM.2, POPUP_COLOR_TO.2),
+            };
+
+            frame.print_styled(
+                Layer::Hud,
+                popup.x,
+                popup.y.saturating_sub(rise),
+                popup.text.as_str().with(color).bold(),
+            );
+        }
+    }
+}
+
+struct Piece {
+    x: f32,
+    y: f32,
+    /// Columns and rows per second
+    speed: (f32, f32),
+    symbol: char,
+    color: crossterm::style::Color,
+}
+
+/// Pieces raining down the whole screen.
+pub struct Confetti {
+    pieces: Vec<Piece>,
+    tween: Tween,
+}
+
+impl Confetti {
+    /// Pieces above a screen of the given size, falling in during `CONFETTI_TIME`.
+    pub fn new(width: u16, height: u16, rng: &mut impl Rng) -> Self {
+        let colors = crate::theme::palette().figures;
+        let (width, height) = (width as f32, height as f32);
+        // The slowest piece falls the screen height during the effect
+        let base_speed = height / CONFETTI_TIME.as_secs_f32();
+        let pieces = (0..(width * CONFETTI_DENSITY) as usize)
+            .map(|_| Piece {
+                x: rng.gen_range(0.0..width),
+                y: rng.gen_range(-height..0.0),
+                speed: (
+                    rng.gen_range(-CONFETTI_DRIFT..CONFETTI_DRIFT),
+                    base_speed * rng.gen_range(1.0..2.0),
+                ),
+                symbol: CONFETTI_SYMBOLS[rng.gen_range(0..CONFETTI_SYMBOLS.len())],
+                color: colors[rng.gen_range(0..colors.len())],
+            })
+            .collect();
+        Self {
+            pieces,
+            tween: Tween::new(CONFETTI_TIME, Ease::Linear),
+        }
+    }
+
+    pub fn advance(&mut self, delta_time: Duration) {
+        let secs = delta_time.as_secs_f32();
+        for piece in self.pieces.iter_mut() {
+            piece.x += piece.speed.0 * secs;
+            piece.y += piece.speed.1 * secs;
+        }
+        self.tween.advance(delta_time);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.tween.is_finished()
+    }
+
+    /// Draw under the HUD, so texts stay readable.
+    pub fn draw(&self, frame: &mut FrameBuffer) {
+        use crate::render::Cell;
+        use crossterm::style::ContentStyle;
+
+        for piece in self.pieces.iter() {
+            let Some(cell) = Point::<ScreenBasis>::new(piece.x, piece.y).to_cell() else {
+                continue;
+            };
+            let mut style = ContentStyle::new();
+            style.foreground_color = Some(piece.color);
+            frame.set(
+                Layer::Game,
+                cell.x,
+                cell.y,
+                Cell {
+                    symbol: piece.symbol,
+                    style,
+                },
+            );
+        }
+    }
+}
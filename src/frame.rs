@@ -0,0 +1,132 @@
+//! Boxes around screen regions, drawn with the frame symbols of the current
+//! glyph set, so they fall back to ASCII with it.
+use crate::layout::Rect;
+use crate::render::{Cell, FrameBuffer, Layer};
+use crossterm::style::{Attribute, ContentStyle, StyledContent};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Lines {
+    #[default]
+    Double,
+    Single,
+}
+
+/// Box with an optional title in its top border.
+#[derive(Clone, Debug, Default)]
+pub struct Frame<'a> {
+    title: Option<&'a str>,
+    lines: Lines,
+    style: ContentStyle,
+    is_padded: bool,
+    is_open_top: bool,
+}
+
+impl<'a> Frame<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn lines(mut self, lines: Lines) -> Self {
+        self.lines = lines;
+        self
+    }
+
+    /// Style of the lines and the title.
+    pub fn style(mut self, style: ContentStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sides two symbols wide with the lines next to the inside,
+    /// so the box fits cells two symbols wide.
+    pub fn padded(mut self) -> Self {
+        self.is_padded = true;
+        self
+    }
+
+    /// No top border, the sides go up to the top.
+    pub fn open_top(mut self) -> Self {
+        self.is_open_top = true;
+        self
+    }
+
+    fn side_width(&self) -> u16 {
+        if self.is_padded {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Inside of the box drawn in `area`.
+    pub fn inner(&self, area: Rect) -> Rect {
+        let side = self.side_width();
+        area.shrink(side, !self.is_open_top as u16, side, 1)
+    }
+
+    /// Draw the box in `area` and return its inside.
+    /// Nothing is drawn if the box doesn't fit.
+    pub fn draw(&self, frame: &mut FrameBuffer, layer: Layer, area: Rect) -> Rect {
+        let inner = self.inner(area);
+        let side = self.side_width();
+        if area.width < side * 2 || area.height < 2 - self.is_open_top as u16 {
+            return inner;
+        }
+
+        let glyphs = crate::glyphs::get();
+        let [vertical, horizontal, top_left, top_right, bottom_right, bottom_left] =
+            match self.lines {
+                Lines::Double => glyphs.border,
+                Lines::Single => glyphs.thin_border,
+            };
+        let mut put = |x: u16, y: u16, symbol: char| {
+            frame.set(
+                layer,
+                x,
+                y,
+                Cell {
+                    symbol,
+                    style: self.style,
+                },
+            )
+        };
+        // Columns of the lines
+        let (left, right) = (area.x + side - 1, area.right() - side);
+        let bottom = area.bottom() - 1;
+
+        for y in area.y..=bottom {
+            if self.is_padded {
+                put(area.x, y, ' ');
+                put(area.right() - 1, y, ' ');
+            }
+            let (left_symbol, middle, right_symbol) = match y {
+                y if y == area.y && !self.is_open_top => (top_left, Some(horizontal), top_right),
+                y if y == bottom => (bottom_left, Some(horizontal), bottom_right),
+                _ => (vertical, None, vertical),
+            };
+            put(left, y, left_symbol);
+            if let Some(middle) = middle {
+                for x in left + 1..right {
+                    put(x, y, middle);
+                }
+            }
+            put(right, y, right_symbol);
+        }
+
+        if let Some(title) = self.title.filter(|_| !self.is_open_top) {
+            // A line on both sides of the title
+            let room = (right - left).saturating_sub(3) as usize;
+            let title: String = format!(" {} ", title).chars().take(room).collect();
+            let mut style = self.style;
+            style.attributes.set(Attribute::Bold);
+            frame.print_styled(layer, left + 2, area.y, StyledContent::new(style, title));
+        }
+
+        inner
+    }
+}
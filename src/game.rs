@@ -1,3 +1,6 @@
+use crate::render::{FrameBuffer, Layer};
+
+#[non_exhaustive]
 pub enum UpdateEvent {
     GameOver,
     GameContinue,
@@ -8,23 +11,142 @@ pub struct Score {
     pub value: i64,
 }
 pub const EXIT_BUTTON: crossterm::event::KeyCode = crossterm::event::KeyCode::Esc;
+pub const PAUSE_BUTTON: crossterm::event::KeyCode = crossterm::event::KeyCode::Char('p');
+/// Leave the run for the menu, it can be resumed from there.
+pub const SUSPEND_BUTTON: crossterm::event::KeyCode = crossterm::event::KeyCode::Char('m');
 
 /// A trait that defines the interface for a game.
 pub trait Game {
-    /// Update the game state with the given input.
+    /// Update the game state with the input of the frame.
     /// Returns false on game end.
     fn update(
         &mut self,
-        input: &Option<crossterm::event::KeyEvent>,
+        input: &crate::input::FrameInput,
         delta_time: &std::time::Duration,
     ) -> UpdateEvent;
 
-    /// Draw the game state to the given output.
-    fn draw(
+    /// Draw the game state to the given frame.
+    fn draw(&self, frame: &mut FrameBuffer, delta_time: &std::time::Duration);
+
+    /// Draw the game `alpha` of the way from the state before the last update
+    /// to the current one, `alpha` is from 0 to 1. Updates take a fixed time
+    /// and frames fall between them, see `pacing::FixedStep`.
+    /// Games without smooth movement draw the current state.
+    fn draw_interpolated(
         &self,
-        out: &mut std::io::Stdout,
+        frame: &mut FrameBuffer,
         delta_time: &std::time::Duration,
-    ) -> crossterm::Result<()>;
+        alpha: f32,
+    ) {
+        _ = alpha;
+        self.draw(frame, delta_time);
+    }
 
     fn get_score(&self) -> Score;
+
+    /// Fill what the engine shows under the play area, see `hud`. The score by default.
+    /// The display comes empty.
+    fn hud(&self, hud: &mut crate::hud::Hud) {
+        hud.score(self.get_score().value);
+    }
+
+    /// The part of the screen the game is played in changed, e.g. the terminal was resized.
+    /// Games with a board of a fixed size keep theirs.
+    fn resize(&mut self, area: crate::point::PlayArea) {
+        _ = area;
+    }
+
+    /// Points worth showing on the heatmap (deaths, landings, hits)
+    /// collected since the previous call.
+    fn take_heat_points(&mut self) -> Vec<crate::point::Point<crate::point::GameBasis>> {
+        vec![]
+    }
+
+    /// Points earned since the previous call and where on the screen,
+    /// used for the floating score popups.
+    fn take_score_events(&mut self) -> Vec<crate::effects::ScoreEvent> {
+        vec![]
+    }
+
+    /// Events worth a sound since the previous call.
+    fn take_sound_effects(&mut self) -> Vec<crate::sound::Effect> {
+        vec![]
+    }
+
+    /// Apply a random chaos mode event.
+    /// Returns the announcement, None if the game has no events.
+    fn chaos_event(&mut self) -> Option<String> {
+        None
+    }
+
+    /// Game state to be written to the disk and continued later.
+    /// Games that can't be saved return None.
+    fn save(&self) -> Option<crate::save::SavedGame> {
+        None
+    }
+
+    /// Copy of the current game state for replays.
+    /// Games that can't be copied return None.
+    fn snapshot(&self) -> Option<Box<dyn Game>> {
+        None
+    }
+
+    /// How well the run is played apart from the score, used for the end-of-run grade.
+    /// Games without a measure return None.
+    fn performance(&self) -> Option<crate::stats::Performance> {
+        None
+    }
+
+    /// Lines cleared, enemies destroyed and such in the run for the lifetime statistics.
+    fn counts(&self) -> crate::lifetime::Counts {
+        Default::default()
+    }
+
+    /// Things on the board by kind for the debug overlay, like ("bullets", 12).
+    fn entities(&self) -> Vec<(&'static str, usize)> {
+        vec![]
+    }
+
+    /// Controls and scoring rules for the help overlay, like "space: fire".
+    /// The keys every game has are listed by the overlay.
+    fn help(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Lines about the run for the game over screen, like "Lines cleared: 4".
+    fn summary(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Ball returns per lost point, judges dynamic difficulty instead of deaths.
+    /// Games without rallies return None.
+    fn rally_length(&self) -> Option<f32> {
+        None
+    }
+
+    /// Draw the pause overlay over the game state.
+    /// Game is not updated while paused.
+    fn draw_pause(&self, frame: &mut FrameBuffer) {
+        use crossterm::style::Stylize;
+
+        use crate::i18n::{format, tr, Text};
+        use crate::input::Action;
+
+        let (width, height) = (frame.width(), frame.height());
+        let title = tr(Text::Paused);
+        let hint = format(Text::PressToResume, &[&crate::help::key(Action::Pause)]);
+        let suspend_hint = format(Text::PressForMenu, &[&crate::help::key(Action::Suspend)]);
+        let help_hint = format(Text::PressForHelp, &[&"?"]);
+        let centered = |text: &str| width.saturating_sub(text.chars().count() as u16) / 2;
+
+        frame.print_styled(Layer::Overlay, centered(title), height / 2, title.bold());
+        for (y, line) in [hint, suspend_hint, help_hint].iter().enumerate() {
+            frame.print(
+                Layer::Overlay,
+                centered(line),
+                height / 2 + 1 + y as u16,
+                line,
+            );
+        }
+    }
 }
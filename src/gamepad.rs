@@ -0,0 +1,144 @@
+//! Controller input, enabled with `gamepad = true` in the config.
+//!
+//! Buttons and the left stick are turned into the default keys, so menus,
+//! versus and the games work with a controller without knowing about it:
+//!
+//! | Controller            | Key    |
+//! |-----------------------|--------|
+//! | D-pad, left stick     | arrows |
+//! | South (A / Cross)     | Enter  |
+//! | West (X / Square)     | Space  |
+//! | East (B / Circle)     | Esc    |
+//! | Start                 | p      |
+//! | Select                | m      |
+//!
+//! Held directions repeat like keys held on the keyboard.
+use crate::game::{EXIT_BUTTON, PAUSE_BUTTON, SUSPEND_BUTTON};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use gilrs::{Axis, Button, EventType, Gilrs};
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+/// How often the controllers are polled.
+const POLL_PERIOD: Duration = Duration::from_millis(10);
+/// Stick deflection counting as a pressed direction.
+const STICK_THRESHOLD: f32 = 0.5;
+/// Held directions are sent again after the delay and then at the rate.
+const REPEAT_DELAY: Duration = Duration::from_millis(250);
+const REPEAT_RATE: Duration = Duration::from_millis(50);
+
+fn direction(button: Button) -> Option<KeyCode> {
+    match button {
+        Button::DPadUp => Some(KeyCode::Up),
+        Button::DPadDown => Some(KeyCode::Down),
+        Button::DPadLeft => Some(KeyCode::Left),
+        Button::DPadRight => Some(KeyCode::Right),
+        _ => None,
+    }
+}
+
+fn key(button: Button) -> Option<KeyCode> {
+    match button {
+        Button::South => Some(KeyCode::Enter),
+        Button::West => Some(KeyCode::Char(' ')),
+        Button::East => Some(EXIT_BUTTON),
+        Button::Start => Some(PAUSE_BUTTON),
+        Button::Select => Some(SUSPEND_BUTTON),
+        _ => None,
+    }
+}
+
+/// Directions being held and when they are sent next.
+#[derive(Default)]
+struct Held {
+    keys: Vec<(KeyCode, Instant)>,
+}
+
+impl Held {
+    /// Returns true if the direction was not held.
+    fn press(&mut self, code: KeyCode) -> bool {
+        if self.keys.iter().any(|&(held, _)| held == code) {
+            return false;
+        }
+        self.keys.push((code, Instant::now() + REPEAT_DELAY));
+        true
+    }
+
+    fn release(&mut self, code: KeyCode) {
+        self.keys.retain(|&(held, _)| held != code);
+    }
+
+    /// Directions to be sent again.
+    fn repeated(&mut self) -> Vec<KeyCode> {
+        let now = Instant::now();
+        let mut repeated = vec![];
+        for (code, next) in self.keys.iter_mut() {
+            if *next <= now {
+                *next = now + REPEAT_RATE;
+                repeated.push(*code);
+            }
+        }
+        repeated
+    }
+}
+
+/// Send controller input to `tx` from a new thread.
+/// Does nothing if controllers can't be used.
+pub fn spawn(tx: Sender<Event>) {
+    std::thread::spawn(move || {
+        let Ok(mut gilrs) = Gilrs::new() else {
+            return;
+        };
+        let mut held = Held::default();
+
+        loop {
+            let mut keys = vec![];
+            while let Some(event) = gilrs.next_event() {
+                match event.event {
+                    EventType::ButtonPressed(button, _) => {
+                        if let Some(code) = direction(button) {
+                            if held.press(code) {
+                                keys.push(code);
+                            }
+                        } else if let Some(code) = key(button) {
+                            keys.push(code);
+                        }
+                    }
+                    EventType::ButtonReleased(button, _) => {
+                        if let Some(code) = direction(button) {
+                            held.release(code);
+                        }
+                    }
+                    EventType::AxisChanged(axis, value, _) => {
+                        // Stick up is positive
+                        let (negative, positive) = match axis {
+                            Axis::LeftStickX => (KeyCode::Left, KeyCode::Right),
+                            Axis::LeftStickY => (KeyCode::Down, KeyCode::Up),
+                            _ => continue,
+                        };
+                        for (code, is_pressed) in [
+                            (negative, value < -STICK_THRESHOLD),
+                            (positive, value > STICK_THRESHOLD),
+                        ] {
+                            if !is_pressed {
+                                held.release(code);
+                            } else if held.press(code) {
+                                keys.push(code);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            keys.extend(held.repeated());
+
+            for code in keys {
+                let key = KeyEvent::new(code, KeyModifiers::NONE);
+                if tx.send(Event::Key(key)).is_err() {
+                    return; // stdin disconnected
+                }
+            }
+            std::thread::sleep(POLL_PERIOD);
+        }
+    });
+}
@@ -0,0 +1,138 @@
+//! Glyph sets: draw code takes its symbols from the current set by their role,
+//! so the games work on terminals and fonts without the Unicode ones.
+//!
+//! Set with `glyphs` in the config: "unicode", "ascii" or "auto" (the default),
+//! which uses ASCII on the Linux console and with a locale that is not UTF-8.
+//!
+//! Colorblind mode (`colorblind` in the config or the settings menu) gives
+//! things told apart by color their own patterns too.
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    #[default]
+    Auto,
+    Unicode,
+    Ascii,
+}
+
+/// Symbols by role. Game cells are two symbols wide.
+#[derive(Debug)]
+pub struct Glyphs {
+    pub snake_head: &'static str,
+    pub invader: [char; 2],
+    pub cannon: &'static str,
+    /// Heatmap cells
+    pub block: &'static str,
+    /// Tetris cells: line, L mirrored, L, square, Z mirrored, T, Z
+    pub figures: [&'static str; 7],
+    /// Invaders shots flying up, down, left and right
+    pub bullets: [&'static str; 4],
+    /// Invaders walls that can be shot through
+    pub barrier: &'static str,
+    /// Invaders walls that can't
+    pub wall: &'static str,
+    /// Frames: vertical, horizontal, then the corners from the top left clockwise
+    pub border: [char; 6],
+    /// Lighter frames, in the same order
+    pub thin_border: [char; 6],
+    /// Line between the versus halves
+    pub divider: &'static str,
+    /// Status effect icons: shield, phasing, inverted, shrunk, overheated
+    pub statuses: [&'static str; 5],
+    /// Chart levels from the lowest to a full cell
+    pub levels: [char; 8],
+}
+
+pub const UNICODE: Glyphs = Glyphs {
+    snake_head: "❮❯",
+    invader: ['◥', '◤'],
+    cannon: "◢◣",
+    block: "██",
+    figures: ["██"; 7],
+    bullets: ["<>"; 4],
+    barrier: "▓▓",
+    wall: "▓▓",
+    border: ['║', '═', '╔', '╗', '╝', '╚'],
+    thin_border: ['│', '─', '┌', '┐', '┘', '└'],
+    divider: "│",
+    statuses: ["◈", "◌", "⇄", "▭", "♨"],
+    levels: ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+};
+
+pub const ASCII: Glyphs = Glyphs {
+    snake_head: "@@",
+    invader: ['\\', '/'],
+    cannon: "/\\",
+    block: "[]",
+    figures: ["[]"; 7],
+    bullets: ["<>"; 4],
+    barrier: "##",
+    wall: "##",
+    border: ['|', '=', '+', '+', '+', '+'],
+    thin_border: ['|', '-', '+', '+', '+', '+'],
+    divider: "|",
+    statuses: ["S", "o", "~", "-", "!"],
+    levels: ['_', '.', '-', '=', '+', '*', '%', '#'],
+};
+
+pub const UNICODE_COLORBLIND: Glyphs = Glyphs {
+    figures: ["██", "▓▓", "▒▒", "░░", "[]", "<>", "{}"],
+    bullets: ["↑↑", "↓↓", "←←", "→→"],
+    barrier: "▒▒",
+    ..UNICODE
+};
+
+pub const ASCII_COLORBLIND: Glyphs = Glyphs {
+    figures: ["[]", "##", "%%", "@@", "{}", "<>", "()"],
+    bullets: ["^^", "vv", "<<", ">>"],
+    barrier: "::",
+    ..ASCII
+};
+
+/// Decided once, the terminal doesn't change during a run.
+static IS_UNICODE_SUPPORTED: Lazy<bool> = Lazy::new(is_unicode_supported);
+
+fn is_unicode_supported() -> bool {
+    // Console fonts have few of the symbols
+    if std::env::var("TERM").is_ok_and(|term| term == "linux") {
+        return false;
+    }
+    // Windows terminals don't follow the locale variables
+    if cfg!(windows) {
+        return true;
+    }
+    // The first one set wins, as in setlocale
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|locale| {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
+
+/// Colorblind mode picked in the settings menu, or the one in the config.
+pub fn is_colorblind() -> bool {
+    crate::settings::get()
+        .colorblind
+        .unwrap_or(crate::config::get().colorblind)
+}
+
+/// Glyph set of the config and the colorblind mode.
+pub fn get() -> &'static Glyphs {
+    let is_unicode = match crate::config::get().glyphs {
+        Mode::Unicode => true,
+        Mode::Ascii => false,
+        Mode::Auto => *IS_UNICODE_SUPPORTED,
+    };
+    match (is_unicode, is_colorblind()) {
+        (true, false) => &UNICODE,
+        (true, true) => &UNICODE_COLORBLIND,
+        (false, false) => &ASCII,
+        (false, true) => &ASCII_COLORBLIND,
+    }
+}
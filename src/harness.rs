@@ -0,0 +1,148 @@
+//! Driving a game with a fixed list of key presses, for tests of how the games
+//! play: the same seed and presses give the same run every time.
+//!
+//! ```
+//! use cl_games::prelude::*;
+//! use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+//! use std::time::Duration;
+//!
+//! let registry = Registry::builtin();
+//! let mut harness = Harness::from_entry(&registry.games()[0], 7, 40, 20);
+//! let left = KeyEvent::new(KeyCode::Left, KeyModifiers::NONE);
+//! harness.play(&[(left, Duration::from_millis(100))]);
+//! harness.assert_running();
+//! harness.press(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), Duration::ZERO);
+//! harness.assert_game_over();
+//! ```
+use crate::game::{Game, UpdateEvent};
+use crate::input::{FrameInput, Input};
+use crate::registry::GameEntry;
+use crossterm::event::KeyEvent;
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::Duration;
+
+/// Game on a screen of a fixed size, updated only by the harness.
+/// Keys act as the default keys, whatever the config says.
+pub struct Harness {
+    game: Box<dyn Game>,
+    screen_size: (u16, u16),
+    /// Time of the updates without a key, see `wait`
+    frame_time: Duration,
+    played: Duration,
+    is_over: bool,
+}
+
+impl Harness {
+    /// Harness for a game made on a screen of the given size.
+    pub fn new(game: Box<dyn Game>, width: u16, height: u16) -> Self {
+        Self {
+            game,
+            screen_size: (width, height),
+            frame_time: Duration::from_secs(1) / crate::config::DEFAULT_FRAME_RATE,
+            played: Duration::ZERO,
+            is_over: false,
+        }
+    }
+
+    /// Harness for the game made from the seed on a screen of the given size.
+    pub fn from_entry(entry: &GameEntry, seed: u64, width: u16, height: u16) -> Self {
+        let game = (entry.create)(
+            StdRng::seed_from_u64(seed),
+            crate::hud::play_area(width, height),
+        );
+        Self::new(game, width, height)
+    }
+
+    /// Time of the updates without a key, a frame at the default frame rate at first.
+    pub fn frame_time(mut self, frame_time: Duration) -> Self {
+        self.frame_time = frame_time;
+        self
+    }
+
+    /// One update with the optional key taking `delta_time`.
+    /// Nothing happens once the game is over.
+    pub fn step(&mut self, key: Option<KeyEvent>, delta_time: Duration) -> UpdateEvent {
+        if self.is_over {
+            return UpdateEvent::GameOver;
+        }
+        let input = key.map(Input::from_default_key);
+        let event = self.game.update(&FrameInput::new(input), &delta_time);
+        self.played += delta_time;
+        self.is_over = matches!(event, UpdateEvent::GameOver);
+        event
+    }
+
+    /// One update with the key pressed.
+    pub fn press(&mut self, key: KeyEvent, delta_time: Duration) -> UpdateEvent {
+        self.step(Some(key), delta_time)
+    }
+
+    /// Updates without a key, a frame time each, until `time` passes or the game is over.
+    pub fn wait(&mut self, time: Duration) {
+        let mut left = time;
+        while !left.is_zero() && !self.is_over {
+            let delta_time = left.min(self.frame_time);
+            self.step(None, delta_time);
+            left -= delta_time;
+        }
+    }
+
+    /// Press the keys in order, each followed by its time without a key.
+    /// Keys after the end of the game are ignored.
+    pub fn play(&mut self, script: &[(KeyEvent, Duration)]) {
+        for &(key, time) in script {
+            self.press(key, Duration::ZERO);
+            self.wait(time);
+        }
+    }
+
+    pub fn game(&self) -> &dyn Game {
+        self.game.as_ref()
+    }
+
+    pub fn score(&self) -> i64 {
+        self.game.get_score().value
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_over
+    }
+
+    /// Time of all the updates so far.
+    pub fn played(&self) -> Duration {
+        self.played
+    }
+
+    /// Current frame as plain text lines, see src/snapshot.rs.
+    pub fn lines(&self) -> Vec<String> {
+        let (width, height) = self.screen_size;
+        crate::snapshot::frame(self.game.as_ref(), width, height).lines()
+    }
+
+    #[track_caller]
+    pub fn assert_score(&self, score: i64) {
+        assert_eq!(self.score(), score, "score after {:?}", self.played);
+    }
+
+    #[track_caller]
+    pub fn assert_game_over(&self) {
+        assert!(self.is_over, "game still going after {:?}", self.played);
+    }
+
+    #[track_caller]
+    pub fn assert_running(&self) {
+        assert!(!self.is_over, "game over after {:?}", self.played);
+    }
+
+    /// The text shows somewhere on the screen.
+    #[track_caller]
+    pub fn assert_shows(&self, text: &str) {
+        let lines = self.lines();
+        assert!(
+            lines.iter().any(|line| line.contains(text)),
+            "{:?} not on the screen:\n{}",
+            text,
+            lines.join("\n")
+        );
+    }
+}
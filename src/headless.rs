@@ -0,0 +1,185 @@
+//! Running games without a terminal.
+//!
+//! ```text
+//! cl_games --headless <game id> [--frames <n>] [--seed <n>] [--inputs <file>]
+//! ```
+//!
+//! Game ids are `snake`, `tetris`, `invaders` and `pong` (see src/registry.rs).
+//! Without `--inputs` keys are random. Runs with the same `--seed` are the same.
+//! The inputs file has one line per frame: a key name as in the config
+//! (`left`, `space`, `x`, ...) or an empty line for no key.
+//! Frames after the end of the file get no input.
+use crate::game::UpdateEvent;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::time::Duration;
+
+pub const FLAG: &str = "--headless";
+/// Screen size the games see without a terminal.
+pub const SCREEN_SIZE: (u16, u16) = (80, 24);
+pub const DEFAULT_FRAMES: u64 = 10_000;
+/// Keys a random player presses. Exit, pause and suspend are left out.
+const RANDOM_KEYS: [KeyCode; 5] = [
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Char(' '),
+];
+/// Chance of a key press in a frame for the random player.
+const PRESS_CHANCE: f64 = 0.2;
+
+#[non_exhaustive]
+pub struct Options {
+    pub game: String,
+    /// Run stops after this many frames if the game is not over yet.
+    pub frames: u64,
+    pub seed: Option<u64>,
+    pub inputs: Option<Vec<Option<KeyCode>>>,
+}
+
+impl Options {
+    /// Run of the game with the given id: random keys, random seed, default frames.
+    pub fn new(game: &str) -> Self {
+        Self {
+            game: game.to_owned(),
+            frames: DEFAULT_FRAMES,
+            seed: None,
+            inputs: None,
+        }
+    }
+}
+
+/// Result of a headless run.
+#[non_exhaustive]
+pub struct Report {
+    pub score: i64,
+    pub frames: u64,
+    pub is_over: bool,
+}
+
+/// Player pressing random keys now and then, for runs without inputs
+/// and the demo on the idle menu.
+pub struct RandomPlayer {
+    rng: StdRng,
+}
+
+impl RandomPlayer {
+    pub fn new(rng: StdRng) -> Self {
+        Self { rng }
+    }
+
+    /// Key pressed in the next frame, None for no key.
+    pub fn next_key(&mut self) -> Option<KeyCode> {
+        self.rng
+            .gen_bool(PRESS_CHANCE)
+            .then(|| RANDOM_KEYS[self.rng.gen_range(0..RANDOM_KEYS.len())])
+    }
+}
+
+/// Input of a pressed key as the games get it.
+pub fn key_input(code: KeyCode) -> crate::input::Input {
+    crate::input::Input::from_key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+/// Parse arguments following `--headless`.
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut args = args.iter();
+    let game = args.next().ok_or("game name expected")?;
+    let mut options = Options::new(game);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("value expected after {}", arg))
+        };
+        match arg.as_str() {
+            "--frames" => {
+                options.frames = value()?
+                    .parse()
+                    .map_err(|_| "bad frames count".to_owned())?
+            }
+            "--seed" => options.seed = Some(value()?.parse().map_err(|_| "bad seed".to_owned())?),
+            "--inputs" => options.inputs = Some(read_inputs(value()?)?),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn read_inputs(path: &str) -> Result<Vec<Option<KeyCode>>, String> {
+    let text = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+
+    text.lines()
+        .enumerate()
+        .map(|(ind, line)| match line.trim() {
+            "" => Ok(None),
+            name => crate::config::parse_key(name)
+                .map(Some)
+                .ok_or_else(|| format!("{}:{}: unknown key name `{}`", path, ind + 1, name)),
+        })
+        .collect()
+}
+
+/// Update the game frame by frame without drawing.
+/// Every frame takes the configured frame time.
+pub fn run(options: &Options) -> Result<Report, String> {
+    crate::util::set_screen_size(SCREEN_SIZE.0, SCREEN_SIZE.1);
+
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    // Game randomness and random keys are reproduced from the same seed
+    let game_rng = StdRng::from_rng(&mut rng).expect("StdRng seeding does not fail");
+    let mut chaos = crate::chaos::Scheduler::new(
+        StdRng::from_rng(&mut rng).expect("StdRng seeding does not fail"),
+    );
+    let registry = crate::registry::Registry::builtin();
+    let entry = registry.find(&options.game).ok_or_else(|| {
+        let known: Vec<String> = registry
+            .games()
+            .iter()
+            .map(|entry| format!("  {} - {}", entry.id, entry.description))
+            .collect();
+        format!(
+            "unknown game {}, known games:\n{}",
+            options.game,
+            known.join("\n")
+        )
+    })?;
+    let mut game = (entry.create)(
+        game_rng,
+        crate::hud::play_area(SCREEN_SIZE.0, SCREEN_SIZE.1),
+    );
+    let delta_time: Duration = crate::config::get().between_frames();
+    let mut player = RandomPlayer::new(rng);
+
+    for frame in 0..options.frames {
+        let code = match &options.inputs {
+            Some(inputs) => inputs.get(frame as usize).copied().flatten(),
+            None => player.next_key(),
+        };
+        let input = code.map(key_input);
+
+        if let UpdateEvent::GameOver =
+            game.update(&crate::input::FrameInput::new(input), &delta_time)
+        {
+            return Ok(Report {
+                score: game.get_score().value,
+                frames: frame + 1,
+                is_over: true,
+            });
+        }
+        if crate::config::get().chaos && chaos.tick(delta_time) {
+            game.chaos_event();
+        }
+    }
+
+    Ok(Report {
+        score: game.get_score().value,
+        frames: options.frames,
+        is_over: false,
+    })
+}
@@ -0,0 +1,215 @@
+use crate::i18n::{tr, Text};
+use crate::point::{GameBasis, Point};
+use crate::render::{FrameBuffer, Layer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub const FILE_NAME: &str = "heatmaps.toml";
+
+/// Hit counts of game cells.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Heatmap {
+    /// (x, y, count)
+    cells: Vec<(i32, i32, u32)>,
+}
+
+impl Heatmap {
+    pub fn add(&mut self, point: Point<GameBasis>) {
+        let (x, y) = (point.x.round() as i32, point.y.round() as i32);
+
+        match self
+            .cells
+            .iter_mut()
+            .find(|(cell_x, cell_y, _)| *cell_x == x && *cell_y == y)
+        {
+            Some((_, _, count)) => *count += 1,
+            None => self.cells.push((x, y, 1)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Draw cells as a colored density grid scaled to fit `width`x`height` screen symbols.
+    pub fn draw(&self, frame: &mut FrameBuffer, origin: (u16, u16), width: u16, height: u16) {
+        use crossterm::style::Stylize;
+
+        let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) = (
+            self.cells.iter().map(|cell| cell.0).min(),
+            self.cells.iter().map(|cell| cell.0).max(),
+            self.cells.iter().map(|cell| cell.1).min(),
+            self.cells.iter().map(|cell| cell.1).max(),
+        ) else {
+            return;
+        };
+
+        // cells have 2-symbols width
+        let columns = (width / 2).max(1) as i32;
+        let rows = height.max(1) as i32;
+        let scale_x = ((max_x - min_x + 1) as f32 / columns as f32).max(1.0);
+        let scale_y = ((max_y - min_y + 1) as f32 / rows as f32).max(1.0);
+
+        // Several game cells can fall into one screen cell
+        let mut grid = vec![0_u32; (columns * rows) as usize];
+        for &(x, y, count) in self.cells.iter() {
+            let column = (((x - min_x) as f32 / scale_x) as i32).min(columns - 1);
+            let row = (((y - min_y) as f32 / scale_y) as i32).min(rows - 1);
+            grid[(row * columns + column) as usize] += count;
+        }
+        let max_count = grid.iter().copied().max().unwrap_or(0).max(1);
+        // Colors from the coldest to the hottest cell
+        let ramp = &crate::theme::palette().heat;
+        let block = crate::glyphs::get().block;
+
+        for (ind, &count) in grid.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let column = ind as i32 % columns;
+            let row = ind as i32 / columns;
+            let heat = (count - 1) as usize * ramp.len() / max_count as usize;
+
+            frame.print_styled(
+                Layer::Game,
+                origin.0 + column as u16 * 2,
+                origin.1 + row as u16,
+                block.with(ramp[heat.min(ramp.len() - 1)]),
+            );
+        }
+    }
+}
+
+/// Heatmaps of all games by game name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Heatmaps {
+    pub games: BTreeMap<String, Heatmap>,
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+impl Heatmaps {
+    /// Read saved heatmaps. Missing or broken file means no heatmaps.
+    pub fn load() -> Self {
+        path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path = path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+}
+
+/// Points of finished runs not written to the disk yet.
+#[derive(Debug, Default)]
+pub struct Unsaved {
+    games: BTreeMap<String, Vec<Point<GameBasis>>>,
+}
+
+impl Unsaved {
+    pub fn add(&mut self, game_name: &str, points: Vec<Point<GameBasis>>) {
+        if !points.is_empty() {
+            self.games
+                .entry(game_name.to_owned())
+                .or_default()
+                .extend(points);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Add all points to the saved heatmaps.
+    /// Points stay unsaved if writing fails.
+    pub fn save(&mut self) -> std::io::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let mut heatmaps = Heatmaps::load();
+        for (game_name, points) in self.games.iter() {
+            let heatmap = heatmaps.games.entry(game_name.clone()).or_default();
+            for &point in points {
+                heatmap.add(point);
+            }
+        }
+        heatmaps.save()?;
+        self.games.clear();
+
+        Ok(())
+    }
+}
+
+/// Show saved heatmaps one game at a time.
+/// Left/Right switch games, any other key returns.
+pub fn show(
+    out: &mut std::io::Stdout,
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+) -> crossterm::Result<()> {
+    use crate::input::Action;
+    use crossterm::event::KeyEventKind;
+
+    let action = |key: &crossterm::event::KeyEvent| crate::config::get().keys.action(key);
+    use crossterm::terminal;
+
+    let heatmaps = Heatmaps::load();
+    let games: Vec<_> = heatmaps
+        .games
+        .iter()
+        .filter(|(_, heatmap)| !heatmap.is_empty())
+        .collect();
+    let mut current = 0;
+    let mut frame = FrameBuffer::from_terminal()?;
+
+    loop {
+        let (width, height) = terminal::size()?;
+        frame.clear(width, height);
+
+        match games.get(current) {
+            None => frame.print(Layer::Hud, 0, 0, tr(Text::NoRunsRecorded)),
+            Some((name, heatmap)) => {
+                frame.print(
+                    Layer::Hud,
+                    0,
+                    0,
+                    &format!("{} ({}/{})", name, current + 1, games.len()),
+                );
+                frame.print(
+                    Layer::Hud,
+                    0,
+                    height.saturating_sub(1),
+                    tr(Text::BrowseHint),
+                );
+                heatmap.draw(&mut frame, (0, 2), width, height.saturating_sub(4));
+            }
+        }
+        frame.flush(out)?;
+
+        let key = crate::input::recv_key(rx);
+        match key {
+            key if key.kind == KeyEventKind::Release => {}
+            key if action(&key) == Some(Action::MoveLeft) && !games.is_empty() => {
+                current = (current + games.len() - 1) % games.len();
+            }
+            key if action(&key) == Some(Action::MoveRight) && !games.is_empty() => {
+                current = (current + 1) % games.len();
+            }
+            _ => break,
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Controls and rules of the game being played, shown over the frozen game
+//! with `?` or F1. Keys are named as configured.
+use crate::frame::{Frame, Lines};
+use crate::i18n::{format, tr, Text};
+use crate::input::Action;
+use crate::layout::Rect;
+use crate::render::{FrameBuffer, Layer};
+use crossterm::event::KeyCode;
+
+/// Keys opening and closing the help, unless bound to an action.
+pub const HELP_BUTTONS: [KeyCode; 2] = [KeyCode::Char('?'), KeyCode::F(1)];
+
+pub fn is_help_key(code: KeyCode) -> bool {
+    HELP_BUTTONS.contains(&code)
+}
+
+/// Name of the key doing the action, the rebound one or the default one.
+pub fn key(action: Action) -> String {
+    use crate::game::{EXIT_BUTTON, PAUSE_BUTTON, SUSPEND_BUTTON};
+
+    let keys = &crate::config::get().keys;
+    let (bound, default) = match action {
+        Action::MoveUp => (keys.up, Some(KeyCode::Up)),
+        Action::MoveDown => (keys.down, Some(KeyCode::Down)),
+        Action::MoveLeft => (keys.left, Some(KeyCode::Left)),
+        Action::MoveRight => (keys.right, Some(KeyCode::Right)),
+        Action::Fire => (keys.fire, Some(KeyCode::Char(' '))),
+        Action::Rotate => (keys.rotate, None),
+        Action::Pause => (keys.pause, Some(PAUSE_BUTTON)),
+        Action::Suspend => (keys.suspend, Some(SUSPEND_BUTTON)),
+        Action::Quit => (keys.exit, Some(EXIT_BUTTON)),
+    };
+    bound
+        .or(default)
+        .and_then(crate::config::key_name)
+        .unwrap_or_else(|| tr(Text::Unbound).to_owned())
+}
+
+/// Box in the middle of the screen with the lines of the game,
+/// then the keys every game has.
+pub fn draw(frame: &mut FrameBuffer, game_name: &str, lines: &[String]) {
+    use crossterm::style::Stylize;
+
+    let common = [
+        format(Text::HelpPause, &[&key(Action::Pause)]),
+        format(Text::HelpSuspend, &[&key(Action::Suspend)]),
+        format(Text::HelpQuit, &[&key(Action::Quit)]),
+        tr(Text::HelpClose).to_owned(),
+    ];
+    let mut text: Vec<&str> = lines.iter().map(String::as_str).collect();
+    if !text.is_empty() {
+        text.push("");
+    }
+    text.extend(common.iter().map(String::as_str));
+
+    let title = format(Text::HowToPlay, &[&game_name]);
+    let text_width = text
+        .iter()
+        .map(|line| line.chars().count())
+        .chain([title.chars().count() + 4])
+        .max()
+        .unwrap_or(0) as u16;
+    let area = Rect::of_frame(frame).centered(text_width + 4, text.len() as u16 + 2);
+    // The game doesn't show through
+    for y in area.y..area.bottom() {
+        frame.print(Layer::Overlay, area.x, y, &" ".repeat(area.width as usize));
+    }
+    let inner = Frame::new()
+        .title(&title)
+        .lines(Lines::Single)
+        .draw(frame, Layer::Overlay, area)
+        .shrink(1, 0, 1, 0);
+    for (y, line) in text.iter().enumerate() {
+        let y = inner.y + y as u16;
+        // Key names before the colon stand out
+        match line.split_once(": ") {
+            Some((keys, what)) => {
+                frame.print_styled(Layer::Overlay, inner.x, y, keys.bold());
+                let x = inner.x + keys.chars().count() as u16;
+                frame.print(Layer::Overlay, x, y, &format!(": {}", what));
+            }
+            None => frame.print(Layer::Overlay, inner.x, y, line),
+        }
+    }
+}
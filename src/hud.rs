@@ -0,0 +1,169 @@
+//! Heads-up display: games declare what the player sees besides the board
+//! (score, lives, level, time, hints) and the engine draws it the same way
+//! for every game, in the rows reserved under the play area.
+use crate::i18n::{display, tr, Text};
+use crate::layout::{Direction, Rect, Size};
+use crate::lifetime::Clock;
+use crate::point::PlayArea;
+use crate::render::{FrameBuffer, Layer};
+use crossterm::style::{Color, ContentStyle, StyledContent, Stylize};
+use std::fmt::Display;
+use std::time::Duration;
+
+/// Rows under the play area taken by the display.
+pub const HEIGHT: u16 = 1;
+/// Symbols between the items.
+const GAP: &str = "  ";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Item {
+    /// The value and the color it is shown in, if any
+    Score(i64, Option<Color>),
+    Lives(u32),
+    Level(u32),
+    Time(Duration),
+    Hint(&'static str),
+}
+
+impl Item {
+    /// Pass the parts of the item in their styles to `part`, nothing is formatted into strings.
+    fn parts(&self, part: &mut impl FnMut(&dyn Display, ContentStyle)) {
+        let plain = ContentStyle::new();
+        match *self {
+            Item::Score(value, color) => {
+                part(&tr(Text::ScoreHint), plain);
+                part(&value, color.map_or(plain, |color| plain.with(color)));
+            }
+            Item::Lives(lives) => part(&display(Text::Lives, &[&lives]), plain),
+            Item::Level(level) => part(&display(Text::Level, &[&level]), plain),
+            Item::Time(elapsed) => part(&display(Text::Time, &[&Clock(elapsed.as_secs())]), plain),
+            Item::Hint(hint) => part(&hint, plain.italic()),
+        }
+    }
+}
+
+/// What a game shows, in the order declared. Hints go to the right end.
+/// The engine keeps one and games fill it anew every frame, see `Game::hud`,
+/// so drawing it doesn't allocate.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Hud {
+    items: Vec<Item>,
+}
+
+impl Hud {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove the items of the previous frame, the room for them is kept.
+    pub fn clear(&mut self) -> &mut Self {
+        self.items.clear();
+        self
+    }
+
+    pub fn score(&mut self, value: i64) -> &mut Self {
+        self.items.push(Item::Score(value, None));
+        self
+    }
+
+    /// Score in a color, e.g. by how good it is.
+    pub fn colored_score(&mut self, value: i64, color: Color) -> &mut Self {
+        self.items.push(Item::Score(value, Some(color)));
+        self
+    }
+
+    pub fn lives(&mut self, lives: u32) -> &mut Self {
+        self.items.push(Item::Lives(lives));
+        self
+    }
+
+    pub fn level(&mut self, level: u32) -> &mut Self {
+        self.items.push(Item::Level(level));
+        self
+    }
+
+    /// Time played.
+    pub fn time(&mut self, elapsed: Duration) -> &mut Self {
+        self.items.push(Item::Time(elapsed));
+        self
+    }
+
+    pub fn hint(&mut self, hint: &'static str) -> &mut Self {
+        self.items.push(Item::Hint(hint));
+        self
+    }
+
+    /// Draw the items in the `region` of the frame.
+    pub fn draw(&self, frame: &mut FrameBuffer) {
+        let area = region(frame);
+        self.draw_in(frame, area);
+    }
+
+    /// Draw the items in `area`.
+    pub fn draw_in(&self, frame: &mut FrameBuffer, area: Rect) {
+        if area.height == 0 {
+            return;
+        }
+
+        // Hints don't cover the stats
+        let stats_width = self.width(false);
+        let [stats_area, hints_area] = area.split(
+            Direction::Horizontal,
+            [Size::Fixed(stats_width), Size::Flex(1)],
+        );
+        self.draw_parts(frame, false, stats_area.x, stats_area);
+        let hints_area = hints_area.shrink(GAP.len() as u16, 0, 0, 0);
+        let hints_x = hints_area.right() - self.width(true).min(hints_area.width);
+        self.draw_parts(frame, true, hints_x, hints_area);
+    }
+
+    /// Pass the parts of the hints or of the other items to `part`, a gap between the items.
+    fn parts(&self, is_hint: bool, mut part: impl FnMut(&dyn Display, ContentStyle)) {
+        let items = self
+            .items
+            .iter()
+            .filter(|item| matches!(item, Item::Hint(_)) == is_hint);
+        for (ind, item) in items.enumerate() {
+            if ind > 0 {
+                part(&GAP, ContentStyle::new());
+            }
+            item.parts(&mut part);
+        }
+    }
+
+    /// Symbols taken by the hints or by the other items.
+    fn width(&self, is_hint: bool) -> u16 {
+        let mut width = 0;
+        self.parts(is_hint, |part, _| {
+            width += crate::render::width(part);
+        });
+        width
+    }
+
+    /// Draw the hints or the other items from column `x` of the top row of `area`,
+    /// cut at its right end.
+    fn draw_parts(&self, frame: &mut FrameBuffer, is_hint: bool, x: u16, area: Rect) {
+        let mut x = x;
+        self.parts(is_hint, |part, style| {
+            x = frame.print_styled_until(
+                Layer::Hud,
+                x,
+                area.y,
+                area.right(),
+                StyledContent::new(style, part),
+            );
+        });
+    }
+}
+
+/// Rows of the frame the display is drawn in.
+pub fn region(frame: &FrameBuffer) -> Rect {
+    let [_, region] =
+        Rect::of_frame(frame).split(Direction::Vertical, [Size::Flex(1), Size::Fixed(HEIGHT)]);
+    region
+}
+
+/// Place of a game on a screen of the size, above the display.
+pub fn play_area(width: u16, height: u16) -> PlayArea {
+    PlayArea::screen(width, height.saturating_sub(HEIGHT))
+}
@@ -0,0 +1,582 @@
+//! Texts on the screen in the configured language.
+//!
+//! Every text has a column per language in `Text::texts`, so a new language
+//! is a new `Language` variant and a new column. `{}` in a text is filled
+//! with the arguments of `format` in order.
+//! Game names, key names and numbers are the same in every language.
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// Name of the language in itself.
+    pub fn title(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+}
+
+/// Texts shown to the player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Text {
+    // Game screens
+    ScoreHint,
+    Lives,
+    Level,
+    NextFigure,
+    Paused,
+    PressToResume,
+    PressForMenu,
+    PressForHelp,
+    QuitRun,
+    RunningSlow,
+    DemoHint,
+    VersusPaused,
+    VersusGameOver,
+    EndMatch,
+    LeftWins,
+    RightWins,
+    Draw,
+    AnyKeyForMenu,
+
+    // Game over screen
+    GameOverTitle,
+    ScoreLine,
+    NewBest,
+    PersonalBest,
+    Time,
+    Seed,
+    PlayAgain,
+    WatchReplay,
+    BackToMenu,
+    Quit,
+
+    // Dialogs
+    ConfirmHint,
+    TextHint,
+
+    // Help overlay
+    HowToPlay,
+    HelpPause,
+    HelpSuspend,
+    HelpQuit,
+    HelpClose,
+
+    // Menus
+    MainMenu,
+    Hello,
+    Play,
+    WithSeed,
+    Practice,
+    RecordMacro,
+    StartFromMacro,
+    Versus,
+    VersusLeft,
+    Heatmaps,
+    Statistics,
+    ScoreTrends,
+    Settings,
+    Exit,
+    Resume,
+    ResumeSaved,
+    SaveAndQuit,
+    Challenge,
+    ChallengeBest,
+    Theme,
+    Colorblind,
+    Language,
+    AsInConfig,
+    Current,
+    On,
+    Off,
+
+    // Snake
+    SnakeTurn,
+    SnakeApple,
+    SnakeShieldApple,
+    SnakeBite,
+    ApplesEaten,
+    ApplesSpawned,
+
+    // Tetris
+    TetrisMove,
+    TetrisRotate,
+    TetrisDrop,
+    TetrisLines,
+    TetrisTwice,
+    TetrisTop,
+    LinesCleared,
+    FiguresPlaced,
+
+    // Space invaders
+    InvadersMove,
+    InvadersFire,
+    InvadersPoints,
+    InvadersWalls,
+    InvadersEnd,
+    EnemiesDestroyed,
+    ShotsFired,
+    Accuracy,
+
+    // Pong
+    PongMove,
+    PongPoint,
+    PongMiss,
+    PongEndless,
+    BallsReturned,
+    BallsMissed,
+
+    // Notices
+    ConfigReloaded,
+    ConfigError,
+    ScriptError,
+    PluginError,
+    SetupNotSaved,
+    ThemeNotSaved,
+    LanguageNotSaved,
+    ColorblindNotSaved,
+    ScreenSizeDiffers,
+    MacroSaved,
+    MacroNotSaved,
+    AskSeed,
+    AskSeedAgain,
+    SavingFailed,
+    SuspendedLost,
+    HeatmapsNotSaved,
+    Replay,
+    Unbound,
+
+    // Run summary
+    AutosaveFailed,
+    RecentScores,
+    StatisticsNotSaved,
+    TelemetryNotWritten,
+    WeeklyBest,
+    WeeklyBestNotSaved,
+    Grade,
+    GradeWithReasons,
+    BetterThanRuns,
+    BestRunYet,
+    FirstRun,
+    BetterRun,
+    BelowUsual,
+    CloseToUsual,
+    SnakePerformance,
+    SnakeTip,
+    TetrisPerformance,
+    TetrisTip,
+    InvadersPerformance,
+    InvadersTip,
+    PongPerformance,
+    PongTip,
+
+    // Chaos events
+    ControlsInverted,
+    BoardShiftsLeft,
+    BoardShiftsRight,
+    WaveWarpsDown,
+    PlankShrinks,
+
+    // Statistics screens
+    NoRunsRecorded,
+    BrowseHint,
+    LastRuns,
+    NoRunsFinished,
+    ColumnGame,
+    ColumnPlayed,
+    ColumnBest,
+    ColumnTotalScore,
+    ColumnPlayTime,
+    ColumnLines,
+    ColumnEnemies,
+    AllGames,
+    AnyKeyToReturn,
+
+    // First-run setup
+    SetupStep,
+    SetupColors,
+    SetupSymbols,
+    SetupAutoSymbols,
+    SetupMovementKeys,
+    SetupArrows,
+    SetupName,
+}
+
+impl Text {
+    /// The text in every language, in the `Language` order.
+    fn texts(self) -> [&'static str; Language::ALL.len()] {
+        match self {
+            Text::ScoreHint => ["Score: ", "Punkte: "],
+            Text::Lives => ["Lives: {}", "Leben: {}"],
+            Text::Level => ["Level: {}", "Stufe: {}"],
+            Text::NextFigure => ["Next figure:", "Nächste Figur:"],
+            Text::Paused => ["PAUSED", "PAUSE"],
+            Text::PressToResume => ["Press {} to resume", "{} drücken zum Fortsetzen"],
+            Text::PressForMenu => ["Press {} to open the menu", "{} drücken für das Menü"],
+            Text::PressForHelp => ["Press {} for the controls", "{} drücken für die Steuerung"],
+            Text::QuitRun => ["Quit the run?", "Runde beenden?"],
+            Text::RunningSlow => ["Running slow", "Läuft langsam"],
+            Text::DemoHint => [
+                "DEMO: {}. Press any key",
+                "DEMO: {}. Beliebige Taste drücken",
+            ],
+            Text::VersusPaused => [
+                "PAUSED: p to resume, Esc to end the match",
+                "PAUSE: p setzt fort, Esc beendet das Match",
+            ],
+            Text::VersusGameOver => ["GAME OVER", "SPIELENDE"],
+            Text::EndMatch => ["End the match?", "Match beenden?"],
+            Text::LeftWins => ["Left player wins!", "Links gewinnt!"],
+            Text::RightWins => ["Right player wins!", "Rechts gewinnt!"],
+            Text::Draw => ["Draw!", "Unentschieden!"],
+            Text::AnyKeyForMenu => [
+                "Press any key to return to the menu",
+                "Beliebige Taste drücken, um zum Menü zurückzukehren",
+            ],
+
+            Text::GameOverTitle => ["Game over: {}", "Spielende: {}"],
+            Text::ScoreLine => ["Score: {}", "Punkte: {}"],
+            Text::NewBest => [
+                "New personal best! {} over previous best",
+                "Neuer Rekord! {} mehr als der alte",
+            ],
+            Text::PersonalBest => ["Personal best: {}", "Rekord: {}"],
+            Text::Time => ["Time: {}", "Zeit: {}"],
+            Text::Seed => ["Seed: {}", "Seed: {}"],
+            Text::PlayAgain => ["Play again", "Nochmal spielen"],
+            Text::WatchReplay => [
+                "Watch the last {} seconds",
+                "Die letzten {} Sekunden ansehen",
+            ],
+            Text::BackToMenu => ["Back to menu", "Zurück zum Menü"],
+            Text::Quit => ["Quit", "Beenden"],
+
+            Text::ConfirmHint => ["y/Enter: yes, n/Esc: no", "y/Enter: ja, n/Esc: nein"],
+            Text::TextHint => ["Enter: done, Esc: cancel", "Enter: fertig, Esc: abbrechen"],
+
+            Text::HowToPlay => ["{}: how to play", "{}: Spielanleitung"],
+            Text::HelpPause => ["{}: pause", "{}: Pause"],
+            Text::HelpSuspend => ["{}: leave for the menu", "{}: ins Menü"],
+            Text::HelpQuit => ["{}: end the run", "{}: Runde beenden"],
+            Text::HelpClose => ["?/f1: close the help", "?/f1: Hilfe schließen"],
+
+            Text::MainMenu => ["Main menu", "Hauptmenü"],
+            Text::Hello => ["Hello, {}!", "Hallo, {}!"],
+            Text::Play => ["Play", "Spielen"],
+            Text::WithSeed => ["With a seed", "Mit Seed"],
+            Text::Practice => ["Practice", "Üben"],
+            Text::RecordMacro => ["Record macro (r to stop)", "Makro aufnehmen (r stoppt)"],
+            Text::StartFromMacro => ["Start from macro", "Mit Makro starten"],
+            Text::Versus => ["Versus (WASD vs arrows)", "Versus (WASD gegen Pfeile)"],
+            Text::VersusLeft => ["{} vs", "{} gegen"],
+            Text::Heatmaps => ["Heatmaps", "Heatmaps"],
+            Text::Statistics => ["Statistics", "Statistik"],
+            Text::ScoreTrends => ["Score trends", "Punkteverlauf"],
+            Text::Settings => ["Settings", "Einstellungen"],
+            Text::Exit => ["Exit", "Beenden"],
+            Text::Resume => ["Resume {}", "{} fortsetzen"],
+            Text::ResumeSaved => ["Resume {} (score {})", "{} fortsetzen ({} Punkte)"],
+            Text::SaveAndQuit => ["Save & quit", "Speichern und beenden"],
+            Text::Challenge => ["Challenge of the week: {}", "Herausforderung der Woche: {}"],
+            Text::ChallengeBest => [
+                "Challenge of the week: {} (best {})",
+                "Herausforderung der Woche: {} (Rekord {})",
+            ],
+            Text::Theme => ["Theme", "Farben"],
+            Text::Colorblind => ["Colorblind mode", "Farbenblind-Modus"],
+            Text::Language => ["Language", "Sprache"],
+            Text::AsInConfig => ["As in the config ({})", "Wie in der Konfiguration ({})"],
+            Text::Current => [" (current)", " (aktuell)"],
+            Text::On => ["On", "An"],
+            Text::Off => ["Off", "Aus"],
+
+            Text::SnakeTurn => ["{}: turn", "{}: lenken"],
+            Text::SnakeApple => [
+                "Apple: 1 point, the snake grows",
+                "Apfel: 1 Punkt, die Schlange wächst",
+            ],
+            Text::SnakeShieldApple => [
+                "Shield apple: one bite of yourself is forgiven",
+                "Schildapfel: ein Biss in dich selbst wird verziehen",
+            ],
+            Text::SnakeBite => [
+                "Biting yourself ends the run",
+                "Ein Biss in dich selbst beendet die Runde",
+            ],
+            Text::ApplesEaten => ["Apples eaten: {}", "Gegessene Äpfel: {}"],
+            Text::ApplesSpawned => ["Apples spawned: {}", "Erschienene Äpfel: {}"],
+
+            Text::TetrisMove => ["{}: move the figure", "{}: Figur bewegen"],
+            Text::TetrisRotate => ["{}: rotate", "{}: drehen"],
+            Text::TetrisDrop => ["{} (hold): drop faster", "{} (halten): schneller fallen"],
+            Text::TetrisLines => [
+                "Line: 100 points, 4 lines at once: 200 each",
+                "Reihe: 100 Punkte, 4 Reihen auf einmal: je 200",
+            ],
+            Text::TetrisTwice => [
+                "4 lines twice in a row: 300 each",
+                "Zweimal 4 Reihen hintereinander: je 300",
+            ],
+            Text::TetrisTop => [
+                "Figures reaching the top end the run",
+                "Figuren am oberen Rand beenden die Runde",
+            ],
+            Text::LinesCleared => ["Lines cleared: {}", "Geräumte Reihen: {}"],
+            Text::FiguresPlaced => ["Figures placed: {}", "Gelegte Figuren: {}"],
+
+            Text::InvadersMove => ["{}: move the cannon", "{}: Kanone bewegen"],
+            Text::InvadersFire => [
+                "{}: fire, the cannon overheats on fast fire",
+                "{}: feuern, bei schnellem Feuer überhitzt die Kanone",
+            ],
+            Text::InvadersPoints => ["Invader: {} point", "Invader: {} Punkt"],
+            Text::InvadersWalls => [
+                "Walls stop shots both ways",
+                "Mauern halten Schüsse in beide Richtungen auf",
+            ],
+            Text::InvadersEnd => [
+                "Getting hit ends the run, so does a cleared wave",
+                "Ein Treffer beendet die Runde, eine besiegte Welle auch",
+            ],
+            Text::EnemiesDestroyed => ["Enemies destroyed: {}", "Zerstörte Gegner: {}"],
+            Text::ShotsFired => ["Shots fired: {}", "Abgefeuerte Schüsse: {}"],
+            Text::Accuracy => ["Accuracy: {}%", "Trefferquote: {}%"],
+
+            Text::PongMove => ["{}: move the plank", "{}: Schläger bewegen"],
+            Text::PongPoint => [
+                "Ball past the computer plank: 1 point",
+                "Ball am Computer vorbei: 1 Punkt",
+            ],
+            Text::PongMiss => [
+                "Ball past your plank: -1 point",
+                "Ball an dir vorbei: -1 Punkt",
+            ],
+            Text::PongEndless => [
+                "The run goes on until you end it",
+                "Die Runde läuft, bis du sie beendest",
+            ],
+            Text::BallsReturned => ["Balls returned: {}", "Zurückgespielte Bälle: {}"],
+            Text::BallsMissed => ["Balls missed: {}", "Verpasste Bälle: {}"],
+
+            Text::ConfigReloaded => ["Config reloaded", "Konfiguration neu geladen"],
+            Text::ConfigError => ["Config error: {}", "Konfigurationsfehler: {}"],
+            Text::ScriptError => ["Script error: {}", "Skriptfehler: {}"],
+            Text::PluginError => ["Plugin error: {}", "Pluginfehler: {}"],
+            Text::SetupNotSaved => ["Setup not saved: {}", "Einrichtung nicht gespeichert: {}"],
+            Text::ThemeNotSaved => ["Theme not saved: {}", "Farben nicht gespeichert: {}"],
+            Text::LanguageNotSaved => ["Language not saved: {}", "Sprache nicht gespeichert: {}"],
+            Text::ColorblindNotSaved => [
+                "Colorblind mode not saved: {}",
+                "Farbenblind-Modus nicht gespeichert: {}",
+            ],
+            Text::ScreenSizeDiffers => [
+                "Screen size differs from the recording",
+                "Die Bildschirmgröße weicht von der Aufnahme ab",
+            ],
+            Text::MacroSaved => [
+                "Practice macro saved ({} s)",
+                "Übungsmakro gespeichert ({} s)",
+            ],
+            Text::MacroNotSaved => [
+                "Practice macro not saved: {}",
+                "Übungsmakro nicht gespeichert: {}",
+            ],
+            Text::AskSeed => ["Seed of the {} run:", "Seed der Runde {}:"],
+            Text::AskSeedAgain => [
+                "Seed of the {} run (a whole number):",
+                "Seed der Runde {} (eine ganze Zahl):",
+            ],
+            Text::SavingFailed => [
+                "Saving failed ({}). Quit anyway?",
+                "Speichern fehlgeschlagen ({}). Trotzdem beenden?",
+            ],
+            Text::SuspendedLost => [
+                "{} is suspended and will be lost. Quit anyway?",
+                "{} ist unterbrochen und geht verloren. Trotzdem beenden?",
+            ],
+            Text::HeatmapsNotSaved => [
+                "Heatmaps are not saved ({}). Quit anyway?",
+                "Heatmaps sind nicht gespeichert ({}). Trotzdem beenden?",
+            ],
+            Text::Replay => ["REPLAY", "WIEDERHOLUNG"],
+            Text::Unbound => ["unbound", "nicht belegt"],
+
+            Text::AutosaveFailed => [
+                "Autosave failed: {}",
+                "Automatisches Speichern fehlgeschlagen: {}",
+            ],
+            Text::RecentScores => ["Recent scores: {}", "Letzte Ergebnisse: {}"],
+            Text::StatisticsNotSaved => [
+                "Statistics not saved: {}",
+                "Statistik nicht gespeichert: {}",
+            ],
+            Text::TelemetryNotWritten => [
+                "Telemetry not written: {}",
+                "Telemetrie nicht geschrieben: {}",
+            ],
+            Text::WeeklyBest => [
+                "New best score of the week {}!",
+                "Neuer Rekord der Woche {}!",
+            ],
+            Text::WeeklyBestNotSaved => [
+                "Best score of the week not saved: {}",
+                "Rekord der Woche nicht gespeichert: {}",
+            ],
+            Text::Grade => ["Grade: {}", "Note: {}"],
+            Text::GradeWithReasons => ["Grade: {} ({})", "Note: {} ({})"],
+            Text::BetterThanRuns => [
+                "better than {}% of your runs",
+                "besser als {}% deiner Runden",
+            ],
+            Text::BestRunYet => ["Your best run yet!", "Deine beste Runde bisher!"],
+            Text::FirstRun => [
+                "First run recorded, the next ones are compared to it",
+                "Erste Runde gespeichert, die nächsten werden mit ihr verglichen",
+            ],
+            Text::BetterRun => ["One of your better runs", "Eine deiner besseren Runden"],
+            Text::BelowUsual => [
+                "Below your usual score, take it slower",
+                "Unter deinem üblichen Ergebnis, lass dir mehr Zeit",
+            ],
+            Text::CloseToUsual => [
+                "Close to your usual score",
+                "Nah an deinem üblichen Ergebnis",
+            ],
+            Text::SnakePerformance => ["apples eaten", "gegessene Äpfel"],
+            Text::SnakeTip => [
+                "Try picking apples up before they pile up",
+                "Sammle die Äpfel ein, bevor sie sich häufen",
+            ],
+            Text::TetrisPerformance => ["cleared", "geräumt"],
+            Text::TetrisTip => [
+                "Try keeping the stack flat, holes stop lines from clearing",
+                "Halte den Stapel flach, Löcher verhindern volle Reihen",
+            ],
+            Text::InvadersPerformance => ["accuracy", "Trefferquote"],
+            Text::InvadersTip => [
+                "Try aiming before firing, missed shots leave you open",
+                "Ziele vor dem Schuss, Fehlschüsse machen dich verwundbar",
+            ],
+            Text::PongPerformance => ["returns", "Rückschläge"],
+            Text::PongTip => [
+                "Try moving under the ball before it comes down",
+                "Stell dich unter den Ball, bevor er herunterkommt",
+            ],
+
+            Text::ControlsInverted => ["Controls inverted!", "Steuerung vertauscht!"],
+            Text::BoardShiftsLeft => [
+                "The board shifts left!",
+                "Das Spielfeld rutscht nach links!",
+            ],
+            Text::BoardShiftsRight => [
+                "The board shifts right!",
+                "Das Spielfeld rutscht nach rechts!",
+            ],
+            Text::WaveWarpsDown => ["The wave warps down!", "Die Welle springt nach unten!"],
+            Text::PlankShrinks => ["Your plank shrinks!", "Dein Schläger schrumpft!"],
+
+            Text::NoRunsRecorded => ["No runs recorded yet.", "Noch keine Runden gespeichert."],
+            Text::BrowseHint => [
+                "Left/Right: switch game, any other key: back",
+                "Links/Rechts: Spiel wechseln, andere Taste: zurück",
+            ],
+            Text::LastRuns => [
+                "Last {} runs: best {}, average {}, last {}",
+                "Letzte {} Runden: bestes {}, Schnitt {}, letztes {}",
+            ],
+            Text::NoRunsFinished => ["No runs finished yet.", "Noch keine Runden beendet."],
+            Text::ColumnGame => ["Game", "Spiel"],
+            Text::ColumnPlayed => ["Played", "Runden"],
+            Text::ColumnBest => ["Best", "Rekord"],
+            Text::ColumnTotalScore => ["Total score", "Punkte ges."],
+            Text::ColumnPlayTime => ["Play time", "Spielzeit"],
+            Text::ColumnLines => ["Lines", "Reihen"],
+            Text::ColumnEnemies => ["Enemies", "Gegner"],
+            Text::AllGames => ["All games", "Alle Spiele"],
+            Text::AnyKeyToReturn => [
+                "Press any key to return.",
+                "Beliebige Taste drücken, um zurückzukehren.",
+            ],
+
+            Text::SetupStep => [
+                "First-run setup, step {} of 4. Esc skips the setup.",
+                "Ersteinrichtung, Schritt {} von 4. Esc überspringt die Einrichtung.",
+            ],
+            Text::SetupColors => ["Colors", "Farben"],
+            Text::SetupSymbols => ["Symbols", "Symbole"],
+            Text::SetupAutoSymbols => [
+                "ASCII where Unicode looks unsupported",
+                "ASCII, wo Unicode nicht unterstützt scheint",
+            ],
+            Text::SetupMovementKeys => ["Movement keys", "Bewegungstasten"],
+            Text::SetupArrows => ["Arrows", "Pfeiltasten"],
+            Text::SetupName => [
+                "Step 4 of 4. Your name (empty for none):",
+                "Schritt 4 von 4. Dein Name (leer für keinen):",
+            ],
+        }
+    }
+
+    pub fn in_language(self, language: Language) -> &'static str {
+        self.texts()[language as usize]
+    }
+}
+
+/// Language of the texts: the one picked in the settings menu, else the configured one.
+pub fn language() -> Language {
+    crate::settings::get()
+        .language
+        .unwrap_or(crate::config::get().language)
+}
+
+/// The text in the current language.
+pub fn tr(text: Text) -> &'static str {
+    text.in_language(language())
+}
+
+/// The text in the current language with its `{}` filled with the arguments in order.
+pub fn format(text: Text, args: &[&dyn Display]) -> String {
+    display(text, args).to_string()
+}
+
+/// `format` written where it is displayed, e.g. into a frame, without a string.
+pub fn display<'a>(text: Text, args: &'a [&'a dyn Display]) -> Formatted<'a> {
+    Formatted {
+        text: tr(text),
+        args,
+    }
+}
+
+/// Text filled with arguments, made by `display`.
+#[derive(Clone, Copy)]
+pub struct Formatted<'a> {
+    text: &'static str,
+    args: &'a [&'a dyn Display],
+}
+
+impl Display for Formatted<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut args = self.args.iter();
+        let mut parts = self.text.split("{}").peekable();
+        while let Some(part) = parts.next() {
+            f.write_str(part)?;
+            if parts.peek().is_some() {
+                if let Some(arg) = args.next() {
+                    arg.fmt(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
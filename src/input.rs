@@ -0,0 +1,305 @@
+//! Player actions and the keys triggering them.
+//!
+//! Games react to actions, not to keys, so keys can be rebound in the config
+//! (see `config::KeyBindings`). Default keys keep working after rebinding.
+//! Mouse input has no action, games wanting it read the mouse event.
+//!
+//! Besides the key, the input of a frame (`FrameInput`) tells which actions
+//! are held down for movement that goes on while a key is held, and how often
+//! each action was pressed since the last frame for quick taps.
+use crate::game::{EXIT_BUTTON, PAUSE_BUTTON, SUSPEND_BUTTON};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, MouseEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+/// Without release events a key is held while the terminal repeats it.
+/// Longer than the usual autorepeat interval.
+pub const REPEAT_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    /// Rotate the figure in tetris, where MoveUp rotates as well
+    Rotate,
+    Pause,
+    /// Leave the run for the menu
+    Suspend,
+    /// End the run
+    Quit,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Source {
+    Key(KeyEvent),
+    /// Click, move or scroll; positions are in screen cells
+    Mouse(MouseEvent),
+}
+
+/// Key pressed (or mouse used) in a frame and the action it triggers.
+#[derive(Clone, Copy, Debug)]
+pub struct Input {
+    pub source: Source,
+    pub action: Option<Action>,
+}
+
+impl Input {
+    /// Action from the configured key bindings.
+    pub fn from_key(key: KeyEvent) -> Self {
+        Self {
+            source: Source::Key(key),
+            action: crate::config::get().keys.action(&key),
+        }
+    }
+
+    /// Action from the default keys only.
+    pub fn from_default_key(key: KeyEvent) -> Self {
+        Self {
+            source: Source::Key(key),
+            action: default_action(key.code),
+        }
+    }
+
+    pub fn from_mouse(mouse: MouseEvent) -> Self {
+        Self {
+            source: Source::Mouse(mouse),
+            action: None,
+        }
+    }
+
+    /// Keys use the configured key bindings. Other terminal events are not input.
+    pub fn from_event(event: Event) -> Option<Self> {
+        match event {
+            Event::Key(key) => Some(Self::from_key(key)),
+            Event::Mouse(mouse) => Some(Self::from_mouse(mouse)),
+            _ => None,
+        }
+    }
+
+    pub fn key(&self) -> Option<KeyEvent> {
+        match self.source {
+            Source::Key(key) => Some(key),
+            Source::Mouse(_) => None,
+        }
+    }
+
+    pub fn mouse(&self) -> Option<MouseEvent> {
+        match self.source {
+            Source::Key(_) => None,
+            Source::Mouse(mouse) => Some(mouse),
+        }
+    }
+}
+
+/// Input of a game for one update, see `Game::update`.
+#[derive(Clone, Debug, Default)]
+pub struct FrameInput {
+    /// Key pressed or mouse used in the frame, only for the first update of the frame
+    pub event: Option<Input>,
+    /// Actions pressed since the last frame, including the one of `event`
+    pub presses: Presses,
+    /// Actions held down in the frame
+    pub held: Vec<Action>,
+}
+
+impl FrameInput {
+    /// Input with only the event, its action pressed once (scripted input, tests).
+    pub fn new(event: Option<Input>) -> Self {
+        Self {
+            presses: action(&event).into(),
+            event,
+            held: vec![],
+        }
+    }
+
+    /// Presses counted by whoever runs the game.
+    /// Without any, the action of the event stays pressed once.
+    pub fn with_presses(mut self, presses: Presses) -> Self {
+        if !presses.is_empty() {
+            self.presses = presses;
+        }
+        self
+    }
+
+    pub fn with_held(mut self, held: Vec<Action>) -> Self {
+        self.held = held;
+        self
+    }
+
+    /// For movement that goes on while a key is held.
+    pub fn is_held(&self, action: Action) -> bool {
+        self.held.contains(&action)
+    }
+
+    /// Action of the key of the frame, if any.
+    pub fn action(&self) -> Option<Action> {
+        action(&self.event)
+    }
+
+    pub fn key(&self) -> Option<KeyEvent> {
+        self.event.and_then(|event| event.key())
+    }
+
+    pub fn mouse(&self) -> Option<MouseEvent> {
+        self.event.and_then(|event| event.mouse())
+    }
+}
+
+/// Action of a key when nothing is rebound.
+pub fn default_action(code: KeyCode) -> Option<Action> {
+    let action = match code {
+        KeyCode::Up => Action::MoveUp,
+        KeyCode::Down => Action::MoveDown,
+        KeyCode::Left => Action::MoveLeft,
+        KeyCode::Right => Action::MoveRight,
+        KeyCode::Char(' ') => Action::Fire,
+        PAUSE_BUTTON => Action::Pause,
+        SUSPEND_BUTTON => Action::Suspend,
+        EXIT_BUTTON => Action::Quit,
+        _ => return None,
+    };
+
+    Some(action)
+}
+
+/// Action of the input, if any.
+pub fn action(input: &Option<Input>) -> Option<Action> {
+    input.and_then(|input| input.action)
+}
+
+/// Wait for the next key press, mouse events and key releases are skipped.
+pub fn recv_key(rx: &Receiver<Event>) -> KeyEvent {
+    loop {
+        match rx.recv() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => return key,
+            Ok(_) => {}
+            Err(_) => panic!("stdin disconnected"),
+        }
+    }
+}
+
+/// Keys held down and their actions.
+///
+/// Terminals with the kitty keyboard protocol report releases (see `term::enter`).
+/// Other terminals only repeat held keys, so a key counts as held
+/// until it is not repeated for `REPEAT_TIMEOUT`.
+#[derive(Debug, Default)]
+pub struct HeldKeys {
+    /// Key, its action and when it was last pressed or repeated
+    keys: Vec<(KeyCode, Action, Instant)>,
+}
+
+impl HeldKeys {
+    /// Keys without an action are not tracked.
+    pub fn update(&mut self, key: &KeyEvent, action: Option<Action>) {
+        self.keys.retain(|(code, ..)| *code != key.code);
+        if let (Some(action), KeyEventKind::Press | KeyEventKind::Repeat) = (action, key.kind) {
+            self.keys.push((key.code, action, Instant::now()));
+        }
+    }
+
+    pub fn actions(&self) -> Vec<Action> {
+        let is_released_reported = crate::term::reports_key_releases();
+        let mut actions = vec![];
+        for &(_, action, pressed) in self.keys.iter() {
+            if (is_released_reported || pressed.elapsed() < REPEAT_TIMEOUT)
+                && !actions.contains(&action)
+            {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+}
+
+/// Actions pressed in a frame and how many times, in the order first pressed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Presses {
+    counts: Vec<(Action, u32)>,
+}
+
+impl Presses {
+    pub fn add(&mut self, action: Action) {
+        match self
+            .counts
+            .iter_mut()
+            .find(|(pressed, _)| *pressed == action)
+        {
+            Some((_, count)) => *count += 1,
+            None => self.counts.push((action, 1)),
+        }
+    }
+
+    /// Add the presses of a later frame.
+    pub fn extend(&mut self, other: &Presses) {
+        for &(action, count) in other.counts.iter() {
+            for _ in 0..count {
+                self.add(action);
+            }
+        }
+    }
+
+    pub fn count(&self, action: Action) -> u32 {
+        self.counts
+            .iter()
+            .find(|(pressed, _)| *pressed == action)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Action, u32)> + '_ {
+        self.counts.iter().copied()
+    }
+}
+
+impl From<Option<Action>> for Presses {
+    fn from(action: Option<Action>) -> Self {
+        Self {
+            counts: action.into_iter().map(|action| (action, 1)).collect(),
+        }
+    }
+}
+
+/// Recognizes a key sequence typed without other keys in between.
+#[derive(Debug, Clone)]
+pub struct SequenceMatcher {
+    sequence: Vec<KeyCode>,
+    /// Last keys, as many as in the sequence
+    recent: VecDeque<KeyCode>,
+}
+
+impl SequenceMatcher {
+    pub fn new(sequence: Vec<KeyCode>) -> Self {
+        Self {
+            recent: VecDeque::with_capacity(sequence.len()),
+            sequence,
+        }
+    }
+
+    /// Returns true when the key completes the sequence. Empty sequences never match.
+    pub fn push(&mut self, code: KeyCode) -> bool {
+        if self.sequence.is_empty() {
+            return false;
+        }
+        if self.recent.len() == self.sequence.len() {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(code);
+
+        let is_matched = self.recent.iter().eq(self.sequence.iter());
+        if is_matched {
+            self.recent.clear();
+        }
+        is_matched
+    }
+}
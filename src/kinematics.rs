@@ -0,0 +1,60 @@
+//! Moving bodies: a position driven by a velocity driven by an acceleration.
+//! Shared by the pong ball and the invaders bullets, and by anything falling
+//! or thrusting, like a lander or a flapping bird.
+use crate::point::Point;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Point that moves on its own. Velocity is per time unit of the steps,
+/// acceleration changes the velocity by as much each time unit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Body<Basis: Copy> {
+    pub position: Point<Basis>,
+    pub velocity: Point<Basis>,
+    #[serde(default = "still")]
+    pub acceleration: Point<Basis>,
+}
+
+fn still<Basis: Copy>() -> Point<Basis> {
+    Point::new(0.0, 0.0)
+}
+
+impl<Basis: Copy> Body<Basis> {
+    /// Body moving at a constant velocity.
+    pub fn new(position: Point<Basis>, velocity: Point<Basis>) -> Self {
+        Self {
+            position,
+            velocity,
+            acceleration: still(),
+        }
+    }
+
+    pub fn with_acceleration(self, acceleration: Point<Basis>) -> Self {
+        Self {
+            acceleration,
+            ..self
+        }
+    }
+
+    /// Velocity after `dt` time units.
+    pub fn next_velocity(&self, dt: f32) -> Point<Basis> {
+        self.velocity + self.acceleration * dt
+    }
+
+    /// Position after `dt` time units, the body is not moved.
+    /// The new velocity moves the body, so it stays stable with big steps.
+    pub fn next_position(&self, dt: f32) -> Point<Basis> {
+        self.position + self.next_velocity(dt) * dt
+    }
+
+    /// Move the body by `dt` time units.
+    pub fn step(&mut self, dt: f32) {
+        self.velocity = self.next_velocity(dt);
+        self.position += self.velocity * dt;
+    }
+
+    /// Move the body by the time passed, the velocity is per second.
+    pub fn integrate(&mut self, delta_time: &Duration) {
+        self.step(delta_time.as_secs_f32());
+    }
+}
@@ -0,0 +1,135 @@
+//! Screen regions: a game splits the frame into rectangles for its board,
+//! panels and frames instead of counting offsets from its other parts.
+//!
+//! Layouts are computed from the frame on every draw, so they follow
+//! terminal resizes. Regions that don't fit are cut, down to nothing.
+use crate::render::FrameBuffer;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Side by side, from the left
+    Horizontal,
+    /// One under another, from the top
+    Vertical,
+}
+
+/// Size of a part along the split direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Size {
+    /// Exactly this many symbols, if there is space left
+    Fixed(u16),
+    /// Share of the space left after the fixed parts, by weight
+    Flex(u16),
+}
+
+impl Rect {
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The whole frame.
+    pub fn of_frame(frame: &FrameBuffer) -> Self {
+        Self::new(0, 0, frame.width(), frame.height())
+    }
+
+    pub fn right(&self) -> u16 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> u16 {
+        self.y + self.height
+    }
+
+    /// Rectangle inside margins of the given sizes.
+    pub fn shrink(self, left: u16, top: u16, right: u16, bottom: u16) -> Self {
+        let width = self.width.saturating_sub(left + right);
+        let height = self.height.saturating_sub(top + bottom);
+        Self::new(
+            (self.x + left).min(self.right()),
+            (self.y + top).min(self.bottom()),
+            width,
+            height,
+        )
+    }
+
+    /// Inside of a frame with borders `width` symbols wide and `height` high.
+    pub fn inside_border(self, width: u16, height: u16) -> Self {
+        self.shrink(width, height, width, height)
+    }
+
+    /// Rectangle of the given size in the middle.
+    pub fn centered(self, width: u16, height: u16) -> Self {
+        let (width, height) = (width.min(self.width), height.min(self.height));
+        Self::new(
+            self.x + (self.width - width) / 2,
+            self.y + (self.height - height) / 2,
+            width,
+            height,
+        )
+    }
+
+    /// Cut into parts of the given sizes. Fixed parts come first when
+    /// there is not enough space, what is left after the flexible ones
+    /// goes to the last of them.
+    pub fn split<const N: usize>(self, direction: Direction, sizes: [Size; N]) -> [Rect; N] {
+        let total = match direction {
+            Direction::Horizontal => self.width,
+            Direction::Vertical => self.height,
+        };
+
+        // Fixed parts, cut by the space left
+        let mut lengths = [0; N];
+        let mut left = total;
+        for (length, size) in lengths.iter_mut().zip(sizes) {
+            if let Size::Fixed(fixed) = size {
+                *length = fixed.min(left);
+                left -= *length;
+            }
+        }
+
+        // Flexible parts
+        let weights: u32 = sizes
+            .iter()
+            .map(|size| match size {
+                Size::Flex(weight) => *weight as u32,
+                Size::Fixed(_) => 0,
+            })
+            .sum();
+        let last_flex = sizes.iter().rposition(|size| matches!(size, Size::Flex(_)));
+        let flexible = left as u32;
+        for (length, size) in lengths.iter_mut().zip(sizes) {
+            if let Size::Flex(weight) = size {
+                *length = (flexible * weight as u32)
+                    .checked_div(weights)
+                    .unwrap_or_default() as u16;
+                left -= *length;
+            }
+        }
+        if let Some(last_flex) = last_flex {
+            lengths[last_flex] += left;
+        }
+
+        let mut offset = 0;
+        lengths.map(|length| {
+            let part = match direction {
+                Direction::Horizontal => Self::new(self.x + offset, self.y, length, self.height),
+                Direction::Vertical => Self::new(self.x, self.y + offset, self.width, length),
+            };
+            offset += length;
+            part
+        })
+    }
+}
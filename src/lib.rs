@@ -0,0 +1,74 @@
+//! Terminal games: snake, tetris, space invaders and pong, with their menus,
+//! statistics and tools. The `cl_games` binary plays them, the library lets
+//! other programs run and embed them, see `prelude`.
+pub mod anim;
+pub mod challenge;
+pub mod chaos;
+pub mod chart;
+pub mod cheats;
+pub mod collision;
+pub mod config;
+pub mod debug;
+pub mod demo;
+pub mod dialog;
+pub mod difficulty;
+pub mod effects;
+pub mod frame;
+pub mod game;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod glyphs;
+pub mod harness;
+pub mod headless;
+pub mod heatmap;
+pub mod help;
+pub mod hud;
+pub mod i18n;
+pub mod input;
+pub mod kinematics;
+pub mod layout;
+pub mod lifetime;
+pub mod logging;
+pub mod menu;
+pub mod pacing;
+pub mod plugin;
+pub mod point;
+#[cfg(feature = "pong")]
+pub mod pong;
+pub mod practice;
+pub mod prelude;
+pub mod preview;
+pub mod profiling;
+pub mod registry;
+pub mod render;
+pub mod replay;
+pub mod save;
+pub mod script;
+pub mod settings;
+pub mod setup;
+#[cfg(feature = "snake")]
+pub mod snake;
+pub mod snapshot;
+pub mod sound;
+#[cfg(feature = "invaders")]
+pub mod space_invaders;
+pub mod stats;
+pub mod status;
+pub mod summary;
+pub mod telemetry;
+pub mod term;
+#[cfg(feature = "tetris")]
+pub mod tetris;
+pub mod text_input;
+pub mod theme;
+pub mod ui;
+pub mod util;
+pub mod versus;
+
+#[cfg(not(any(
+    feature = "snake",
+    feature = "tetris",
+    feature = "invaders",
+    feature = "pong"
+)))]
+compile_error!("at least one game feature must be enabled");
@@ -0,0 +1,189 @@
+//! Statistics of all finished runs, kept in `<data dir>/cl_games/lifetime.toml`,
+//! and the statistics screen.
+use crate::frame::Frame;
+use crate::i18n::{tr, Text};
+use crate::layout::Rect;
+use crate::render::{FrameBuffer, Layer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const FILE_NAME: &str = "lifetime.toml";
+
+/// Things done in a run that only some games count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Counts {
+    pub lines_cleared: u64,
+    pub enemies_destroyed: u64,
+}
+
+/// Totals of one game.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameTotals {
+    pub games_played: u64,
+    pub total_score: i64,
+    pub best_score: Option<i64>,
+    /// Seconds, pauses excluded
+    pub play_time: f64,
+    pub counts: Counts,
+}
+
+impl GameTotals {
+    fn add(&mut self, other: &GameTotals) {
+        self.games_played += other.games_played;
+        self.total_score += other.total_score;
+        self.best_score = self.best_score.max(other.best_score);
+        self.play_time += other.play_time;
+        self.counts.lines_cleared += other.counts.lines_cleared;
+        self.counts.enemies_destroyed += other.counts.enemies_destroyed;
+    }
+}
+
+/// Totals of every game by name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Lifetime {
+    games: BTreeMap<String, GameTotals>,
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+impl Lifetime {
+    /// Missing or broken file means nothing played yet.
+    pub fn load() -> Self {
+        path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path = path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+
+    /// Best score of the game, None if it wasn't played yet.
+    pub fn best_score(&self, game_name: &str) -> Option<i64> {
+        self.games
+            .get(game_name)
+            .and_then(|totals| totals.best_score)
+    }
+
+    /// Count a finished run.
+    pub fn add(&mut self, game_name: &str, score: i64, played: Duration, counts: Counts) {
+        self.games
+            .entry(game_name.to_owned())
+            .or_default()
+            .add(&GameTotals {
+                games_played: 1,
+                total_score: score,
+                best_score: Some(score),
+                play_time: played.as_secs_f64(),
+                counts,
+            });
+    }
+}
+
+/// Hours, minutes and seconds like `1:02:03`.
+pub fn format_time(secs: f64) -> String {
+    Clock(secs as u64).to_string()
+}
+
+/// Whole seconds shown as `format_time` does, written without a string.
+#[derive(Clone, Copy, Debug)]
+pub struct Clock(pub u64);
+
+impl std::fmt::Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let secs = self.0;
+        write!(f, "{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    }
+}
+
+/// Show the totals of every game and of all of them. Any key returns.
+pub fn show(
+    out: &mut std::io::Stdout,
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+) -> crossterm::Result<()> {
+    use crossterm::event::KeyEventKind;
+
+    let lifetime = Lifetime::load();
+    let mut all = GameTotals::default();
+    for totals in lifetime.games.values() {
+        all.add(totals);
+    }
+    let row = |name: &str, totals: &GameTotals| {
+        format!(
+            "{:<16}{:>8}{:>10}{:>13}{:>12}{:>8}{:>10}",
+            name,
+            totals.games_played,
+            totals
+                .best_score
+                .map_or_else(|| "-".to_owned(), |score| score.to_string()),
+            totals.total_score,
+            format_time(totals.play_time),
+            totals.counts.lines_cleared,
+            totals.counts.enemies_destroyed,
+        )
+    };
+
+    let mut lines = Vec::new();
+    if lifetime.games.is_empty() {
+        lines.push(tr(Text::NoRunsFinished).to_owned());
+    } else {
+        lines.push(format!(
+            "{:<16}{:>8}{:>10}{:>13}{:>12}{:>8}{:>10}",
+            tr(Text::ColumnGame),
+            tr(Text::ColumnPlayed),
+            tr(Text::ColumnBest),
+            tr(Text::ColumnTotalScore),
+            tr(Text::ColumnPlayTime),
+            tr(Text::ColumnLines),
+            tr(Text::ColumnEnemies)
+        ));
+        lines.extend(
+            lifetime
+                .games
+                .iter()
+                .map(|(name, totals)| row(name, totals)),
+        );
+        lines.push(row(tr(Text::AllGames), &all));
+    }
+    let hint = tr(Text::AnyKeyToReturn);
+    let area = Rect::new(
+        0,
+        0,
+        lines.iter().map(String::len).max().unwrap_or(0) as u16 + 2,
+        lines.len() as u16 + 2,
+    );
+
+    let mut frame = FrameBuffer::from_terminal()?;
+    loop {
+        let (width, height) = crossterm::terminal::size()?;
+        frame.clear(width, height);
+        let inner = Frame::new()
+            .title(tr(Text::Statistics))
+            .draw(&mut frame, Layer::Hud, area);
+        for (y, line) in lines.iter().enumerate() {
+            frame.print(Layer::Hud, inner.x, inner.y + y as u16, line);
+        }
+        frame.print(Layer::Hud, 0, area.bottom() + 1, hint);
+        frame.flush(out)?;
+
+        if crate::input::recv_key(rx).kind != KeyEventKind::Release {
+            break;
+        }
+    }
+
+    Ok(())
+}
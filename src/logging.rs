@@ -0,0 +1,118 @@
+//! Log file of what happens in the games: runs starting and ending, collisions,
+//! saving errors and such. Lines go to `<data dir>/cl_games/cl_games.log`,
+//! never to the terminal the games draw on. The level is the `log_level`
+//! config value, or for one start
+//!
+//! ```text
+//! cl_games --log debug
+//! ```
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::level_filters::LevelFilter;
+
+pub const FLAG: &str = "--log";
+pub const FILE_NAME: &str = "cl_games.log";
+
+/// Least important lines written, from `Error` only to everything with `Trace`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Off,
+    Error,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub const ALL: [Level; 6] = [
+        Level::Off,
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Off => "off",
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+
+    fn filter(self) -> LevelFilter {
+        match self {
+            Level::Off => LevelFilter::OFF,
+            Level::Error => LevelFilter::ERROR,
+            Level::Warn => LevelFilter::WARN,
+            Level::Info => LevelFilter::INFO,
+            Level::Debug => LevelFilter::DEBUG,
+            Level::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+impl std::str::FromStr for Level {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Level::ALL.iter().map(|level| level.name()).collect();
+                format!(
+                    "unknown log level {:?}, expected {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Take `--log <level>` out of the arguments, so the rest can be read without it.
+pub fn take_flag(args: &mut Vec<String>) -> Result<Option<Level>, String> {
+    let Some(ind) = args.iter().position(|arg| arg == FLAG) else {
+        return Ok(None);
+    };
+    args.remove(ind);
+    if ind == args.len() {
+        return Err(format!("{} needs a level", FLAG));
+    }
+    args.remove(ind).parse().map(Some)
+}
+
+/// `<data dir>/cl_games/cl_games.log`
+pub fn path() -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+/// Start writing the log. Lines are written by a background thread
+/// until the returned guard is dropped, so keep it until the exit.
+/// None if the level is off or the file can't be opened.
+pub fn init(level: Level) -> Option<tracing_appender::non_blocking::WorkerGuard> {
+    if level == Level::Off {
+        return None;
+    }
+    let dir = crate::config::data_dir()?;
+    std::fs::create_dir_all(&dir).ok()?;
+    let appender = tracing_appender::rolling::RollingFileAppender::builder()
+        .filename_prefix(FILE_NAME)
+        .build(dir)
+        .ok()?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_max_level(level.filter())
+        .with_span_events(crate::profiling::SPAN_EVENTS)
+        .try_init()
+        .ok()?;
+    Some(guard)
+}
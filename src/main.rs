@@ -1,170 +1,960 @@
-extern crate static_assertions as sa;
-pub mod game;
-pub mod point;
-pub mod pong;
-pub mod snake;
-pub mod space_invaders;
-pub mod tetris;
-pub mod util;
+#[cfg(feature = "gamepad")]
+use cl_games::gamepad;
+use cl_games::{
+    anim, challenge, chaos, chart, cheats, config, debug, demo, dialog, difficulty, effects, game,
+    headless, heatmap, help, hud, i18n, input, layout, lifetime, logging, menu, pacing, plugin,
+    point, practice, preview, registry, render, replay, save, script, settings, setup, sound,
+    stats, summary, telemetry, term, theme, util, versus,
+};
+use i18n::{tr, Text};
 
-enum MenuChoice {
-    Exit = 0,
-    SnakeGame = 1,
-    TetrisGame = 2,
-    SpaceInvadersGame = 3,
-    Pong,
+/// Menu entry
+#[derive(Clone, Copy)]
+enum Choice {
+    /// Index in the games registry
+    Game(usize),
+    /// Index in the games registry of the game to play from a seed the player enters
+    Seeded(usize),
+    /// Index in the discovered plugins
+    Plugin(usize),
+    /// Indices in the games registry of the left and the right games
+    Versus(usize, usize),
+    /// Index in the games registry of the game to record a practice macro in
+    RecordMacro(usize),
+    /// Index in the games registry of the game to practice from its macro
+    Practice(usize),
+    /// Challenge of the week
+    Challenge,
+    Heatmaps,
+    Statistics,
+    ScoreTrends,
+    /// Theme picked in the settings menu, None to follow the config
+    Theme(Option<theme::Name>),
+    /// Colorblind mode picked in the settings menu, None to follow the config
+    Colorblind(Option<bool>),
+    /// Language picked in the settings menu, None to follow the config
+    Language(Option<i18n::Language>),
+    Resume,
+    SaveAndQuit,
+    Continue,
+    Exit,
+}
+
+/// Title over the main menu.
+const BANNER: &[&str] = &[
+    r"  ____ _        ____                           ",
+    r" / ___| |      / ___| __ _ _ __ ___   ___  ___ ",
+    r"| |   | |     | |  _ / _` | '_ ` _ \ / _ \/ __|",
+    r"| |___| |___  | |_| | (_| | | | | | |  __/\__ \",
+    r" \____|_____|  \____|\__,_|_| |_| |_|\___||___/",
+    "",
+];
+
+/// How often the config file is checked for changes during a run.
+const CONFIG_CHECK_PERIOD: std::time::Duration = std::time::Duration::from_secs(1);
+/// Scores in the sparkline of the game over screen.
+const RECENT_SCORES_SHOWN: u16 = 20;
+/// How long messages at the bottom of the screen are shown.
+const TOAST_TIME: std::time::Duration = std::time::Duration::from_secs(3);
 
-    #[allow(dead_code)]
-    LastElement, // for static check
+fn toast_tween() -> anim::Tween {
+    anim::Tween::new(TOAST_TIME, anim::Ease::Linear)
 }
 
-const BETWEEN_FRAMES_TIME_MS: u64 = 1000 / 60;
+/// Game being played with its replay.
+/// Kept alive in the menu while suspended.
+struct Run {
+    name: String,
+    game: Box<dyn game::Game>,
+    replay: replay::ReplayBuffer,
+    /// Game id and the practice macro being recorded
+    recording: Option<(&'static str, practice::InputMacro)>,
+    /// Weekly challenge the run's score counts for
+    challenge: Option<challenge::Challenge>,
+    /// Time the game was updated, pauses excluded
+    played: std::time::Duration,
+    /// Seed the game was made from, None for plugins and continued runs
+    seed: Option<u64>,
+}
+
+impl Run {
+    fn new(name: String, game: Box<dyn game::Game>) -> Self {
+        Self {
+            name,
+            game,
+            replay: replay::ReplayBuffer::new(replay::LENGTH),
+            recording: None,
+            challenge: None,
+            played: std::time::Duration::ZERO,
+            seed: None,
+        }
+    }
+
+    /// Run of the game made from the seed.
+    fn seeded(entry: &registry::GameEntry, seed: u64) -> Self {
+        let (width, height) = util::screen_size();
+        let mut run = Self::new(
+            entry.name.to_owned(),
+            (entry.create)(
+                rand::SeedableRng::seed_from_u64(seed),
+                hud::play_area(width, height),
+            ),
+        );
+        run.seed = Some(seed);
+        run
+    }
+
+    /// Save the run to be resumed from the menu after the next start.
+    /// False if the game can't be saved or writing failed.
+    fn keep_for_later(&self) -> bool {
+        self.save_file().is_some_and(|file| file.store().is_ok())
+    }
+
+    fn save_file(&self) -> Option<save::SaveFile> {
+        Some(save::SaveFile {
+            name: self.name.clone(),
+            score: self.game.get_score().value,
+            game: self.game.save()?,
+        })
+    }
+}
 
 fn main() -> crossterm::Result<()> {
-    use crossterm::{cursor, event::read, execute, terminal};
-    use game::Game;
-    use point::Point;
-    use snake::SnakeGame;
-    use terminal::{Clear, ClearType};
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let log_level = match logging::take_flag(&mut args) {
+        Ok(level) => level,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(2);
+        }
+    };
+    if args.first().map(String::as_str) == Some(headless::FLAG) {
+        let _log_guard = log_level.and_then(logging::init);
+        run_headless(&args[1..]);
+        return Ok(());
+    }
+    if args.first().map(String::as_str) == Some(telemetry::COMMAND) {
+        run_stats_command(&args[1..]);
+        return Ok(());
+    }
+    if args.first().map(String::as_str) == Some(config::COMMAND) {
+        if let Err(error) = config::run_command(&args[1..]) {
+            eprintln!("{}", error);
+            std::process::exit(2);
+        }
+        return Ok(());
+    }
 
     let mut stdout = std::io::stdout();
+    term::install_panic_hook();
+    term::enter(&mut stdout)?;
+    let result = run_interactive(&mut stdout, log_level);
+    term::leave(&mut stdout)?;
+    result
+}
+
+/// Menus and runs until the player exits.
+/// `log_level` from the command line wins over the configured one.
+fn run_interactive(
+    stdout: &mut std::io::Stdout,
+    log_level: Option<logging::Level>,
+) -> crossterm::Result<()> {
+    use crossterm::terminal;
+
+    let mut config_error = config::load().err();
+    point::set_cell_aspect(config::get().cell_aspect);
+    if config::get().mouse {
+        term::capture_mouse(stdout)?;
+    }
     let stdin_chan = spawn_stdin_channel();
+    let games = registry::Registry::builtin();
+    let plugins = plugin::discover(&plugin::plugins_dir());
+    let script_errors = script::load_scripts();
+    let mut unsaved_heat = heatmap::Unsaved::default();
+    // Levels of the games played this session
+    let mut difficulty_trackers: std::collections::HashMap<String, difficulty::Tracker> =
+        Default::default();
+    let mut suspended: Option<Run> = None;
+    // Debug overlay stays on from run to run
+    let mut is_debug_shown = false;
+    // Shown over the main menu once
+    let mut menu_notice: Option<String> = None;
+    // Picked instead of the menu after "play again" on the game over screen
+    let mut restart: Option<(Choice, String)> = None;
+    // Offered when the player enters a seed
+    let mut last_seed: Option<u64> = None;
+
+    if setup::is_first_run() {
+        if let Some(choices) = setup::ask(stdout, &stdin_chan)? {
+            if let Err(err) = choices.save() {
+                menu_notice = Some(i18n::format(Text::SetupNotSaved, &[&err]));
+            }
+            config_error = None;
+        }
+    }
+    // After the setup, which only shows without the data directory
+    let _log_guard = logging::init(log_level.unwrap_or(config::get().log_level));
+    if let Some(error) = &config_error {
+        tracing::warn!("config error: {}", error);
+    }
+    for error in &script_errors {
+        tracing::warn!("script error: {}", error);
+    }
 
     'main_loop: loop {
-        // Create all games on stack
-        let (mut snake, mut tetris, mut space_invaders, mut pong);
+        if term::is_quit_requested() {
+            // Suspended run and the heatmaps are kept if possible
+            if let Some(run) = &suspended {
+                run.keep_for_later();
+            }
+            _ = unsaved_heat.save();
+            break 'main_loop;
+        }
+        if let Some(result) = config::reload_if_changed() {
+            config_error = result.err();
+        }
+        let saved = save::SaveFile::load().map(|file| (file.name, file.score));
+        let weekly = challenge::Challenge::of_week(challenge::Week::current(), &games);
+        let weekly_best =
+            weekly.and_then(|weekly| challenge::BestScores::load().get(&weekly, &games));
+        let main_menu = main_menu(
+            &games,
+            &plugins,
+            suspended.as_ref(),
+            saved.as_ref().map(|(name, score)| (name.as_str(), *score)),
+            weekly.map(|weekly| (weekly.entry(&games), weekly_best)),
+        );
+
+        // Read player choice
+        let (choice, game_name) = if let Some(restart) = restart.take() {
+            restart
+        } else {
+            let header: Vec<String> = settings::get()
+                .name
+                .map(|name| i18n::format(Text::Hello, &[&name]))
+                .into_iter()
+                .chain(
+                    config_error
+                        .iter()
+                        .map(|error| i18n::format(Text::ConfigError, &[error])),
+                )
+                .chain(
+                    script_errors
+                        .iter()
+                        .map(|error| i18n::format(Text::ScriptError, &[error])),
+                )
+                .chain(menu_notice.take())
+                .collect();
+
+            // Codes follow the config reloaded during runs
+            let mut cheat_codes = cheats::Codes::from_config();
+            let on_key = &mut |key: &crossterm::event::KeyEvent| {
+                cheat_codes.push(key.code).map(cheats::apply)
+            };
+            let on_idle = &mut |out: &mut std::io::Stdout, rx: &_| demo::play(out, rx, &games);
+            let idle = demo::idle_time().map(|time| (time, on_idle as &mut menu::Idle));
+            match main_menu.run_idle(stdout, &stdin_chan, &header, on_key, idle)? {
+                Some((name, choice)) => (choice, name),
+                None => (Choice::Exit, String::new()),
+            }
+        };
+
+        // Shown when the run starts
+        let mut notice: Option<String> = None;
 
         // Make game from player choice
-        let game: &mut dyn Game = match {
-            let mut choice;
-            'input_read: loop {
-                execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
-                println!("Choose a game:");
-                println!("   {}. Exit", MenuChoice::Exit as usize);
-                println!("   {}. Snake", MenuChoice::SnakeGame as usize);
-                println!("   {}. Tetris", MenuChoice::TetrisGame as usize);
-                println!(
-                    "   {}. Space invaders",
-                    MenuChoice::SpaceInvadersGame as usize
-                );
-                println!("   {}. Pong", MenuChoice::Pong as usize);
-
-                choice = read_game_choice();
-
-                if choice.is_some() {
-                    break 'input_read;
-                }
-            }
-            choice
-        } {
-            Some(MenuChoice::SnakeGame) => {
-                snake = SnakeGame::new(Point::new(10.0, 10.0));
-                &mut snake
-            }
-            Some(MenuChoice::TetrisGame) => {
-                tetris = tetris::TetrisGame::new();
-                &mut tetris
-            }
-            Some(MenuChoice::SpaceInvadersGame) => {
-                let (w, h) = crossterm::terminal::size().expect("Failed to get terminal size");
-
-                space_invaders = space_invaders::SpaceInvadersGame::new(
-                    h,
-                    w,
-                    space_invaders::EnemyPreset::RandomFire,
-                    space_invaders::PropsPreset::Wall,
-                );
-                &mut space_invaders
-            }
-            Some(MenuChoice::Pong) => {
-                pong = pong::PongGame::new();
-                &mut pong
-            }
-            Some(MenuChoice::Exit) => break 'main_loop,
-            Some(MenuChoice::LastElement) | None => unreachable!(),
+        let mut run = match choice {
+            Choice::Game(ind) => {
+                let entry = games.get(ind).expect("menu lists registered games");
+                // Short enough to be shared
+                Run::seeded(entry, rand::random::<u32>() as u64)
+            }
+            Choice::Seeded(ind) => {
+                let entry = games.get(ind).expect("menu lists registered games");
+                let Some(seed) = ask_seed(stdout, &stdin_chan, entry.name, last_seed)? else {
+                    continue 'main_loop;
+                };
+                Run::seeded(entry, seed)
+            }
+            Choice::Plugin(ind) => match plugins[ind].spawn() {
+                Ok(plugin) => Run::new(game_name.clone(), Box::new(plugin)),
+                // A broken or missing plugin leaves the rest of the games playable
+                Err(error) => {
+                    tracing::warn!("plugin {} failed to start: {}", game_name, error);
+                    menu_notice = Some(i18n::format(Text::PluginError, &[&error]));
+                    continue 'main_loop;
+                }
+            },
+            Choice::RecordMacro(ind) => {
+                let entry = games.get(ind).expect("menu lists registered games");
+                // Practice runs get the same game from the seed
+                let seed = rand::random();
+                let mut run = Run::seeded(entry, seed);
+                run.recording = Some((entry.id, practice::InputMacro::new(seed)));
+                run
+            }
+            Choice::Practice(ind) => {
+                let entry = games.get(ind).expect("menu lists registered games");
+                let Some(input_macro) = practice::InputMacro::load(entry.id) else {
+                    continue 'main_loop;
+                };
+                let mut run = Run::seeded(entry, input_macro.seed);
+                if input_macro.screen_size != util::screen_size() {
+                    notice = Some(tr(Text::ScreenSizeDiffers).to_owned());
+                }
+                input_macro.apply(run.game.as_mut());
+                run
+            }
+            Choice::Challenge => {
+                let Some(weekly) = weekly else {
+                    continue 'main_loop;
+                };
+                let entry = weekly.entry(&games);
+                let mut run = Run::seeded(entry, weekly.seed);
+                run.challenge = Some(weekly);
+                run
+            }
+            Choice::Resume => match suspended.take() {
+                Some(run) => run,
+                None => continue 'main_loop,
+            },
+            Choice::Continue => match save::SaveFile::load() {
+                Some(file) => {
+                    // Saved run can be continued once
+                    _ = save::SaveFile::remove();
+                    Run::new(file.name, file.game.into_game())
+                }
+                None => continue 'main_loop,
+            },
+            Choice::SaveAndQuit => {
+                let Some(file) = suspended.as_ref().and_then(Run::save_file) else {
+                    continue 'main_loop;
+                };
+                let is_saved = match file.store() {
+                    Ok(()) => true,
+                    Err(err) => dialog::confirm(
+                        stdout,
+                        &stdin_chan,
+                        &i18n::format(Text::SavingFailed, &[&err]),
+                    )?,
+                };
+                if is_saved {
+                    suspended = None;
+                    if confirm_exit(stdout, &stdin_chan, &mut unsaved_heat, None)? {
+                        break 'main_loop;
+                    }
+                }
+                continue 'main_loop;
+            }
+            Choice::Versus(left, right) => {
+                let left = games.get(left).expect("menu lists registered games");
+                let right = games.get(right).expect("menu lists registered games");
+                versus::play(stdout, &stdin_chan, left, right)?;
+                continue 'main_loop;
+            }
+            Choice::Heatmaps => {
+                heatmap::show(stdout, &stdin_chan)?;
+                continue 'main_loop;
+            }
+            Choice::Statistics => {
+                lifetime::show(stdout, &stdin_chan)?;
+                continue 'main_loop;
+            }
+            Choice::ScoreTrends => {
+                stats::show(stdout, &stdin_chan)?;
+                continue 'main_loop;
+            }
+            Choice::Theme(name) => {
+                if let Err(err) = settings::update(|settings| settings.theme = name) {
+                    menu_notice = Some(i18n::format(Text::ThemeNotSaved, &[&err]));
+                }
+                continue 'main_loop;
+            }
+            Choice::Language(language) => {
+                if let Err(err) = settings::update(|settings| settings.language = language) {
+                    menu_notice = Some(i18n::format(Text::LanguageNotSaved, &[&err]));
+                }
+                continue 'main_loop;
+            }
+            Choice::Colorblind(is_on) => {
+                if let Err(err) = settings::update(|settings| settings.colorblind = is_on) {
+                    menu_notice = Some(i18n::format(Text::ColorblindNotSaved, &[&err]));
+                }
+                continue 'main_loop;
+            }
+            Choice::Exit => {
+                // Suspended run is resumed after the next start if it can be saved
+                let is_kept = suspended.as_ref().is_some_and(Run::keep_for_later);
+                let lost_name = suspended
+                    .as_ref()
+                    .filter(|_| !is_kept)
+                    .map(|run| run.name.as_str());
+                if confirm_exit(stdout, &stdin_chan, &mut unsaved_heat, lost_name)? {
+                    break 'main_loop;
+                }
+                if is_kept {
+                    _ = save::SaveFile::remove();
+                }
+                continue 'main_loop;
+            }
         };
 
-        let mut prev_time = std::time::SystemTime::now();
+        // Same game again from the game over screen
+        let restart_choice = match choice {
+            Choice::Resume | Choice::Continue => games
+                .games()
+                .iter()
+                .position(|entry| entry.name == run.name)
+                .map(Choice::Game),
+            choice => Some(choice),
+        };
+
+        // Runs made now show where they came from
+        if !matches!(choice, Choice::Resume | Choice::Continue) {
+            if let Some(seed) = run.seed {
+                last_seed = Some(seed);
+                notice = notice.or_else(|| Some(i18n::format(Text::Seed, &[&seed])));
+            }
+        }
+
+        tracing::info!(game = %run.name, seed = ?run.seed, "run started");
+
+        // New game replaces the suspended one
+        if let Some(mut old) = suspended.take() {
+            unsaved_heat.add(&old.name, old.game.take_heat_points());
+        }
+
+        let mut prev_time = std::time::Instant::now();
+        // Resumed run waits for the player to unpause
+        let mut is_paused = matches!(
+            choice,
+            Choice::Resume | Choice::Continue | Choice::Practice(_)
+        );
+        // The quit key asks first, the run is frozen until the answer
+        let mut is_quit_asked = false;
+        // The run is frozen while the help is shown too
+        let mut is_help_shown = false;
+        let help = run.game.help();
+        let mut frame = render::FrameBuffer::from_terminal()?;
+        // Filled by the game every frame
+        let mut hud = hud::Hud::new();
+        // Resumed and loaded runs may come back to a resized terminal
+        run.game
+            .resize(hud::play_area(frame.width(), frame.height()));
+        let mut since_config_check = std::time::Duration::ZERO;
+        // Message at the bottom of the screen and its time left
+        let mut toast: Option<(String, anim::Tween)> = notice.map(|text| (text, toast_tween()));
+        let mut chaos = chaos::Scheduler::new(rand::SeedableRng::from_entropy());
+        let is_chaos_unlocked = cheats::Unlocks::load().has(cheats::Unlock::Chaos);
+        // Chaos event announcement and its time left
+        let mut banner: Option<(String, anim::Tween)> = None;
+        let mut effects = effects::Effects::default();
+        let mut held = input::HeldKeys::default();
+        // Config changes apply from the next run
+        let mut sound = sound::from_config();
+        // Written while the next frame is simulated
+        let renderer = render::RenderThread::spawn();
+        let autosaver = save::Autosaver::spawn();
+        let mut since_autosave = std::time::Duration::ZERO;
+        // A finished run leaves no save behind
+        let mut is_autosaved = false;
+        let tracker = difficulty_trackers.entry(run.name.clone()).or_default();
+        // Level of the last update, None with the difficulty fixed
+        let mut difficulty_level = None;
+        let mut timings = debug::Timings::default();
+        let mut budget = pacing::FrameBudget::default();
+        let mut steps = pacing::FixedStep::default();
+        let mut delta_filter = pacing::DeltaFilter::default();
+        // Key and presses of frames without an update
+        let mut waiting_input = None;
+        let mut waiting_presses = input::Presses::default();
 
-        'game_loop: loop {
+        let is_suspended = 'game_loop: loop {
             use std::thread;
-            use std::time::Duration;
-            use std::time::SystemTime;
 
-            let current_time = SystemTime::now();
-            let delta = &current_time.duration_since(prev_time).unwrap();
+            // Monotonic, a clock set back can't make the delta negative
+            let current_time = std::time::Instant::now();
+            let frame_delta = current_time.duration_since(prev_time);
+            timings.frame(frame_delta);
+            let delta = &delta_filter.filter(frame_delta);
+            // Frames after a slow one aren't drawn, the game keeps its speed
+            let is_drawn = budget.start_frame(*delta);
+            let phase_start = std::time::Instant::now();
 
-            // Clear the screen
-            execute!(
-                stdout,
-                cursor::MoveTo(0, 0),
-                Clear(ClearType::FromCursorDown)
-            )?;
+            // Apply config changes without leaving the run
+            since_config_check += *delta;
+            if since_config_check >= CONFIG_CHECK_PERIOD {
+                since_config_check = std::time::Duration::ZERO;
+                match config::reload_if_changed() {
+                    Some(Ok(())) => {
+                        config_error = None;
+                        toast = Some((tr(Text::ConfigReloaded).to_owned(), toast_tween()));
+                    }
+                    Some(Err(error)) => {
+                        tracing::warn!("config error: {}", error);
+                        toast = Some((i18n::format(Text::ConfigError, &[&error]), toast_tween()));
+                        config_error = Some(error);
+                    }
+                    None => {}
+                }
+            }
+
+            // Macros and challenge runs must play the same every time
+            let is_difficulty_dynamic = config::get().difficulty.dynamic
+                && run.recording.is_none()
+                && run.challenge.is_none();
+
+            // Update the game state (frozen while paused)
+            let mut presses = input::Presses::default();
+            let input =
+                read_input(&stdin_chan, &mut held, &mut presses).and_then(input::Input::from_event);
+            if term::is_quit_requested() {
+                break 'game_loop false;
+            }
+            let phase_start = timings.lap(debug::Phase::Input, phase_start);
+            let key = input.and_then(|input| input.key());
+            match input::action(&input) {
+                _ if is_quit_asked => match key.as_ref().and_then(dialog::answer) {
+                    Some(true) => break 'game_loop false,
+                    Some(false) => is_quit_asked = false,
+                    None => {}
+                },
+                Some(input::Action::Quit) if is_help_shown => is_help_shown = false,
+                None if key.is_some_and(|key| help::is_help_key(key.code)) => {
+                    is_help_shown = !is_help_shown
+                }
+                None if key.is_some_and(|key| key.code == debug::TOGGLE_BUTTON) => {
+                    is_debug_shown = !is_debug_shown
+                }
+                _ if is_help_shown => {}
+                Some(input::Action::Suspend) => break 'game_loop true,
+                Some(input::Action::Pause) => {
+                    is_paused = !is_paused;
+                    tracing::debug!(is_paused, "pause toggled");
+                }
+                Some(input::Action::Quit) if is_paused => break 'game_loop false,
+                _ if is_paused => {}
+                Some(input::Action::Quit) => is_quit_asked = true,
+                _ if run.recording.is_some()
+                    && key.is_some_and(|key| key.code == practice::STOP_BUTTON) =>
+                {
+                    toast = Some((stop_recording(&mut run), toast_tween()));
+                }
+                _ => {
+                    // Updates take the same time whatever the frame rate, a key goes
+                    // to the first update of the frame or waits for the next one
+                    steps.advance(*delta);
+                    waiting_presses.extend(&presses);
+                    let mut input = input::FrameInput::new(waiting_input.take().or(input))
+                        .with_presses(std::mem::take(&mut waiting_presses))
+                        .with_held(held.actions());
+                    let mut stepped = std::time::Duration::ZERO;
+                    while let Some(step) = steps.take() {
+                        let delta = &step;
+                        run.played += *delta;
+                        difficulty_level = is_difficulty_dynamic.then(|| {
+                            tracker.advance(*delta, run.game.rally_length());
+                            tracker.level()
+                        });
+                        run.replay
+                            .step(run.game.as_ref(), &input, *delta, difficulty_level);
+                        let event = match difficulty_level {
+                            Some(level) => {
+                                difficulty::with_level(level, || run.game.update(&input, delta))
+                            }
+                            None => run.game.update(&input, delta),
+                        };
+                        if let Some((_, input_macro)) = &mut run.recording {
+                            if !input_macro.push(&input, *delta) {
+                                toast = Some((stop_recording(&mut run), toast_tween()));
+                            }
+                        }
+                        effects.advance(*delta);
+                        for effect in run.game.take_sound_effects() {
+                            sound.play_effect(effect);
+                        }
+                        for score_event in run.game.take_score_events() {
+                            if config::get().score_popups {
+                                effects.spawn(score_event);
+                            }
+                        }
+                        stepped += *delta;
+                        since_autosave += *delta;
+                        if since_autosave >= save::AUTOSAVE_PERIOD {
+                            since_autosave = std::time::Duration::ZERO;
+                            if let Some(file) = run.save_file() {
+                                autosaver.submit(file);
+                                is_autosaved = true;
+                            }
+                        }
+                        if let game::UpdateEvent::GameOver = event {
+                            run.replay.frame(stepped);
+                            if is_difficulty_dynamic && input.action() != Some(input::Action::Quit)
+                            {
+                                tracker.add_death();
+                            }
+                            sound.play_effect(sound::Effect::GameOver);
+                            break 'game_loop false;
+                        }
 
-            // Update the game state
-            if let game::UpdateEvent::GameOver = game.update(&read_input(&stdin_chan), delta) {
-                break 'game_loop;
+                        // Chaos events would not be in the macro
+                        // and would make challenge runs differ
+                        if (config::get().chaos || is_chaos_unlocked)
+                            && run.recording.is_none()
+                            && run.challenge.is_none()
+                            && chaos.tick(*delta)
+                        {
+                            if let Some(text) = run.game.chaos_event() {
+                                run.replay.chaos_event();
+                                let tween =
+                                    anim::Tween::new(chaos::BANNER_TIME, anim::Ease::Linear);
+                                banner = Some((text, tween));
+                            }
+                        }
+                        input.event = None;
+                        input.presses = input::Presses::default();
+                    }
+                    waiting_input = input.event;
+                    if waiting_input.is_some() {
+                        waiting_presses = input.presses;
+                    }
+                    // The replay shows the frames as they were drawn
+                    run.replay.frame(stepped);
+                }
             }
 
+            let phase_start = timings.lap(debug::Phase::Update, phase_start);
+
             // Draw the game state
-            game.draw(&mut stdout, delta)?;
+            if is_drawn {
+                let (width, height) = terminal::size()?;
+                if (width, height) != (frame.width(), frame.height()) {
+                    run.game.resize(hud::play_area(width, height));
+                }
+                frame.clear(width, height);
+                run.game.draw_interpolated(&mut frame, delta, steps.alpha());
+                run.game.hud(hud.clear());
+                hud.draw(&mut frame);
+                effects.draw(&mut frame);
+                if is_paused {
+                    run.game.draw_pause(&mut frame);
+                }
+                if is_help_shown {
+                    help::draw(&mut frame, &run.name, &help);
+                }
+                if is_quit_asked {
+                    dialog::draw_question(&mut frame, tr(Text::QuitRun));
+                }
 
-            // draw delta
-            {
-                use std::io::Write;
-                execute!(stdout, cursor::MoveTo(0, 0))?;
-                write!(stdout, "Delta: {}", delta.as_nanos() as f32 / 1_000_000.0)?;
-                stdout.flush()?;
+                if is_debug_shown {
+                    let mut notes: Vec<String> = is_difficulty_dynamic
+                        .then(|| tracker.describe())
+                        .into_iter()
+                        .collect();
+                    notes.push(format!("Frames not drawn: {}", budget.skipped()));
+                    timings.draw(&mut frame, &run.game.entities(), &notes);
+                }
+
+                if let Some((text, tween)) = &banner {
+                    use crossterm::style::Stylize;
+
+                    let elapsed = tween.elapsed();
+                    let drop = anim::Ease::OutQuad
+                        .apply(elapsed.as_secs_f32() / chaos::BANNER_DROP_TIME.as_secs_f32());
+                    let y = anim::lerp(0.0, (frame.height() / 3) as f32, drop).round() as u16;
+                    let text = if anim::oscillate(elapsed, chaos::BANNER_BLINK_PERIOD) >= 0.0 {
+                        text.as_str().with(theme::palette().danger).bold()
+                    } else {
+                        text.as_str().with(theme::palette().warning).bold()
+                    };
+                    frame.print_styled(
+                        render::Layer::Overlay,
+                        frame
+                            .width()
+                            .saturating_sub(text.content().chars().count() as u16)
+                            / 2,
+                        y,
+                        text,
+                    );
+                }
+
+                if let Some((message, _)) = &toast {
+                    use crossterm::style::Stylize;
+
+                    // Only the first line of multi-line errors fits
+                    let line = message.lines().next().unwrap_or_default();
+                    frame.print_styled(
+                        render::Layer::Overlay,
+                        0,
+                        frame.height().saturating_sub(1),
+                        line.reverse(),
+                    );
+                }
+
+                if budget.is_slow() {
+                    use crossterm::style::Stylize;
+
+                    let text = tr(Text::RunningSlow);
+                    frame.print_styled(
+                        render::Layer::Overlay,
+                        frame.width().saturating_sub(text.chars().count() as u16),
+                        0,
+                        text.with(theme::palette().warning),
+                    );
+                }
+
+                let phase_start = timings.lap(debug::Phase::Draw, phase_start);
+                renderer.submit(&frame)?;
+                timings.lap(debug::Phase::Output, phase_start);
+            }
+
+            // Banners and toasts go on in frames not drawn
+            if let Some((_, tween)) = &mut banner {
+                if !is_paused {
+                    tween.advance(*delta);
+                }
+                if tween.is_finished() {
+                    banner = None;
+                }
+            }
+            if let Some((_, tween)) = &mut toast {
+                tween.advance(*delta);
+                if tween.is_finished() {
+                    toast = None;
+                }
             }
 
-            // Wait for the next frame
-            thread::sleep(Duration::from_millis(BETWEEN_FRAMES_TIME_MS));
+            // Wait for the next frame, time spent on this one counts
+            let frame_time = current_time.elapsed();
+            let between_frames = config::get().between_frames();
+            budget.end_frame(frame_time, between_frames);
+            thread::sleep(between_frames.saturating_sub(frame_time));
 
             prev_time = current_time;
+        };
+        renderer.finish()?;
+        let autosave_result = autosaver.finish();
+
+        if is_suspended {
+            tracing::info!(game = %run.name, "run suspended");
+            suspended = Some(run);
+            continue 'main_loop;
         }
 
-        println!("Game over! Score: {}", game.get_score().value);
-        println!("Press any key to restart.");
-        // Wait for prevent the game from restarting immediately
-        std::thread::sleep(std::time::Duration::from_millis(750));
-        _ = read();
+        if is_autosaved {
+            _ = save::SaveFile::remove();
+        }
+
+        // Failed saves are retried after the next run and on exit
+        unsaved_heat.add(&run.name, run.game.take_heat_points());
+        _ = unsaved_heat.save();
+        if term::is_quit_requested() {
+            // Closed terminal, the run is resumed after the next start
+            run.keep_for_later();
+            break 'main_loop;
+        }
+
+        let score = run.game.get_score().value;
+        tracing::info!(game = %run.name, score, played = ?run.played, "run over");
+        let mut lines = vec![];
+        if let Err(err) = autosave_result {
+            tracing::error!("autosave failed: {}", err);
+            lines.push(i18n::format(Text::AutosaveFailed, &[&err]));
+        }
+        let mut record = telemetry::RunRecord::new(
+            &run.name,
+            score,
+            run.played,
+            telemetry::RunSettings {
+                frame_rate: config::get().frame_rate,
+                chaos: (config::get().chaos || is_chaos_unlocked) && run.challenge.is_none(),
+                difficulty: difficulty_level,
+                theme: theme::current(),
+            },
+        );
+        {
+            let par_score = games
+                .games()
+                .iter()
+                .find(|entry| entry.name == run.name)
+                .map(|entry| entry.par_score);
+            let mut history = stats::History::load();
+            let report = history.grade(&run.name, score, par_score, run.game.performance());
+            lines.push(grade_line(&report));
+            record.grade = Some(report.grade);
+            lines.push(report.feedback);
+            // Grades go on without the history if it can't be written
+            history.add(&run.name, score);
+            _ = history.save();
+            let recent: Vec<f32> = history
+                .scores(&run.name)
+                .iter()
+                .map(|&score| score as f32)
+                .collect();
+            if recent.len() > 1 {
+                let sparkline = chart::sparkline(&recent, RECENT_SCORES_SHOWN);
+                lines.push(i18n::format(Text::RecentScores, &[&sparkline]));
+            }
+        }
+        let previous_best;
+        {
+            let mut lifetime = lifetime::Lifetime::load();
+            previous_best = lifetime.best_score(&run.name);
+            lifetime.add(&run.name, score, run.played, run.game.counts());
+            if let Err(err) = lifetime.save() {
+                tracing::error!("statistics not saved: {}", err);
+                lines.push(i18n::format(Text::StatisticsNotSaved, &[&err]));
+            }
+        }
+        record.challenge = run.challenge.map(|weekly| weekly.week.to_string());
+        if let Err(err) = telemetry::record(&record) {
+            lines.push(i18n::format(Text::TelemetryNotWritten, &[&err]));
+        }
+        if let Some(weekly) = &run.challenge {
+            let mut best_scores = challenge::BestScores::load();
+            if best_scores.add(weekly, &games, score) {
+                lines.push(match best_scores.save() {
+                    Ok(()) => i18n::format(Text::WeeklyBest, &[&weekly.week]),
+                    Err(err) => i18n::format(Text::WeeklyBestNotSaved, &[&err]),
+                });
+            }
+        }
+        let summary = summary::Summary {
+            game_name: run.name.clone(),
+            score,
+            played: run.played,
+            previous_best,
+            seed: run.seed,
+            details: run.game.summary(),
+            notes: lines,
+        };
+        let can_restart = restart_choice.is_some();
+        match summary::show(stdout, &stdin_chan, &summary, &run.replay, can_restart)? {
+            summary::Next::Restart => {
+                restart = restart_choice.map(|choice| (choice, run.name.clone()));
+            }
+            summary::Next::Menu => {}
+            summary::Next::Quit => {
+                if confirm_exit(stdout, &stdin_chan, &mut unsaved_heat, None)? {
+                    break 'main_loop;
+                }
+            }
+        }
     }
 
     Ok(())
 }
 
+/// Run a `stats` subcommand, exits with code 2 on errors.
+fn run_stats_command(args: &[String]) {
+    if let Err(error) = config::load() {
+        eprintln!("Config error: {}", error);
+    }
+    if let Err(error) = telemetry::run_command(args) {
+        eprintln!("{}", error);
+        std::process::exit(2);
+    }
+}
+
+/// Simulate a run without the terminal and print the result.
+/// Exits with code 2 on bad arguments.
+fn run_headless(args: &[String]) {
+    if let Err(error) = config::load() {
+        eprintln!("Config error: {}", error);
+    }
+    for error in script::load_scripts() {
+        eprintln!("Script error: {}", error);
+    }
+
+    let report = headless::parse_args(args).and_then(|options| headless::run(&options));
+    match report {
+        Ok(report) => {
+            println!("Score: {}", report.score);
+            println!(
+                "Frames: {}{}",
+                report.frames,
+                if report.is_over { " (game over)" } else { "" }
+            );
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(2);
+        }
+    }
+}
+
 /// [how can I read non blocking from stdin](https://stackoverflow.com/questions/30012995/how-can-i-read-non-blocking-from-stdin)
-fn spawn_stdin_channel() -> std::sync::mpsc::Receiver<crossterm::event::KeyEvent> {
-    let (tx, rx) = std::sync::mpsc::channel::<crossterm::event::KeyEvent>();
-    use crossterm::event::{read, Event};
+/// Sends keys, mouse events and resizes, and controller input turned into keys.
+/// Nothing else reads stdin, menus and runs wait on the channel.
+fn spawn_stdin_channel() -> std::sync::mpsc::Receiver<crossterm::event::Event> {
+    let (tx, rx) = std::sync::mpsc::channel::<crossterm::event::Event>();
+    use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyModifiers};
     use std::thread;
 
+    #[cfg(feature = "gamepad")]
+    if config::get().gamepad {
+        gamepad::spawn(tx.clone());
+    }
+
     thread::spawn(move || loop {
-        if let Ok(Event::Key(key)) = read() {
-            match tx.send(key) {
-                Ok(_) => {}
-                Err(_) => break, // stdin disconnected
+        let event = match read() {
+            // Raw mode turns Ctrl+C into a key instead of SIGINT.
+            // Esc wakes up whoever waits for a key and leaves menus and runs.
+            Ok(Event::Key(key))
+                if key.code == KeyCode::Char('c')
+                    && key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                term::request_quit();
+                Event::Key(KeyEvent {
+                    code: game::EXIT_BUTTON,
+                    modifiers: KeyModifiers::NONE,
+                    ..key
+                })
             }
+            Ok(event @ (Event::Key(_) | Event::Mouse(_) | Event::Resize(..))) => event,
+            _ => continue,
+        };
+        match tx.send(event) {
+            Ok(_) => {}
+            Err(_) => break, // stdin disconnected
         }
     });
 
     rx
 }
 
+/// Input of the frame: the first key press, or the last mouse event if no key was pressed.
+/// Actions of all key presses are counted in `presses`, other events only update the held keys.
 fn read_input(
-    rx: &std::sync::mpsc::Receiver<crossterm::event::KeyEvent>,
-) -> Option<crossterm::event::KeyEvent> {
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+    held: &mut input::HeldKeys,
+    presses: &mut input::Presses,
+) -> Option<crossterm::event::Event> {
+    use crossterm::event::{Event, KeyEventKind};
     use std::sync::mpsc::TryRecvError;
 
-    let result = match rx.try_recv() {
-        Ok(input) => Some(input),
-        Err(TryRecvError::Disconnected) => panic!("stdin disconnected"),
-        Err(TryRecvError::Empty) => None,
-    };
-
-    // Skip all other inputs
+    let mut result = None;
     loop {
         match rx.try_recv() {
+            Ok(Event::Key(key)) => {
+                let action = config::get().keys.action(&key);
+                held.update(&key, action);
+                if key.kind == KeyEventKind::Release {
+                    continue;
+                }
+                if let Some(action) = action {
+                    presses.add(action);
+                }
+                if !matches!(result, Some(Event::Key(_))) {
+                    result = Some(Event::Key(key));
+                }
+            }
+            Ok(event @ Event::Mouse(_)) => {
+                if !matches!(result, Some(Event::Key(_))) {
+                    result = Some(event);
+                }
+            }
+            // The frame is sized from the screen every frame anyway
             Ok(_) => {}
             Err(TryRecvError::Disconnected) => panic!("stdin disconnected"),
             Err(TryRecvError::Empty) => break,
@@ -174,27 +964,278 @@ fn read_input(
     result
 }
 
-fn read_game_choice() -> Option<MenuChoice> {
-    use std::io::stdin;
+/// Grade with what it is based on, like `Grade: B (better than 62% of your runs, accuracy 40%)`.
+fn grade_line(report: &stats::Report) -> String {
+    let mut reasons = vec![];
+    if let Some(percentile) = report.percentile {
+        let percent = format!("{:.0}", percentile * 100.0);
+        reasons.push(i18n::format(Text::BetterThanRuns, &[&percent]));
+    }
+    if let Some(performance) = report.performance {
+        reasons.push(format!(
+            "{} {:.0}%",
+            tr(performance.name),
+            performance.value * 100.0
+        ));
+    }
+
+    if reasons.is_empty() {
+        i18n::format(Text::Grade, &[&report.grade])
+    } else {
+        let reasons = reasons.join(", ");
+        i18n::format(Text::GradeWithReasons, &[&report.grade, &reasons])
+    }
+}
+
+/// Save the macro being recorded. Returns the message for the player.
+fn stop_recording(run: &mut Run) -> String {
+    match run.recording.take() {
+        Some((game_id, input_macro)) => match input_macro.store(game_id) {
+            Ok(()) => {
+                let secs = format!("{:.1}", input_macro.duration().as_secs_f32());
+                i18n::format(Text::MacroSaved, &[&secs])
+            }
+            Err(err) => i18n::format(Text::MacroNotSaved, &[&err]),
+        },
+        None => String::new(),
+    }
+}
+
+/// Ask for the seed of a run of the game, again while it is not a number.
+/// None if the player cancels.
+fn ask_seed(
+    out: &mut std::io::Stdout,
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+    game_name: &str,
+    last_seed: Option<u64>,
+) -> crossterm::Result<Option<u64>> {
+    let mut question = i18n::format(Text::AskSeed, &[&game_name]);
+    let mut text = last_seed.map(|seed| seed.to_string()).unwrap_or_default();
+    loop {
+        let Some(answer) = dialog::ask_text(out, rx, &question, &text)? else {
+            return Ok(None);
+        };
+        match answer.trim().parse() {
+            Ok(seed) => return Ok(Some(seed)),
+            Err(_) => {
+                question = i18n::format(Text::AskSeedAgain, &[&game_name]);
+                text = answer;
+            }
+        }
+    }
+}
+
+/// Exit right away if nothing is lost, ask the player otherwise.
+fn confirm_exit(
+    out: &mut std::io::Stdout,
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+    unsaved_heat: &mut heatmap::Unsaved,
+    suspended_name: Option<&str>,
+) -> crossterm::Result<bool> {
+    if term::is_quit_requested() {
+        _ = unsaved_heat.save();
+        return Ok(true);
+    }
+    if let Some(name) = suspended_name {
+        return dialog::confirm(out, rx, &i18n::format(Text::SuspendedLost, &[&name]));
+    }
+
+    match unsaved_heat.save() {
+        Ok(()) => Ok(true),
+        Err(err) => dialog::confirm(out, rx, &i18n::format(Text::HeatmapsNotSaved, &[&err])),
+    }
+}
+
+/// Settings menu, the current choices are marked
+fn settings_items() -> Vec<menu::Item<Choice>> {
+    use menu::{Item, Menu};
+
+    let settings = settings::get();
+    let picked = settings.theme;
+    let mark = |is_picked: bool| if is_picked { tr(Text::Current) } else { "" };
+    let mut themes: Vec<Item<Choice>> = theme::Name::ALL
+        .iter()
+        .map(|&name| {
+            Item::Action(
+                format!("{}{}", name.title(), mark(picked == Some(name))),
+                Choice::Theme(Some(name)),
+            )
+        })
+        .collect();
+    let as_in_config =
+        |value: &str, is_picked: bool| i18n::format(Text::AsInConfig, &[&value]) + mark(is_picked);
+    themes.push(Item::Action(
+        as_in_config(config::get().theme.title(), picked.is_none()),
+        Choice::Theme(None),
+    ));
+
+    let on_off = |is_on: bool| if is_on { tr(Text::On) } else { tr(Text::Off) };
+    let mut colorblind: Vec<Item<Choice>> = [true, false]
+        .iter()
+        .map(|&is_on| {
+            Item::Action(
+                format!(
+                    "{}{}",
+                    on_off(is_on),
+                    mark(settings.colorblind == Some(is_on))
+                ),
+                Choice::Colorblind(Some(is_on)),
+            )
+        })
+        .collect();
+    colorblind.push(Item::Action(
+        as_in_config(
+            on_off(config::get().colorblind),
+            settings.colorblind.is_none(),
+        ),
+        Choice::Colorblind(None),
+    ));
+
+    let mut languages: Vec<Item<Choice>> = i18n::Language::ALL
+        .iter()
+        .map(|&language| {
+            Item::Action(
+                format!(
+                    "{}{}",
+                    language.title(),
+                    mark(settings.language == Some(language))
+                ),
+                Choice::Language(Some(language)),
+            )
+        })
+        .collect();
+    languages.push(Item::Action(
+        as_in_config(config::get().language.title(), settings.language.is_none()),
+        Choice::Language(None),
+    ));
+
+    vec![
+        Item::Submenu(Menu::new(tr(Text::Theme), themes)),
+        Item::Submenu(Menu::new(tr(Text::Colorblind), colorblind)),
+        Item::Submenu(Menu::new(tr(Text::Language), languages)),
+    ]
+}
 
-    let mut input = String::new();
-    stdin().read_line(&mut input).ok()?;
-    let choice = input.trim().parse::<usize>().ok()?;
+/// Main menu tree with items for the suspended and the saved runs
+fn main_menu(
+    registry: &registry::Registry,
+    plugins: &[plugin::PluginInfo],
+    suspended: Option<&Run>,
+    saved: Option<(&str, i64)>,
+    weekly: Option<(&registry::GameEntry, Option<i64>)>,
+) -> menu::Menu<Choice> {
+    use menu::{Item, Menu};
 
-    sa::const_assert!(MenuChoice::Exit as usize == 0);
-    sa::const_assert!(MenuChoice::SnakeGame as usize == 1);
-    sa::const_assert!(MenuChoice::TetrisGame as usize == 2);
-    sa::const_assert!(MenuChoice::SpaceInvadersGame as usize == 3);
-    sa::const_assert!(MenuChoice::Pong as usize == 4);
+    let mut games: Vec<Item<Choice>> = registry
+        .games()
+        .iter()
+        .enumerate()
+        .map(|(ind, entry)| Item::Action(entry.name.to_owned(), Choice::Game(ind)))
+        .collect();
+    let seeded = registry
+        .games()
+        .iter()
+        .enumerate()
+        .map(|(ind, entry)| Item::Action(entry.name.to_owned(), Choice::Seeded(ind)))
+        .collect();
+    // Plugins are listed after the built-in games
+    games.extend(
+        plugins
+            .iter()
+            .enumerate()
+            .map(|(ind, plugin)| Item::Action(plugin.name.clone(), Choice::Plugin(ind))),
+    );
+    games.push(Item::Submenu(Menu::new(tr(Text::WithSeed), seeded)));
 
-    sa::const_assert!(MenuChoice::LastElement as usize == 5);
+    // Left game first, then the right one
+    let versus = registry
+        .games()
+        .iter()
+        .enumerate()
+        .map(|(left, left_entry)| {
+            let rights = registry
+                .games()
+                .iter()
+                .enumerate()
+                .map(|(right, entry)| {
+                    Item::Action(entry.name.to_owned(), Choice::Versus(left, right))
+                })
+                .collect();
+            Item::Submenu(Menu::new(
+                &i18n::format(Text::VersusLeft, &[&left_entry.name]),
+                rights,
+            ))
+        })
+        .collect();
 
-    match choice {
-        0 => Some(MenuChoice::Exit),
-        1 => Some(MenuChoice::SnakeGame),
-        2 => Some(MenuChoice::TetrisGame),
-        3 => Some(MenuChoice::SpaceInvadersGame),
-        4 => Some(MenuChoice::Pong),
-        _ => None,
+    let practice = registry
+        .games()
+        .iter()
+        .enumerate()
+        .map(|(ind, entry)| {
+            let mut actions = vec![Item::Action(
+                tr(Text::RecordMacro).to_owned(),
+                Choice::RecordMacro(ind),
+            )];
+            if practice::InputMacro::exists(entry.id) {
+                actions.push(Item::Action(
+                    tr(Text::StartFromMacro).to_owned(),
+                    Choice::Practice(ind),
+                ));
+            }
+            Item::Submenu(Menu::new(entry.name, actions))
+        })
+        .collect();
+
+    let mut items = vec![
+        Item::Submenu(Menu::new(tr(Text::Play), games).with_preview(preview_game)),
+        Item::Submenu(Menu::new(tr(Text::Practice), practice)),
+        Item::Submenu(Menu::new(tr(Text::Versus), versus)),
+        Item::Action(tr(Text::Heatmaps).to_owned(), Choice::Heatmaps),
+        Item::Action(tr(Text::Statistics).to_owned(), Choice::Statistics),
+        Item::Action(tr(Text::ScoreTrends).to_owned(), Choice::ScoreTrends),
+        Item::Submenu(Menu::new(tr(Text::Settings), settings_items())),
+        Item::Action(tr(Text::Exit).to_owned(), Choice::Exit),
+    ];
+    if let Some((entry, best)) = weekly {
+        let name = match best {
+            Some(score) => i18n::format(Text::ChallengeBest, &[&entry.name, &score]),
+            None => i18n::format(Text::Challenge, &[&entry.name]),
+        };
+        items.insert(1, Item::Action(name, Choice::Challenge));
+    }
+    // Runs to get back to go first to be picked with Enter.
+    // The save of a suspended run is its autosave
+    if let Some((name, score)) = saved.filter(|_| suspended.is_none()) {
+        items.insert(
+            0,
+            Item::Action(
+                i18n::format(Text::ResumeSaved, &[&name, &score]),
+                Choice::Continue,
+            ),
+        );
+    }
+    if let Some(run) = suspended {
+        if run.game.save().is_some() {
+            items.insert(
+                items.len() - 1,
+                Item::Action(tr(Text::SaveAndQuit).to_owned(), Choice::SaveAndQuit),
+            );
+        }
+        items.insert(
+            0,
+            Item::Action(i18n::format(Text::Resume, &[&run.name]), Choice::Resume),
+        );
+    }
+
+    Menu::new(tr(Text::MainMenu), items).with_banner(BANNER)
+}
+
+/// Picture of the built-in game of the menu item.
+fn preview_game(choice: &Choice, frame: &mut render::FrameBuffer, area: layout::Rect) {
+    if let Choice::Game(ind) = choice {
+        if let Some(entry) = registry::Registry::builtin().get(*ind) {
+            preview::draw(entry, frame, area);
+        }
     }
 }
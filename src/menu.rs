@@ -0,0 +1,276 @@
+use crate::input::Action;
+use crate::layout::Rect;
+use crate::render::FrameBuffer;
+
+/// Draws the picture of an item value in the given part of the screen.
+pub type Preview<T> = fn(&T, &mut FrameBuffer, Rect);
+
+/// Menu item: leaf with a value or a nested menu.
+pub enum Item<T> {
+    Action(String, T),
+    Submenu(Menu<T>),
+}
+
+impl<T> Item<T> {
+    pub fn name(&self) -> &str {
+        match self {
+            Item::Action(name, _) => name,
+            Item::Submenu(menu) => &menu.title,
+        }
+    }
+}
+
+/// Called with the screen and the input when a menu is left idle.
+pub type Idle<'a> = dyn FnMut(
+        &mut std::io::Stdout,
+        &std::sync::mpsc::Receiver<crossterm::event::Event>,
+    ) -> crossterm::Result<()>
+    + 'a;
+
+pub struct Menu<T> {
+    pub title: String,
+    pub items: Vec<Item<T>>,
+    /// Lines over the menu and its submenus, left out on small screens
+    banner: &'static [&'static str],
+    /// Picture of the selected item shown next to the menu
+    preview: Option<Preview<T>>,
+}
+
+impl<T: Clone> Menu<T> {
+    pub fn new(title: &str, items: Vec<Item<T>>) -> Self {
+        Self {
+            title: title.to_owned(),
+            items,
+            banner: &[],
+            preview: None,
+        }
+    }
+
+    pub fn with_banner(mut self, banner: &'static [&'static str]) -> Self {
+        self.banner = banner;
+        self
+    }
+
+    pub fn with_preview(mut self, preview: Preview<T>) -> Self {
+        self.preview = Some(preview);
+        self
+    }
+
+    /// Let the player walk the menu with Up/Down/Enter, digit keys or the mouse.
+    /// Esc (or right click) returns to the parent menu; Esc in the root menu returns None.
+    /// Returns the picked item name and value.
+    ///
+    /// Every key press is passed to `on_key` too, messages it returns are added to the header.
+    pub fn run(
+        &self,
+        out: &mut std::io::Stdout,
+        rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+        header: &[String],
+        on_key: &mut dyn FnMut(&crossterm::event::KeyEvent) -> Option<String>,
+    ) -> crossterm::Result<Option<(String, T)>> {
+        self.run_idle(out, rx, header, on_key, None)
+    }
+
+    /// Same as `run`, but when there is no input for the given time, `on_idle` takes
+    /// the screen and the input. The menu is shown as it was after it returns.
+    pub fn run_idle(
+        &self,
+        out: &mut std::io::Stdout,
+        rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+        header: &[String],
+        on_key: &mut dyn FnMut(&crossterm::event::KeyEvent) -> Option<String>,
+        mut on_idle: Option<(std::time::Duration, &mut Idle)>,
+    ) -> crossterm::Result<Option<(String, T)>> {
+        use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+        use crossterm::{execute, terminal};
+        use std::sync::mpsc::RecvTimeoutError;
+
+        // Opened menus with the selected item in each
+        let mut stack: Vec<(&Menu<T>, usize)> = vec![(self, 0)];
+        let mut header: Vec<String> = header
+            .iter()
+            .flat_map(|line| line.lines())
+            .map(str::to_owned)
+            .collect();
+        // Mouse moves within an item change nothing on the screen
+        let mut needs_redraw = true;
+
+        let result = 'input_read: loop {
+            let breadcrumbs = stack
+                .iter()
+                .map(|(menu, _)| menu.title.as_str())
+                .collect::<Vec<_>>()
+                .join(" > ");
+            let (menu, selected) = *stack.last().expect("root menu is never popped");
+            let (_, height) = terminal::size()?;
+            // Header and the items fit first
+            let banner = match self.banner.len() + header.len() + menu.items.len() + 2 {
+                lines if lines <= height as usize => self.banner,
+                _ => &[],
+            };
+            if needs_redraw {
+                draw(out, banner, &header, &breadcrumbs, menu, selected)?;
+            }
+            needs_redraw = true;
+
+            let received = match &mut on_idle {
+                Some((idle_time, _)) => rx.recv_timeout(*idle_time),
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            let event = match received {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Some((_, on_idle)) = &mut on_idle {
+                        on_idle(out, rx)?;
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => panic!("stdin disconnected"),
+            };
+            if crate::term::is_quit_requested() {
+                break 'input_read None;
+            }
+            let (action, code) = match event {
+                Event::Key(key) if key.kind != KeyEventKind::Release => {
+                    if let Some(message) = on_key(&key) {
+                        header.push(message);
+                    }
+                    (crate::config::get().keys.action(&key), key.code)
+                }
+                // Mouse input does what the keys do
+                Event::Mouse(mouse) => {
+                    // Items are listed under the banner, the header and the top of their box
+                    let hovered = (mouse.row as usize)
+                        .checked_sub(banner.len() + header.len() + 1)
+                        .filter(|&ind| ind < menu.items.len());
+                    match (mouse.kind, hovered) {
+                        (MouseEventKind::Moved, Some(ind)) if ind != selected => {
+                            stack.last_mut().expect("not empty").1 = ind;
+                            continue;
+                        }
+                        (MouseEventKind::Down(MouseButton::Left), Some(ind)) => {
+                            stack.last_mut().expect("not empty").1 = ind;
+                            (None, KeyCode::Enter)
+                        }
+                        // Right click never leaves the root menu
+                        (MouseEventKind::Down(MouseButton::Right), _) if stack.len() > 1 => {
+                            (Some(Action::Quit), KeyCode::Null)
+                        }
+                        (MouseEventKind::ScrollUp, _) => (Some(Action::MoveUp), KeyCode::Null),
+                        (MouseEventKind::ScrollDown, _) => (Some(Action::MoveDown), KeyCode::Null),
+                        _ => {
+                            needs_redraw = false;
+                            continue;
+                        }
+                    }
+                }
+                // Resizes only redraw
+                _ => continue,
+            };
+            if menu.items.is_empty() && action != Some(Action::Quit) {
+                continue;
+            }
+            let selected = stack.last().expect("not empty").1;
+
+            let picked = match (action, code) {
+                (Some(Action::MoveUp), _) => {
+                    stack.last_mut().expect("not empty").1 =
+                        (selected + menu.items.len() - 1) % menu.items.len();
+                    None
+                }
+                (Some(Action::MoveDown), _) => {
+                    stack.last_mut().expect("not empty").1 = (selected + 1) % menu.items.len();
+                    None
+                }
+                (Some(Action::Quit), _) if stack.len() > 1 => {
+                    stack.pop();
+                    None
+                }
+                (Some(Action::Quit), _) => break 'input_read None,
+                (_, KeyCode::Enter) => Some(selected),
+                (_, KeyCode::Char(c)) => c
+                    .to_digit(10)
+                    .map(|ind| ind as usize)
+                    .filter(|&ind| ind < menu.items.len()),
+                _ => None,
+            };
+
+            if let Some(ind) = picked {
+                match &menu.items[ind] {
+                    Item::Action(name, value) => {
+                        break 'input_read Some((name.clone(), value.clone()))
+                    }
+                    Item::Submenu(submenu) => {
+                        stack.last_mut().expect("not empty").1 = ind;
+                        stack.push((submenu, 0));
+                    }
+                }
+            }
+        };
+        execute!(
+            out,
+            terminal::Clear(terminal::ClearType::All),
+            crossterm::cursor::MoveTo(0, 0)
+        )?;
+
+        Ok(result)
+    }
+}
+
+/// Banner and header lines, then the items in a box titled with the breadcrumbs
+/// and the preview of the selected item to the right.
+fn draw<T>(
+    out: &mut std::io::Stdout,
+    banner: &[&str],
+    header: &[String],
+    breadcrumbs: &str,
+    menu: &Menu<T>,
+    selected: usize,
+) -> crossterm::Result<()> {
+    use crate::render::Layer;
+    use crate::ui::{Label, ListMenu};
+    use crossterm::style::{ContentStyle, Stylize};
+
+    let mut frame = FrameBuffer::from_terminal()?;
+    let screen = Rect::of_frame(&frame);
+    let banner_style = ContentStyle::new()
+        .with(crate::theme::palette().player)
+        .bold();
+    for (y, line) in banner.iter().enumerate() {
+        Label::styled(line, banner_style).draw(
+            &mut frame,
+            Layer::Hud,
+            screen.shrink(0, y as u16, 0, 0),
+        );
+    }
+    let top = banner.len() as u16;
+    for (y, line) in header.iter().enumerate() {
+        Label::new(line).draw(
+            &mut frame,
+            Layer::Hud,
+            screen.shrink(0, top + y as u16, 0, 0),
+        );
+    }
+
+    let lines: Vec<String> = menu
+        .items
+        .iter()
+        .enumerate()
+        .map(|(ind, item)| match item {
+            Item::Action(..) => format!("  {}. {} ", ind, item.name()),
+            Item::Submenu(..) => format!("  {}. {} > ", ind, item.name()),
+        })
+        .collect();
+    let list = ListMenu::new(breadcrumbs, &lines, selected);
+    let (width, height) = list.size();
+    let area = Rect::new(0, top + header.len() as u16, width, height);
+    list.draw(&mut frame, Layer::Hud, area);
+
+    if let (Some(preview), Some(Item::Action(_, value))) = (menu.preview, menu.items.get(selected))
+    {
+        let side = screen.shrink(area.right() + 1, area.y, 0, 0);
+        preview(value, &mut frame, side);
+    }
+
+    frame.flush(out)
+}
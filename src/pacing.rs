@@ -0,0 +1,154 @@
+//! Keeping runs at full speed when frames take too long: the game goes on
+//! with the time that passed, and frames after a slow one aren't drawn.
+//! Games are updated in steps of the same length whatever the frame rate,
+//! so the physics don't depend on how long frames take.
+use std::time::Duration;
+
+/// Longest frame time given to the games, the rest of a longer stall is lost.
+pub const MAX_DELTA: Duration = Duration::from_millis(250);
+/// Weight of the newest frame in the smoothed frame time.
+const DELTA_SMOOTHING: f32 = 0.2;
+
+/// Game updates a second, whatever the frame rate.
+pub const UPDATE_RATE: u32 = 120;
+
+/// Frames not drawn in a row at most, so the screen still moves.
+const MAX_SKIPPED: u32 = 4;
+/// Time the "running slow" sign stays after the last frame not drawn.
+const SLOW_SIGN_TIME: Duration = Duration::from_secs(2);
+
+/// Frame times for the games: clamped, so a stalled terminal doesn't move
+/// things half the screen at once, and smoothed, so jitter doesn't shake them.
+/// The smoothed times add up to the time that passed, up to the clamping.
+#[derive(Clone, Debug, Default)]
+pub struct DeltaFilter {
+    smoothed: Option<Duration>,
+}
+
+impl DeltaFilter {
+    pub fn filter(&mut self, delta: Duration) -> Duration {
+        let delta = delta.min(MAX_DELTA);
+        let smoothed = match self.smoothed {
+            Some(smoothed) => {
+                smoothed.mul_f32(1.0 - DELTA_SMOOTHING) + delta.mul_f32(DELTA_SMOOTHING)
+            }
+            None => delta,
+        };
+        self.smoothed = Some(smoothed);
+        smoothed
+    }
+}
+
+/// How the frames kept to the time between frames lately.
+#[derive(Clone, Debug, Default)]
+pub struct FrameBudget {
+    /// Work of the last frame took longer than the time between frames
+    is_over: bool,
+    skipped_in_row: u32,
+    skipped: u64,
+    since_skip: Option<Duration>,
+}
+
+impl FrameBudget {
+    /// Whether to draw the frame that starts, `delta` after the previous one.
+    pub fn start_frame(&mut self, delta: Duration) -> bool {
+        self.since_skip = self.since_skip.map(|time| time + delta);
+        if self.is_over && self.skipped_in_row < MAX_SKIPPED {
+            self.skipped_in_row += 1;
+            self.skipped += 1;
+            self.since_skip = Some(Duration::ZERO);
+            false
+        } else {
+            self.skipped_in_row = 0;
+            true
+        }
+    }
+
+    /// Time the update and the drawing of the frame took,
+    /// and the time between frames at the frame rate.
+    pub fn end_frame(&mut self, work: Duration, budget: Duration) {
+        self.is_over = work > budget;
+    }
+
+    /// Frames were left out lately.
+    pub fn is_slow(&self) -> bool {
+        self.since_skip.is_some_and(|time| time < SLOW_SIGN_TIME)
+    }
+
+    /// Frames not drawn since the start of the run.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+}
+
+/// Time that passed cut into updates of the same length.
+#[derive(Clone, Debug)]
+pub struct FixedStep {
+    step: Duration,
+    /// Time not given to the game yet
+    accumulated: Duration,
+}
+
+impl Default for FixedStep {
+    fn default() -> Self {
+        Self {
+            step: Duration::from_secs(1) / UPDATE_RATE,
+            accumulated: Duration::ZERO,
+        }
+    }
+}
+
+impl FixedStep {
+    /// Updates in a frame at most, as many as the longest frame time takes.
+    /// Time past them is dropped, so a stall doesn't keep the game catching up for long.
+    fn max_steps(&self) -> u32 {
+        MAX_DELTA.as_nanos().div_ceil(self.step.as_nanos()) as u32
+    }
+
+    pub fn advance(&mut self, delta: Duration) {
+        self.accumulated = (self.accumulated + delta).min(self.step * self.max_steps());
+    }
+
+    /// Time of the next update, None until enough time has passed.
+    pub fn take(&mut self) -> Option<Duration> {
+        let left = self.accumulated.checked_sub(self.step)?;
+        self.accumulated = left;
+        Some(self.step)
+    }
+
+    /// Part of an update the time left is, how far between the last two updates to draw.
+    pub fn alpha(&self) -> f32 {
+        self.accumulated.as_secs_f32() / self.step.as_secs_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(fixed: &mut FixedStep) -> u32 {
+        std::iter::from_fn(|| fixed.take()).count() as u32
+    }
+
+    #[test]
+    fn slow_frame_keeps_real_time() {
+        let mut fixed = FixedStep::default();
+        fixed.advance(Duration::from_millis(100));
+        assert_eq!(steps(&mut fixed), 12);
+    }
+
+    #[test]
+    fn longest_frame_keeps_real_time() {
+        let mut fixed = FixedStep::default();
+        fixed.advance(MAX_DELTA);
+        assert_eq!(steps(&mut fixed), 30);
+    }
+
+    #[test]
+    fn stall_is_cut_to_longest_frame() {
+        let mut fixed = FixedStep::default();
+        fixed.advance(Duration::from_secs(5));
+        assert!(steps(&mut fixed) <= fixed.max_steps());
+        assert!(fixed.max_steps() as f32 / UPDATE_RATE as f32 >= MAX_DELTA.as_secs_f32());
+    }
+}
@@ -0,0 +1,236 @@
+//! External games running as separate processes.
+//!
+//! Every executable file in the plugins directory is listed in the menu.
+//! Host and plugin talk line by line over the plugin's stdin/stdout:
+//!
+//! ```text
+//! host   -> plugin: update <delta in microseconds> <key>
+//! plugin -> host:   state <continue|over> <score>
+//! plugin -> host:   frame <lines count>
+//! plugin -> host:   <line 1>
+//! ...
+//! ```
+//!
+//! `<key>` is one of `none`, `up`, `down`, `left`, `right`, `enter`,
+//! `backspace`, `tab` or `char:<c>`. Rebound movement and fire keys are sent
+//! as the default ones (arrows and `char: `).
+//!
+//! A plugin that does not answer within [`REPLY_TIMEOUT`] is killed. Control
+//! characters are dropped from frame lines so a plugin can't write escape
+//! sequences to the terminal.
+use crate::game::{Game, Score, UpdateEvent};
+use crate::input::{self, Action, FrameInput, Input};
+use crate::render::{FrameBuffer, Layer};
+use crossterm::event::KeyCode;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Environment variable overriding the plugins directory.
+pub const PLUGINS_DIR_VAR: &str = "CL_GAMES_PLUGINS";
+pub const DEFAULT_PLUGINS_DIR: &str = "plugins";
+/// How long a plugin may take to send a line of its reply.
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// An external game found in the plugins directory.
+#[derive(Debug, Clone)]
+pub struct PluginInfo {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl PluginInfo {
+    /// Start the plugin process.
+    pub fn spawn(&self) -> std::io::Result<PluginGame> {
+        let mut child = Command::new(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        // Reading blocks, so it happens on its own thread and the game waits
+        // for lines with a timeout. The thread ends with the plugin's stdout.
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in stdout.lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(PluginGame {
+            child,
+            stdin,
+            lines,
+            frame: vec![],
+            score: 0,
+        })
+    }
+}
+
+/// Plugins directory: `$CL_GAMES_PLUGINS` or `./plugins`.
+pub fn plugins_dir() -> PathBuf {
+    std::env::var_os(PLUGINS_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_PLUGINS_DIR))
+}
+
+/// List executables in the given directory sorted by name.
+/// Missing directory means no plugins.
+pub fn discover(dir: &Path) -> Vec<PluginInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut plugins: Vec<PluginInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_executable(path))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            Some(PluginInfo { name, path })
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+
+    plugins
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() && path.extension().map_or(false, |ext| ext == "exe")
+}
+
+fn encode_key(input: &Option<Input>) -> String {
+    let code = match input::action(input) {
+        Some(Action::MoveUp) => Some(KeyCode::Up),
+        Some(Action::MoveDown) => Some(KeyCode::Down),
+        Some(Action::MoveLeft) => Some(KeyCode::Left),
+        Some(Action::MoveRight) => Some(KeyCode::Right),
+        Some(Action::Fire) => Some(KeyCode::Char(' ')),
+        _ => input.and_then(|input| input.key()).map(|key| key.code),
+    };
+    match code {
+        Some(KeyCode::Up) => "up".to_owned(),
+        Some(KeyCode::Down) => "down".to_owned(),
+        Some(KeyCode::Left) => "left".to_owned(),
+        Some(KeyCode::Right) => "right".to_owned(),
+        Some(KeyCode::Enter) => "enter".to_owned(),
+        Some(KeyCode::Backspace) => "backspace".to_owned(),
+        Some(KeyCode::Tab) => "tab".to_owned(),
+        Some(KeyCode::Char(c)) => format!("char:{}", c),
+        _ => "none".to_owned(),
+    }
+}
+
+/// Game backed by a plugin process.
+pub struct PluginGame {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<std::io::Result<String>>,
+    frame: Vec<String>,
+    score: i64,
+}
+
+impl PluginGame {
+    fn read_line(&mut self) -> std::io::Result<String> {
+        match self.lines.recv_timeout(REPLY_TIMEOUT) {
+            Ok(line) => Ok(line?.trim_end_matches('\r').to_owned()),
+            Err(RecvTimeoutError::Timeout) => {
+                // Hung plugin, also ends the reader thread
+                _ = self.child.kill();
+                Err(std::io::ErrorKind::TimedOut.into())
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(std::io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    fn exchange(
+        &mut self,
+        input: &Option<Input>,
+        delta_time: &std::time::Duration,
+    ) -> std::io::Result<UpdateEvent> {
+        use std::io::{Error, ErrorKind};
+
+        writeln!(
+            self.stdin,
+            "update {} {}",
+            delta_time.as_micros(),
+            encode_key(input)
+        )?;
+        self.stdin.flush()?;
+
+        let state = self.read_line()?;
+        let event = match state.split_whitespace().collect::<Vec<_>>()[..] {
+            ["state", event, score] => {
+                self.score = score
+                    .parse()
+                    .map_err(|_| Error::new(ErrorKind::InvalidData, "bad score"))?;
+                match event {
+                    "continue" => UpdateEvent::GameContinue,
+                    "over" => UpdateEvent::GameOver,
+                    _ => return Err(Error::new(ErrorKind::InvalidData, "bad state")),
+                }
+            }
+            _ => return Err(Error::new(ErrorKind::InvalidData, "expected state")),
+        };
+
+        let header = self.read_line()?;
+        let lines_count: usize = header
+            .strip_prefix("frame ")
+            .and_then(|count| count.parse().ok())
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "expected frame"))?;
+        self.frame.clear();
+        for _ in 0..lines_count {
+            let line = self.read_line()?;
+            self.frame
+                .push(line.chars().filter(|c| !c.is_control()).collect());
+        }
+
+        Ok(event)
+    }
+}
+
+impl Drop for PluginGame {
+    fn drop(&mut self) {
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+}
+
+impl Game for PluginGame {
+    fn update(&mut self, input: &FrameInput, delta_time: &std::time::Duration) -> UpdateEvent {
+        // quit
+        if input.action() == Some(Action::Quit) {
+            return UpdateEvent::GameOver;
+        }
+
+        // Broken plugin ends the game
+        self.exchange(&input.event, delta_time)
+            .unwrap_or(UpdateEvent::GameOver)
+    }
+
+    fn draw(&self, frame: &mut FrameBuffer, _delta_time: &std::time::Duration) {
+        for (y, line) in self.frame.iter().enumerate() {
+            frame.print(Layer::Game, 0, y as u16, line);
+        }
+    }
+
+    fn get_score(&self) -> Score {
+        Score { value: self.score }
+    }
+}
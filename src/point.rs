@@ -1,13 +1,33 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Height of a terminal cell over its width in most fonts.
+pub const DEFAULT_CELL_ASPECT: f32 = 2.0;
+
+/// Bits of the cell aspect used by the basis conversions.
+static CELL_ASPECT: AtomicU32 = AtomicU32::new(DEFAULT_CELL_ASPECT.to_bits());
+
+/// Height of a terminal cell over its width, the screen columns in a unit
+/// of the game world. Set once at the start, before any game is created.
+pub fn set_cell_aspect(aspect: f32) {
+    CELL_ASPECT.store(aspect.to_bits(), Ordering::Relaxed);
+}
+
+pub fn cell_aspect() -> f32 {
+    f32::from_bits(CELL_ASPECT.load(Ordering::Relaxed))
+}
+
 /// Point screen basis
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct ScreenBasis;
 
 /// Point game world basis
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct GameBasis;
 
 /// A point in the game world or screen.
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Point<Basis: Copy> {
     pub x: f32,
     pub y: f32,
@@ -70,6 +90,11 @@ where
     pub fn dot(&self, other: &Self) -> f32 {
         self.x * other.x + self.y * other.y
     }
+
+    /// Point `t` of the way from this one to `other`, `t` is from 0 to 1.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        *self + (*other - *self) * t
+    }
 }
 
 impl<Basis: Copy> std::ops::Add for Point<Basis> {
@@ -137,7 +162,7 @@ impl<Basis: Copy> std::ops::SubAssign for Point<Basis> {
 impl From<Point<ScreenBasis>> for Point<GameBasis> {
     fn from(point: Point<ScreenBasis>) -> Self {
         Point {
-            x: point.x / 2.0,
+            x: point.x / cell_aspect(),
             y: point.y,
             basis: std::marker::PhantomData,
         }
@@ -147,13 +172,191 @@ impl From<Point<ScreenBasis>> for Point<GameBasis> {
 impl From<Point<GameBasis>> for Point<ScreenBasis> {
     fn from(point: Point<GameBasis>) -> Self {
         Point {
-            x: point.x * 2.0,
+            x: point.x * cell_aspect(),
             y: point.y,
             basis: std::marker::PhantomData,
         }
     }
 }
 
+/// Way along an axis on the screen or the grid of a game, y grows down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Vector of length 1 the way of the direction.
+    pub fn as_vec<Basis: Copy>(self) -> Point<Basis> {
+        match self {
+            Direction::Up => Point::new(0.0, -1.0),
+            Direction::Down => Point::new(0.0, 1.0),
+            Direction::Left => Point::new(-1.0, 0.0),
+            Direction::Right => Point::new(1.0, 0.0),
+        }
+    }
+
+    /// The point `distance` away the way of the direction.
+    pub fn moved<Basis: Copy>(self, point: Point<Basis>, distance: f32) -> Point<Basis> {
+        point + self.as_vec() * distance
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// Quarter turn counterclockwise as seen on the screen.
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// Quarter turn clockwise as seen on the screen.
+    pub fn turn_right(self) -> Self {
+        self.turn_left().opposite()
+    }
+}
+
+/// Scale, rotation and translation of points, composed with `then`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform<Basis: Copy> {
+    /// Where the x and y axes go, as the columns of a matrix
+    x_axis: Point<Basis>,
+    y_axis: Point<Basis>,
+    translation: Point<Basis>,
+}
+
+impl<Basis: Copy> Transform<Basis> {
+    pub const IDENTITY: Self = Self {
+        x_axis: Point::new(1.0, 0.0),
+        y_axis: Point::new(0.0, 1.0),
+        translation: Point::new(0.0, 0.0),
+    };
+
+    pub fn translation(offset: Point<Basis>) -> Self {
+        Self {
+            translation: offset,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Turn by `angle` radians around the origin, from the x axis towards the y axis.
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            x_axis: Point::new(cos, sin),
+            y_axis: Point::new(-sin, cos),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Turn by `angle` radians around `center`.
+    pub fn rotation_around(angle: f32, center: Point<Basis>) -> Self {
+        Self::translation(center * -1.0)
+            .then(&Self::rotation(angle))
+            .then(&Self::translation(center))
+    }
+
+    /// Stretch away from the origin, by `x` along the x axis and by `y` along the y axis.
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self {
+            x_axis: Point::new(x, 0.0),
+            y_axis: Point::new(0.0, y),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// This transform, then the next one.
+    pub fn then(&self, next: &Self) -> Self {
+        Self {
+            x_axis: next.apply_linear(self.x_axis),
+            y_axis: next.apply_linear(self.y_axis),
+            translation: next.apply(self.translation),
+        }
+    }
+
+    /// Scale and rotation only, for directions.
+    pub fn apply_linear(&self, point: Point<Basis>) -> Point<Basis> {
+        self.x_axis * point.x + self.y_axis * point.y
+    }
+
+    pub fn apply(&self, point: Point<Basis>) -> Point<Basis> {
+        self.apply_linear(point) + self.translation
+    }
+}
+
+/// Screen cell at column `x` and row `y`.
+///
+/// Points become cells with `to_cell`, which rounds and gives None for points
+/// left of or above the screen, where a cast would put them in the first column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Cell {
+    pub x: u16,
+    pub y: u16,
+}
+
+impl Cell {
+    pub const fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+
+    /// Cell `dx` columns and `dy` rows away, None past the first or the last cell.
+    pub fn offset(self, dx: i32, dy: i32) -> Option<Self> {
+        Some(Self::new(
+            u16::try_from(i32::from(self.x) + dx).ok()?,
+            u16::try_from(i32::from(self.y) + dy).ok()?,
+        ))
+    }
+}
+
+impl From<Cell> for Point<ScreenBasis> {
+    fn from(cell: Cell) -> Self {
+        Point::new(cell.x as f32, cell.y as f32)
+    }
+}
+
+/// Rounded cell coordinate, None if it isn't one.
+fn cell_coordinate(value: f32) -> Option<u16> {
+    let value = value.round();
+    (0.0..=u16::MAX as f32)
+        .contains(&value)
+        .then_some(value as u16)
+}
+
+impl Point<ScreenBasis> {
+    /// Cell the point is drawn in.
+    pub fn to_cell(&self) -> Option<Cell> {
+        Some(Cell::new(
+            cell_coordinate(self.x)?,
+            cell_coordinate(self.y)?,
+        ))
+    }
+}
+
+impl Point<GameBasis> {
+    /// Size of the game world shown on a screen of the size.
+    pub fn from_screen_size(width: u16, height: u16) -> Self {
+        Point::<ScreenBasis>::new(width as f32, height as f32).into()
+    }
+
+    /// Cell the point is drawn in.
+    pub fn to_cell(&self) -> Option<Cell> {
+        Point::<ScreenBasis>::from(*self).to_cell()
+    }
+}
+
 #[derive(Debug)]
 pub enum BoundsCollision {
     Top,
@@ -184,7 +387,390 @@ impl Point<GameBasis> {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Axis-aligned rectangle from the top left corner `min` to the bottom right corner `max`.
+/// Edges belong to the rectangle, so touching rectangles intersect.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect<Basis: Copy> {
+    pub min: Point<Basis>,
+    pub max: Point<Basis>,
+}
+
+impl<Basis: Copy> Rect<Basis> {
+    /// Corners in any order.
+    pub fn new(a: Point<Basis>, b: Point<Basis>) -> Self {
+        Self {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// Rectangle reaching `half_size` from the center on each axis.
+    pub fn around(center: Point<Basis>, half_size: Point<Basis>) -> Self {
+        Self::new(center - half_size, center + half_size)
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn center(&self) -> Point<Basis> {
+        self.min.lerp(&self.max, 0.5)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    pub fn contains(&self, point: &Point<Basis>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// The other rectangle is inside this one, edges may touch.
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    /// Grown by `margin` on each side. A rectangle of half size `margin` moving by
+    /// its center touches this one where the center touches the grown one.
+    pub fn expanded(&self, margin: Point<Basis>) -> Self {
+        Self::new(self.min - margin, self.max + margin)
+    }
+
+    /// Closest point of the rectangle.
+    pub fn clamp(&self, point: Point<Basis>) -> Point<Basis> {
+        Point::new(
+            point.x.max(self.min.x).min(self.max.x),
+            point.y.max(self.min.y).min(self.max.y),
+        )
+    }
+
+    /// Random point of the rectangle, any point as likely as another.
+    pub fn random_point(&self, rng: &mut impl Rng) -> Point<Basis> {
+        Point::new(
+            rng.gen_range(self.min.x..=self.max.x),
+            rng.gen_range(self.min.y..=self.max.y),
+        )
+    }
+
+    /// Random point of the rectangle with whole coordinates where `is_free` holds,
+    /// None if there is no such point. A few random tries come first, then all
+    /// the points are checked, so a full board doesn't take forever.
+    pub fn random_free_point(
+        &self,
+        rng: &mut impl Rng,
+        mut is_free: impl FnMut(Point<Basis>) -> bool,
+    ) -> Option<Point<Basis>> {
+        const RANDOM_TRIES: usize = 32;
+
+        let (left, right) = (self.min.x.ceil() as i32, self.max.x.floor() as i32);
+        let (top, bottom) = (self.min.y.ceil() as i32, self.max.y.floor() as i32);
+        if left > right || top > bottom {
+            return None;
+        }
+        for _ in 0..RANDOM_TRIES {
+            let point = Point::new(
+                rng.gen_range(left..=right) as f32,
+                rng.gen_range(top..=bottom) as f32,
+            );
+            if is_free(point) {
+                return Some(point);
+            }
+        }
+        let free: Vec<Point<Basis>> = (top..=bottom)
+            .flat_map(|y| (left..=right).map(move |x| Point::new(x as f32, y as f32)))
+            .filter(|&point| is_free(point))
+            .collect();
+        (!free.is_empty()).then(|| free[rng.gen_range(0..free.len())])
+    }
+
+    /// The line has a point inside the rectangle.
+    pub fn intersects_line(&self, line: &Line<Basis>) -> bool {
+        self.clip_line(line).is_some()
+    }
+
+    /// Part of the line inside the rectangle, None if it is all outside.
+    pub fn clip_line(&self, line: &Line<Basis>) -> Option<Line<Basis>> {
+        let (enter, leave) = self.line_span(line)?;
+        // Ends inside stay exactly as they were
+        let at = |t: f32| match t {
+            t if t <= 0.0 => line.begin,
+            t if t >= 1.0 => line.end,
+            t => line.begin.lerp(&line.end, t),
+        };
+        Some(Line::new(at(enter), at(leave)))
+    }
+
+    /// How far along `path` a point moving on it first touches the rectangle,
+    /// from 0 at the beginning to 1 at the end. 0 if it starts inside, None if it misses.
+    /// Unlike checking where the point ends up, fast points don't pass through.
+    pub fn sweep(&self, path: &Line<Basis>) -> Option<f32> {
+        self.line_span(path).map(|(enter, _)| enter)
+    }
+
+    /// Part of the line within the rectangle on both axes, from 0 at `begin`
+    /// to 1 at `end`, None if it is all outside (Liang-Barsky).
+    fn line_span(&self, line: &Line<Basis>) -> Option<(f32, f32)> {
+        let (mut enter, mut leave) = (0.0_f32, 1.0_f32);
+        let axes = [
+            (line.begin.x, line.end.x, self.min.x, self.max.x),
+            (line.begin.y, line.end.y, self.min.y, self.max.y),
+        ];
+        for (begin, end, min, max) in axes {
+            let delta = end - begin;
+            if delta.abs() < f32::EPSILON {
+                if begin < min || begin > max {
+                    return None;
+                }
+                continue;
+            }
+            let (a, b) = ((min - begin) / delta, (max - begin) / delta);
+            enter = enter.max(a.min(b));
+            leave = leave.min(a.max(b));
+        }
+        (enter <= leave).then_some((enter, leave))
+    }
+}
+
+impl Rect<ScreenBasis> {
+    /// Centers of the cells of a screen of the size.
+    pub fn screen(width: u16, height: u16) -> Self {
+        Self::new(
+            Point::new(0.0, 0.0),
+            Point::new(width as f32 - 1.0, height as f32 - 1.0),
+        )
+    }
+}
+
+impl From<Rect<ScreenBasis>> for Rect<GameBasis> {
+    fn from(rect: Rect<ScreenBasis>) -> Self {
+        Self::new(rect.min.into(), rect.max.into())
+    }
+}
+
+impl From<Rect<GameBasis>> for Rect<ScreenBasis> {
+    fn from(rect: Rect<GameBasis>) -> Self {
+        Self::new(rect.min.into(), rect.max.into())
+    }
+}
+
+/// Part of the screen a game is played in, in the game basis.
+///
+/// Games place things from the top left corner of the area and only read
+/// its size; whoever runs the game draws its frame at `origin`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PlayArea {
+    /// Top left corner on the screen
+    pub origin: Point<GameBasis>,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PlayArea {
+    pub const fn new(origin: Point<GameBasis>, width: f32, height: f32) -> Self {
+        Self {
+            origin,
+            width,
+            height,
+        }
+    }
+
+    /// The whole screen of the size.
+    pub fn screen(width: u16, height: u16) -> Self {
+        let size = Point::<GameBasis>::from_screen_size(width, height);
+        Self::new(Point::new(0.0, 0.0), size.x, size.y)
+    }
+
+    /// The whole screen as it is now, see `util::screen_size`.
+    pub fn current() -> Self {
+        let (width, height) = crate::util::screen_size();
+        Self::screen(width, height)
+    }
+
+    /// Area of the size in the middle of a screen, cut to the screen if it's smaller.
+    pub fn centered(width: f32, height: f32, screen_width: u16, screen_height: u16) -> Self {
+        let screen = Self::screen(screen_width, screen_height);
+        let (width, height) = (width.min(screen.width), height.min(screen.height));
+        let origin = Point::new((screen.width - width) / 2.0, (screen.height - height) / 2.0);
+        Self::new(origin, width, height)
+    }
+
+    pub fn size(&self) -> Point<GameBasis> {
+        Point::new(self.width, self.height)
+    }
+
+    /// Columns and rows taken on the screen.
+    pub fn screen_size(&self) -> (u16, u16) {
+        let size = Point::<ScreenBasis>::from(self.size());
+        (size.x.round() as u16, size.y.round() as u16)
+    }
+
+    /// Screen cell of the top left corner.
+    pub fn origin_cell(&self) -> Cell {
+        self.origin.to_cell().unwrap_or_default()
+    }
+}
+
+/// Circle around `center`, the edge belongs to the circle.
+/// Only for the game basis, screen cells are not square.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Circle<Basis: Copy> {
+    pub center: Point<Basis>,
+    pub radius: f32,
+}
+
+impl<Basis: Copy> Circle<Basis> {
+    pub const fn new(center: Point<Basis>, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains(&self, point: &Point<Basis>) -> bool {
+        self.center.distance_to(point) <= self.radius
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.center.distance_to(&other.center) <= self.radius + other.radius
+    }
+
+    /// Some point of the line is in the circle.
+    pub fn intersects_line(&self, line: &Line<Basis>) -> bool {
+        line.distance_to(&self.center) <= self.radius
+    }
+}
+
+/// Convex polygon with the vertices in order around it, either way round.
+/// Edges belong to the polygon, so touching polygons intersect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Polygon<Basis: Copy> {
+    pub vertices: Vec<Point<Basis>>,
+}
+
+impl<Basis: Copy> Polygon<Basis> {
+    pub fn new(vertices: Vec<Point<Basis>>) -> Self {
+        Self { vertices }
+    }
+
+    pub fn translated(&self, offset: Point<Basis>) -> Self {
+        Self::new(
+            self.vertices
+                .iter()
+                .map(|&vertex| vertex + offset)
+                .collect(),
+        )
+    }
+
+    /// Turned by `angle` radians around `center`.
+    pub fn rotated(&self, angle: f32, center: Point<Basis>) -> Self {
+        self.transformed(&Transform::rotation_around(angle, center))
+    }
+
+    pub fn transformed(&self, transform: &Transform<Basis>) -> Self {
+        Self::new(
+            self.vertices
+                .iter()
+                .map(|&vertex| transform.apply(vertex))
+                .collect(),
+        )
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Line<Basis>> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices
+            .iter()
+            .zip(next)
+            .map(|(&begin, &end)| Line::new(begin, end))
+    }
+
+    /// Lowest and highest dot product of the vertices with the axis.
+    fn project(&self, axis: &Point<Basis>) -> (f32, f32) {
+        self.vertices
+            .iter()
+            .map(|vertex| vertex.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            })
+    }
+
+    /// Separating axis test: convex polygons are apart only if
+    /// their shadows on the normal of some edge don't overlap.
+    /// Points and segments, polygons with coinciding vertices, have edges
+    /// without a normal, the x and y axes part them along their line.
+    ///
+    /// ```
+    /// use cl_games::point::{Point, Polygon, ScreenBasis};
+    ///
+    /// let dot = |x, y| Polygon::<ScreenBasis>::new(vec![Point::new(x, y)]);
+    /// assert!(!dot(0.0, 0.0).intersects(&dot(5.0, 5.0)));
+    /// assert!(dot(1.0, 1.0).intersects(&dot(1.0, 1.0)));
+    /// ```
+    pub fn intersects(&self, other: &Self) -> bool {
+        if self.vertices.is_empty() || other.vertices.is_empty() {
+            return false;
+        }
+        let normals = self.edges().chain(other.edges()).filter_map(|edge| {
+            let direction = edge.end - edge.begin;
+            (direction.length() >= f32::EPSILON).then(|| Point::new(-direction.y, direction.x))
+        });
+        let axes = [Point::new(1.0, 0.0), Point::new(0.0, 1.0)];
+        normals.chain(axes).all(|axis| {
+            let (min, max) = self.project(&axis);
+            let (other_min, other_max) = other.project(&axis);
+            min <= other_max && other_min <= max
+        })
+    }
+
+    /// True if the point is inside or on an edge. Points and segments,
+    /// polygons without an area, contain only the points on their edges.
+    pub fn contains(&self, point: &Point<Basis>) -> bool {
+        let Some(&first) = self.vertices.first() else {
+            return false;
+        };
+        let far = self
+            .vertices
+            .iter()
+            .copied()
+            .max_by(|a, b| (*a - first).length().total_cmp(&(*b - first).length()))
+            .unwrap_or(first);
+        let is_flat = self
+            .vertices
+            .iter()
+            .all(|vertex| orientation(&first, &far, vertex) == 0);
+        if is_flat {
+            return self.edges().any(|edge| {
+                orientation(&edge.begin, &edge.end, point) == 0 && within(point, &edge)
+            });
+        }
+        // Points inside are on the same side of every edge, points on an edge on none
+        let sides = self
+            .edges()
+            .map(|edge| (edge.end - edge.begin).cross(&(*point - edge.begin)));
+        let (mut has_left, mut has_right) = (false, false);
+        for side in sides {
+            has_left |= side > 0.0;
+            has_right |= side < 0.0;
+        }
+        !(has_left && has_right)
+    }
+}
+
+impl<Basis: Copy> From<Rect<Basis>> for Polygon<Basis> {
+    fn from(rect: Rect<Basis>) -> Self {
+        Self::new(vec![
+            rect.min,
+            Point::new(rect.max.x, rect.min.y),
+            rect.max,
+            Point::new(rect.min.x, rect.max.y),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Line<Basis: Copy> {
     pub begin: Point<Basis>,
     pub end: Point<Basis>,
@@ -242,24 +828,93 @@ impl<Basis: Copy> Line<Basis> {
         }
     }
 
+    /// True if the segments touch, also when they only share an end
+    /// or lie on one line and overlap.
+    ///
+    /// ```
+    /// use cl_games::point::{Line, Point, ScreenBasis};
+    ///
+    /// let line = |ax, ay, bx, by| Line::<ScreenBasis>::new(Point::new(ax, ay), Point::new(bx, by));
+    /// assert!(line(0.0, 0.0, 4.0, 0.0).intersects(&line(2.0, 0.0, 6.0, 0.0)));
+    /// assert!(line(0.0, 0.0, 4.0, 0.0).intersects(&line(4.0, 0.0, 4.0, 3.0)));
+    /// assert!(!line(0.0, 0.0, 4.0, 0.0).intersects(&line(5.0, 0.0, 6.0, 0.0)));
+    /// ```
     pub fn intersects(&self, other: &Self) -> bool {
+        let (a, b, c, d) = (self.begin, self.end, other.begin, other.end);
+        let abc = orientation(&a, &b, &c);
+        let abd = orientation(&a, &b, &d);
+        let cda = orientation(&c, &d, &a);
+        let cdb = orientation(&c, &d, &b);
+
+        // Ends of each segment on both sides of the other one
+        if abc * abd < 0 && cda * cdb < 0 {
+            return true;
+        }
+        // An end on the other segment, collinear overlaps included
+        abc == 0 && within(&c, self)
+            || abd == 0 && within(&d, self)
+            || cda == 0 && within(&a, other)
+            || cdb == 0 && within(&b, other)
+    }
+
+    /// How far along this line a point moving on it touches `segment`,
+    /// from 0 at `begin` to 1 at `end`. On one line, where they start to overlap.
+    /// None if they don't touch.
+    pub fn sweep(&self, segment: &Self) -> Option<f32> {
+        if !self.intersects(segment) {
+            return None;
+        }
+
         let a = self.end - self.begin;
-        let b = other.end - other.begin;
-        let c = other.begin - self.begin;
+        let b = segment.end - segment.begin;
+        let c = segment.begin - self.begin;
 
         let det = a.cross(&b);
+        if det.abs() > COLLINEAR_EPSILON * a.length() * b.length() {
+            return Some((c.cross(&b) / det).clamp(0.0, 1.0));
+        }
 
-        if det.abs() < f32::EPSILON {
-            return false;
+        // Parallel, so collinear: the closest end of the segment along this line
+        let length = a.dot(&a);
+        if length < f32::EPSILON {
+            return Some(0.0);
         }
+        let t0 = c.dot(&a) / length;
+        let t1 = (segment.end - self.begin).dot(&a) / length;
+        Some(t0.min(t1).clamp(0.0, 1.0))
+    }
+}
 
-        let t = c.cross(&b) / det;
-        let u = c.cross(&a) / det;
+/// Sine of the angle under which points are taken as lying on one line.
+const COLLINEAR_EPSILON: f32 = 1e-5;
+/// Distance under which a point is taken as lying on a segment end.
+const TOUCH_EPSILON: f32 = 1e-4;
 
-        (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)
+/// Side of the line through `a` and `b` the point `c` is on:
+/// 1 or -1 for the two sides, 0 on the line.
+fn orientation<Basis: Copy>(a: &Point<Basis>, b: &Point<Basis>, c: &Point<Basis>) -> i8 {
+    let ab = *b - *a;
+    let ac = *c - *a;
+    let cross = ab.cross(&ac);
+    // Relative to the lengths, so long and short lines are alike
+    if cross.abs() <= COLLINEAR_EPSILON * ab.length() * ac.length() {
+        0
+    } else if cross > 0.0 {
+        1
+    } else {
+        -1
     }
 }
 
+/// True if the point, lying on the line of the segment, is between its ends.
+fn within<Basis: Copy>(point: &Point<Basis>, segment: &Line<Basis>) -> bool {
+    let (begin, end) = (segment.begin, segment.end);
+    point.x >= begin.x.min(end.x) - TOUCH_EPSILON
+        && point.x <= begin.x.max(end.x) + TOUCH_EPSILON
+        && point.y >= begin.y.min(end.y) - TOUCH_EPSILON
+        && point.y <= begin.y.max(end.y) + TOUCH_EPSILON
+}
+
 impl<Basis: Copy> std::ops::Add<Point<Basis>> for Line<Basis> {
     type Output = Self;
 
@@ -281,3 +936,109 @@ impl<Basis: Copy> std::ops::Sub<Point<Basis>> for Line<Basis> {
         }
     }
 }
+
+/// Waypoints joined by straight lines, walked by the distance from the first one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Path<Basis: Copy> {
+    pub waypoints: Vec<Point<Basis>>,
+}
+
+impl<Basis: Copy> Path<Basis> {
+    pub fn new(waypoints: Vec<Point<Basis>>) -> Self {
+        Self { waypoints }
+    }
+
+    /// Arc from `start` turning by `angle` radians around `center`,
+    /// from the x axis towards the y axis, made of `lines` straight lines.
+    pub fn arc(center: Point<Basis>, start: Point<Basis>, angle: f32, lines: usize) -> Self {
+        let lines = lines.max(1);
+        Self::new(
+            (0..=lines)
+                .map(|ind| {
+                    let turn = angle * ind as f32 / lines as f32;
+                    Transform::rotation_around(turn, center).apply(start)
+                })
+                .collect(),
+        )
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line<Basis>> + '_ {
+        self.waypoints
+            .windows(2)
+            .map(|pair| Line::new(pair[0], pair[1]))
+    }
+
+    pub fn length(&self) -> f32 {
+        self.lines().map(|line| line.length()).sum()
+    }
+
+    /// Point `distance` along the path from the first waypoint,
+    /// the first or the last one past the ends. Empty paths are at the origin.
+    pub fn at(&self, distance: f32) -> Point<Basis> {
+        let Some(&first) = self.waypoints.first() else {
+            return Point::new(0.0, 0.0);
+        };
+        if distance <= 0.0 {
+            return first;
+        }
+        let mut left = distance;
+        for line in self.lines() {
+            let length = line.length();
+            if length > 0.0 && left <= length {
+                return line.begin.lerp(&line.end, left / length);
+            }
+            left -= length;
+        }
+        *self.waypoints.last().unwrap_or(&first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(f32, f32)]) -> Polygon<ScreenBasis> {
+        Polygon::new(vertices.iter().map(|&(x, y)| Point::new(x, y)).collect())
+    }
+
+    #[test]
+    fn dot_contains_only_itself() {
+        let dot = polygon(&[(1.0, 1.0)]);
+        assert!(dot.contains(&Point::new(1.0, 1.0)));
+        assert!(!dot.contains(&Point::new(2.0, 1.0)));
+        assert!(!dot.contains(&Point::new(-3.0, 7.0)));
+    }
+
+    #[test]
+    fn segment_contains_only_its_points() {
+        let segment = polygon(&[(0.0, 0.0), (4.0, 0.0)]);
+        assert!(segment.contains(&Point::new(0.0, 0.0)));
+        assert!(segment.contains(&Point::new(2.0, 0.0)));
+        assert!(segment.contains(&Point::new(4.0, 0.0)));
+        assert!(!segment.contains(&Point::new(5.0, 0.0)));
+        assert!(!segment.contains(&Point::new(-1.0, 0.0)));
+        assert!(!segment.contains(&Point::new(2.0, 1.0)));
+    }
+
+    #[test]
+    fn collinear_vertices_contain_only_the_segment() {
+        let flat = polygon(&[(0.0, 0.0), (2.0, 2.0), (4.0, 4.0)]);
+        assert!(flat.contains(&Point::new(3.0, 3.0)));
+        assert!(!flat.contains(&Point::new(6.0, 6.0)));
+    }
+
+    #[test]
+    fn coinciding_vertices_are_a_dot() {
+        let dot = polygon(&[(1.0, 1.0), (1.0, 1.0), (1.0, 1.0)]);
+        assert!(dot.contains(&Point::new(1.0, 1.0)));
+        assert!(!dot.contains(&Point::new(1.0, 2.0)));
+    }
+
+    #[test]
+    fn square_contains_inside_and_edges() {
+        let square = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert!(square.contains(&Point::new(1.0, 1.0)));
+        assert!(square.contains(&Point::new(2.0, 1.0)));
+        assert!(!square.contains(&Point::new(3.0, 1.0)));
+    }
+}
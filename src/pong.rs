@@ -1,24 +1,43 @@
-use crate::game::{Game, Score, UpdateEvent, EXIT_BUTTON};
-use crate::point::{BoundsCollision, GameBasis, Line, Point, ScreenBasis};
-use crossterm::{cursor::MoveTo, event::KeyEvent, execute, style::Print, terminal};
-use rand::Rng;
-
-mod planks {
+use crate::config;
+use crate::difficulty;
+use crate::effects::ScoreEvent;
+use crate::game::{Game, Score, UpdateEvent};
+use crate::hud::Hud;
+use crate::input::{Action, FrameInput};
+use crate::kinematics::Body;
+use crate::point::{BoundsCollision, GameBasis, Line, PlayArea, Point, Rect, ScreenBasis};
+use crate::profiling::profile_scope;
+use crate::render::{Cell, FrameBuffer, Layer};
+use crate::script;
+use crate::sound::Effect;
+use crate::status::{Status, StatusEffects};
+use crossterm::event::MouseEventKind;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+pub mod planks {
     pub const FROM_BOUNDS_INDENT: u16 = 5;
     pub const DEFAULT_LENGTH: u16 = 5;
+    /// Player plank length after the chaos mode event
+    pub const SHRUNK_LENGTH: u16 = 2;
     pub const PLAYER_SPEED: f32 = 2.0;
+    /// Player speed steps per second while a move key is held
+    pub const HELD_STEPS_PER_SECOND: f32 = 15.0;
     pub const ENEMY_SPEED: f32 = 25.0;
-    pub const COLLISION_EXTRA_LENGTH: f32 = 1.0;
 }
 mod ball {
     use crate::point::{GameBasis, Point};
     pub const MAX_INITIAL_SPEED: Point<GameBasis> = Point::new(10.0, 10.0);
     pub const MIN_INITIAL_SPEED: Point<GameBasis> = Point::new(5.0, 5.0);
+    /// The ball takes a cell
+    pub const RADIUS: f32 = 0.5;
 }
 const VELOCITY_X_SCALE: f32 = 3.0;
+/// Closest the mouse moves the player plank to a wall
+const MOUSE_WALL_GAP: f32 = 0.5;
 const VELOCITY_Y_SCALE: f32 = 1.1;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Plank {
     position: Point<GameBasis>,
     length: u16,
@@ -27,40 +46,52 @@ pub struct Plank {
 impl Plank {
     fn new(w: u16, y: u16) -> Self {
         Self {
-            position: Point::new(w as f32 / 2.0 / 2.0, y as f32),
+            position: Point::<ScreenBasis>::new(w as f32 / 2.0, y as f32).into(),
             length: planks::DEFAULT_LENGTH,
         }
     }
 
-    fn draw(&self, out: &mut std::io::Stdout) -> crossterm::Result<()> {
+    fn draw(&self, frame: &mut FrameBuffer) {
         let screen_pos = Point::<ScreenBasis>::from(self.position);
-        let bx = (screen_pos.x.round() - self.length as f32) as u16;
-
-        for dx in (0..self.length).map(|x| x * 2) {
-            execute!(
-                out,
-                MoveTo(bx + dx, screen_pos.y.round() as u16),
-                Print("==")
-            )?;
+        let left_end = Point::<ScreenBasis>::new(screen_pos.x - self.length as f32, screen_pos.y);
+        let Some(cell) = left_end.to_cell() else {
+            return;
+        };
+
+        // As many columns as the plank is long on the screen
+        let columns = (self.length as f32 * crate::point::cell_aspect()).round() as u16;
+        let symbol = Cell {
+            symbol: '=',
+            ..Cell::default()
+        };
+        for column in 0..columns {
+            frame.set(Layer::Game, cell.x.saturating_add(column), cell.y, symbol);
         }
+    }
 
-        Ok(())
+    /// Place taken by the plank, a line as long as the plank.
+    fn rect(&self, position: Point<GameBasis>) -> Rect<GameBasis> {
+        Rect::around(position, Point::new(self.length as f32 / 2.0, 0.0))
     }
 
     fn bounds_check(&self, w: u16, next_position: Option<Point<GameBasis>>) -> bool {
-        next_position.unwrap_or(self.position).x - self.length as f32 / 2.0 > 0.0
-            && next_position.unwrap_or(self.position).x + self.length as f32 / 2.0 < w as f32 / 2.0
+        let rect = self.rect(next_position.unwrap_or(self.position));
+        // Touching a wall is out too
+        rect.min.x > 0.0 && rect.max.x < Point::<GameBasis>::from_screen_size(w, 0).x
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Ball {
-    position: Point<GameBasis>,
-    velocity: Point<GameBasis>,
+    #[serde(flatten)]
+    body: Body<GameBasis>,
+    /// Position before the last update, None for a new ball
+    #[serde(skip)]
+    previous: Option<Point<GameBasis>>,
 }
 
 impl Ball {
-    fn new(w: u16, h: u16) -> Self {
-        let mut rng = rand::thread_rng();
+    fn new(w: u16, h: u16, rng: &mut StdRng) -> Self {
         let mut velocity = Point::<GameBasis>::new(
             rng.gen::<i32>() as f32 % ball::MAX_INITIAL_SPEED.x,
             rng.gen::<i32>() as f32 % ball::MAX_INITIAL_SPEED.y,
@@ -74,103 +105,180 @@ impl Ball {
         }
 
         Self {
-            position: Point::<ScreenBasis>::new(w as f32 / 2.0, h as f32 / 2.0).into(),
-            velocity,
+            body: Body::new(
+                Point::<ScreenBasis>::new(w as f32 / 2.0, h as f32 / 2.0).into(),
+                velocity,
+            ),
+            previous: None,
         }
     }
 }
 
 /// Ball moves from `prev_ball_pos` to `ball_pos`
-/// Returns true if ball collides with plank on its way
-fn collides(
-    plank_pos: &Point<GameBasis>,
-    plank_length: f32,
-    prev_ball_pos: &Point<GameBasis>,
-    ball_pos: &Point<GameBasis>,
-) -> bool {
-    let plank = Line::new(
-        Point::new(
-            plank_pos.x - plank_length / 2.0 - planks::COLLISION_EXTRA_LENGTH,
-            plank_pos.y,
-        ),
-        Point::new(
-            plank_pos.x + plank_length / 2.0 + planks::COLLISION_EXTRA_LENGTH,
-            plank_pos.y,
-        ),
-    );
-    let ball = Line::new(*prev_ball_pos, *ball_pos);
-
-    plank.intersects(&ball)
+/// Returns true if ball touches the plank anywhere on its way,
+/// so a fast ball doesn't pass through between updates
+fn collides(plank: &Plank, prev_ball_pos: &Point<GameBasis>, ball_pos: &Point<GameBasis>) -> bool {
+    // The ball center touches the plank grown by the ball radius,
+    // a square ball near the plank ends
+    let reach = plank
+        .rect(plank.position)
+        .expanded(Point::new(ball::RADIUS, ball::RADIUS));
+
+    reach.sweep(&Line::new(*prev_ball_pos, *ball_pos)).is_some()
+}
+
+/// Values set by scripts, looked up once per game.
+#[derive(Clone, Copy)]
+struct Tweaks {
+    player_speed: Option<f32>,
+    enemy_speed: Option<f32>,
 }
 
+impl Tweaks {
+    fn load() -> Self {
+        Self {
+            player_speed: script::tweak("pong.player_speed"),
+            enemy_speed: script::tweak("pong.enemy_speed"),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PongGame {
     enemy: Plank,
     player: Plank,
     ball: Ball,
     score: i64,
+    /// Effects on the player plank
+    #[serde(default)]
+    status: StatusEffects,
+    /// Balls the player plank sent back
+    #[serde(default)]
+    returns: usize,
+    /// Balls that passed the player plank
+    #[serde(default)]
+    misses: usize,
+    #[serde(skip)]
+    score_events: Vec<ScoreEvent>,
+    #[serde(skip)]
+    sound_effects: Vec<Effect>,
+    /// Walls are at its edges
+    #[serde(default = "PlayArea::current")]
+    area: PlayArea,
+    #[serde(skip, default = "Tweaks::load")]
+    tweaks: Tweaks,
+    /// Not saved, loaded games get a new one
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng,
 }
 
 impl PongGame {
-    pub fn new() -> Self {
-        let (width, height) = terminal::size().expect("Failed to get terminal size");
+    /// Game in the area, ball directions are picked with the given random generator.
+    pub fn new(area: PlayArea, mut rng: StdRng) -> Self {
+        let (width, height) = area.screen_size();
 
         Self {
             enemy: Plank::new(width, planks::FROM_BOUNDS_INDENT),
             player: Plank::new(width, height - planks::FROM_BOUNDS_INDENT - 1),
-            ball: Ball::new(width, height),
+            ball: Ball::new(width, height, &mut rng),
             score: 0,
+            status: StatusEffects::default(),
+            returns: 0,
+            misses: 0,
+            score_events: vec![],
+            sound_effects: vec![],
+            area,
+            tweaks: Tweaks::load(),
+            rng,
         }
     }
 
     fn reset_positions(&mut self) {
-        let (width, height) = terminal::size().expect("Failed to get terminal size");
+        let (width, height) = self.area.screen_size();
 
         // self.enemy = Plank::new(width, planks::FROM_BOUNDS_INDENT);
         // self.player = Plank::new(width, height - planks::FROM_BOUNDS_INDENT - 1);
-        self.ball = Ball::new(width, height);
+        self.ball = Ball::new(width, height, &mut self.rng);
     }
 }
 
 impl Default for PongGame {
     fn default() -> Self {
-        Self::new()
+        Self::new(PlayArea::current(), StdRng::from_entropy())
     }
 }
 
 impl Game for PongGame {
-    fn update(
-        &mut self,
-        input: &Option<KeyEvent>,
-        delta_time: &std::time::Duration,
-    ) -> UpdateEvent {
+    fn update(&mut self, input: &FrameInput, delta_time: &std::time::Duration) -> UpdateEvent {
+        profile_scope!("pong.update");
+        #[derive(Debug)]
         enum OutOfBoard {
             OnEnemySide,
             OnPlayerSide,
         }
 
-        let (width, height) = terminal::size().expect("Failed to get terminal size");
+        let (width, height) = self.area.screen_size();
+
+        if self.status.tick(*delta_time).contains(&Status::Shrunk) {
+            self.player.length = planks::DEFAULT_LENGTH;
+        }
 
         // quit
-        if let Some(key) = input {
-            if key.code == EXIT_BUTTON {
-                return UpdateEvent::GameOver;
-            }
+        if input.action() == Some(Action::Quit) {
+            return UpdateEvent::GameOver;
         }
 
         // player input
         // modifies self.player
         {
             let prev_position = self.player.position;
-
-            if let Some(key) = input {
-                match key.code {
-                    crossterm::event::KeyCode::Left => {
-                        self.player.position.x -= planks::PLAYER_SPEED;
+            let player_speed = self
+                .tweaks
+                .player_speed
+                .unwrap_or_else(|| config::get().pong.player_speed);
+
+            // Held keys move the plank smoothly, taps move it by a step
+            let held_step = player_speed * planks::HELD_STEPS_PER_SECOND * delta_time.as_secs_f32();
+            if input.is_held(Action::MoveLeft) {
+                self.player.position.x -= held_step;
+            } else if input.is_held(Action::MoveRight) {
+                self.player.position.x += held_step;
+            } else {
+                // Quick taps in one frame all count, up to the wall
+                let presses = &input.presses;
+                let taps = presses.count(Action::MoveRight) as i32
+                    - presses.count(Action::MoveLeft) as i32;
+                for _ in 0..taps.unsigned_abs() {
+                    let tap_start = self.player.position;
+                    self.player.position.x += player_speed * taps.signum() as f32;
+                    if !self.player.bounds_check(width, None) {
+                        self.player.position = tap_start;
+                        break;
                     }
-                    crossterm::event::KeyCode::Right => {
-                        self.player.position.x += planks::PLAYER_SPEED;
-                    }
-                    _ => {}
+                }
+            }
+
+            // The plank follows the mouse, up to the walls
+            if let Some(mouse) = input.mouse() {
+                if matches!(
+                    mouse.kind,
+                    MouseEventKind::Moved | MouseEventKind::Drag(_) | MouseEventKind::Down(_)
+                ) {
+                    let y = self.player.position.y;
+                    let pointed =
+                        Point::<GameBasis>::from(Point::<ScreenBasis>::new(mouse.column as f32, y));
+                    // Where the plank center can go
+                    let half_length = self.player.length as f32 / 2.0;
+                    let reach = Rect::new(
+                        Point::new(half_length + MOUSE_WALL_GAP, y),
+                        Point::new(
+                            Point::<GameBasis>::from_screen_size(width, 0).x
+                                - half_length
+                                - MOUSE_WALL_GAP,
+                            y,
+                        ),
+                    );
+                    self.player.position = reach.clamp(pointed);
                 }
             }
 
@@ -183,11 +291,16 @@ impl Game for PongGame {
         // modifies self.enemy
         {
             let prev_position = self.enemy.position;
-
-            if self.ball.position.x < self.enemy.position.x {
-                self.enemy.position.x -= planks::ENEMY_SPEED * delta_time.as_secs_f32();
-            } else if self.ball.position.x > self.enemy.position.x {
-                self.enemy.position.x += planks::ENEMY_SPEED * delta_time.as_secs_f32();
+            let enemy_speed = self
+                .tweaks
+                .enemy_speed
+                .unwrap_or_else(|| config::get().pong.enemy_speed)
+                * difficulty::level();
+
+            if self.ball.body.position.x < self.enemy.position.x {
+                self.enemy.position.x -= enemy_speed * delta_time.as_secs_f32();
+            } else if self.ball.body.position.x > self.enemy.position.x {
+                self.enemy.position.x += enemy_speed * delta_time.as_secs_f32();
             }
 
             if !self.enemy.bounds_check(width, None) {
@@ -198,16 +311,17 @@ impl Game for PongGame {
         // ball
         // modifies self.ball
         let out_of_board: Option<OutOfBoard> = {
-            let prev_position = self.ball.position;
+            let prev_position = self.ball.body.position;
+            self.ball.previous = Some(prev_position);
             let mut out_of_board = None;
 
-            self.ball.position.x += self.ball.velocity.x * delta_time.as_secs_f32();
-            self.ball.position.y += self.ball.velocity.y * delta_time.as_secs_f32();
+            // Where the ball would get, it moves once the bounces are known
+            let mut next_position = self.ball.body.next_position(delta_time.as_secs_f32());
 
-            match self.ball.position.bounds_check(width, height) {
+            match next_position.bounds_check(width, height) {
                 Some(BoundsCollision::Left | BoundsCollision::Right) => {
-                    self.ball.velocity.x *= -1.0;
-                    self.ball.position.x = prev_position.x;
+                    self.ball.body.velocity.x *= -1.0;
+                    next_position.x = prev_position.x;
                 }
                 Some(BoundsCollision::Top) => {
                     out_of_board = Some(OutOfBoard::OnEnemySide);
@@ -220,29 +334,25 @@ impl Game for PongGame {
 
             // enemy/player collision
             {
-                let plank = if self.ball.velocity.y < 0.0 {
+                let plank = if self.ball.body.velocity.y < 0.0 {
                     &self.enemy
                 } else {
                     &self.player
                 };
 
-                if collides(
-                    &plank.position,
-                    plank.length as f32,
-                    &prev_position,
-                    &self.ball.position,
-                ) {
-                    self.ball.velocity.y *= -1.0;
+                if collides(plank, &prev_position, &next_position) {
+                    self.ball.body.velocity.y *= -1.0;
                     // velocity.x change depends on ball position relative to plank
-                    self.ball.velocity.x +=
-                        (self.ball.position.x - plank.position.x) * VELOCITY_X_SCALE;
-                    self.ball.velocity.y *= VELOCITY_Y_SCALE;
+                    self.ball.body.velocity.x +=
+                        (next_position.x - plank.position.x) * VELOCITY_X_SCALE;
+                    self.ball.body.velocity.y *= VELOCITY_Y_SCALE;
+                    if self.ball.body.velocity.y < 0.0 {
+                        self.returns += 1;
+                    }
                 }
             }
 
-            self.ball.position = prev_position;
-            self.ball.position.x += self.ball.velocity.x * delta_time.as_secs_f32();
-            self.ball.position.y += self.ball.velocity.y * delta_time.as_secs_f32();
+            self.ball.body.integrate(delta_time);
 
             out_of_board
         };
@@ -250,87 +360,148 @@ impl Game for PongGame {
         // check collision
         // modifies self.score, self.ball, self.enemy, self.player
         if let Some(out_of_board) = out_of_board {
-            match out_of_board {
-                OutOfBoard::OnEnemySide => {
-                    self.score += 1;
-                    self.reset_positions();
-                }
+            let points = match out_of_board {
+                OutOfBoard::OnEnemySide => 1,
                 OutOfBoard::OnPlayerSide => {
-                    self.score -= 1;
-                    self.reset_positions();
+                    self.misses += 1;
+                    -1
                 }
-            }
+            };
+            self.score += points;
+            tracing::debug!(?out_of_board, score = self.score, "ball out of the board");
+
+            // Where the ball left, kept on the screen
+            let (width, height) = self.area.screen_size();
+            let mut event = ScoreEvent::at(points, self.ball.body.position);
+            event.x = event.x.min(width.saturating_sub(1));
+            event.y = event.y.min(height.saturating_sub(1));
+            self.score_events.push(event);
+            self.sound_effects.push(if points > 0 {
+                Effect::PointScored
+            } else {
+                Effect::PointLost
+            });
+
+            self.reset_positions();
         }
 
         UpdateEvent::GameContinue
     }
 
-    fn draw(
+    fn draw(&self, frame: &mut FrameBuffer, delta_time: &std::time::Duration) {
+        self.draw_interpolated(frame, delta_time, 1.0);
+    }
+
+    fn draw_interpolated(
         &self,
-        out: &mut std::io::Stdout,
+        frame: &mut FrameBuffer,
         _delta_time: &std::time::Duration,
-    ) -> crossterm::Result<()> {
-        use crossterm::style::Stylize;
-        use std::io::Write;
+        alpha: f32,
+    ) {
+        profile_scope!("pong.draw");
 
-        let (width, height) = terminal::size()?;
+        let (width, height) = self.area.screen_size();
 
         // draw planks
         {
-            self.player.draw(out)?;
-            self.enemy.draw(out)?;
+            self.player.draw(frame);
+            self.enemy.draw(frame);
         }
 
         // draw ball
         {
-            let screen_pos = Point::<ScreenBasis>::from(self.ball.position);
-
-            execute!(
-                out,
-                MoveTo(screen_pos.x.round() as u16, screen_pos.y.round() as u16),
-                Print("()")
-            )?;
-        }
-
-        // score
-        {
-            fn digits_num(num: i64) -> u16 {
-                let num = num.abs();
-                if num == 0 {
-                    1
-                } else {
-                    f32::floor(f32::log10(num as f32) + 1.0) as u16
-                }
+            // The path since the last update, cut where it leaves the screen
+            let path = Line::new(
+                self.ball.previous.unwrap_or(self.ball.body.position).into(),
+                self.ball.body.position.into(),
+            );
+            let clipped = Rect::screen(width, height).clip_line(&path);
+            if let Some(cell) = clipped.and_then(|path| path.begin.lerp(&path.end, alpha).to_cell())
+            {
+                frame.print(Layer::Game, cell.x, cell.y, "()");
             }
-
-            let score_hint = "Score: ";
-            let score = format!("{}", self.score);
-            execute!(
-                out,
-                MoveTo(
-                    width
-                        - score_hint.len() as u16
-                        - digits_num(self.score)
-                        - (self.score < 0) as u16,
-                    height / 2
-                ),
-            )?;
-            write!(
-                out,
-                "{}{}",
-                score_hint,
-                if self.score < 0 {
-                    score.red()
-                } else {
-                    score.green()
-                }
-            )?;
         }
 
-        execute!(out, MoveTo(0, 0))
+        self.status.draw(frame, 0, height.saturating_sub(1));
     }
 
     fn get_score(&self) -> Score {
         Score { value: self.score }
     }
+
+    fn hud(&self, hud: &mut Hud) {
+        let color = if self.score < 0 {
+            crate::theme::palette().losing
+        } else {
+            crate::theme::palette().winning
+        };
+        hud.colored_score(self.score, color);
+    }
+
+    fn resize(&mut self, area: PlayArea) {
+        self.area = area;
+    }
+
+    fn take_score_events(&mut self) -> Vec<ScoreEvent> {
+        std::mem::take(&mut self.score_events)
+    }
+
+    fn take_sound_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.sound_effects)
+    }
+
+    fn chaos_event(&mut self) -> Option<String> {
+        self.player.length = planks::SHRUNK_LENGTH;
+        self.status
+            .add(Status::Shrunk, Some(crate::chaos::EFFECT_TIME));
+        Some(crate::i18n::tr(crate::i18n::Text::PlankShrinks).to_owned())
+    }
+
+    fn save(&self) -> Option<crate::save::SavedGame> {
+        Some(crate::save::SavedGame::Pong(self.clone()))
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Game>> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn help(&self) -> Vec<String> {
+        use crate::help::key;
+        use crate::i18n::{format, tr, Text};
+
+        let sideways = format!("{}/{}", key(Action::MoveLeft), key(Action::MoveRight));
+        vec![
+            format(Text::PongMove, &[&sideways]),
+            tr(Text::PongPoint).to_owned(),
+            tr(Text::PongMiss).to_owned(),
+            tr(Text::PongEndless).to_owned(),
+        ]
+    }
+
+    fn summary(&self) -> Vec<String> {
+        use crate::i18n::{format, Text};
+
+        vec![
+            format(Text::BallsReturned, &[&self.returns]),
+            format(Text::BallsMissed, &[&self.misses]),
+        ]
+    }
+
+    /// Share of the balls coming down that were sent back
+    fn performance(&self) -> Option<crate::stats::Performance> {
+        if self.returns + self.misses == 0 {
+            return None;
+        }
+
+        Some(crate::stats::Performance {
+            name: crate::i18n::Text::PongPerformance,
+            value: self.returns as f32 / (self.returns + self.misses) as f32,
+            tip: crate::i18n::Text::PongTip,
+        })
+    }
+
+    /// The rally going on counts as lost
+    fn rally_length(&self) -> Option<f32> {
+        Some(self.returns as f32 / (self.misses + 1) as f32)
+    }
 }
@@ -0,0 +1,111 @@
+//! Input macros for practice runs, stored in `<data dir>/cl_games/macros/<game id>.json`.
+//!
+//! A macro is recorded in a run started from the practice menu: every frame keeps
+//! its key and frame time. Practice runs replay it at once on a game made with
+//! the same seed, so they start from the same position.
+use crate::game::{Game, UpdateEvent};
+use crate::input::{Action, FrameInput, Input, Presses};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const DIR_NAME: &str = "macros";
+/// Ends the recording, the run goes on.
+pub const STOP_BUTTON: KeyCode = KeyCode::Char('r');
+/// Longer recordings are stopped automatically.
+pub const MAX_LENGTH: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize)]
+pub struct InputMacro {
+    /// Seed of the game random generator
+    pub seed: u64,
+    /// Screen size of the recording, games depending on it differ on other sizes
+    pub screen_size: (u16, u16),
+    /// Key name (see `config::parse_key`) and frame time of every frame
+    pub frames: Vec<(Option<String>, Duration)>,
+    /// Actions held down in every frame, empty in older recordings
+    #[serde(default)]
+    pub held: Vec<Vec<Action>>,
+    /// Actions pressed in every frame, empty in older recordings
+    #[serde(default)]
+    pub presses: Vec<Presses>,
+}
+
+pub fn path(game_id: &str) -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(DIR_NAME).join(format!("{}.json", game_id)))
+}
+
+impl InputMacro {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            screen_size: crate::util::screen_size(),
+            frames: vec![],
+            held: vec![],
+            presses: vec![],
+        }
+    }
+
+    pub fn exists(game_id: &str) -> bool {
+        path(game_id).is_some_and(|path| path.exists())
+    }
+
+    /// Missing or broken file means no macro.
+    pub fn load(game_id: &str) -> Option<Self> {
+        let text = std::fs::read_to_string(path(game_id)?).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    /// Replaces the previous macro of the game.
+    pub fn store(&self, game_id: &str) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path =
+            path(game_id).ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text =
+            serde_json::to_string(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|(_, delta)| *delta).sum()
+    }
+
+    /// Returns false when the recording is full.
+    pub fn push(&mut self, input: &FrameInput, delta_time: Duration) -> bool {
+        // Mouse input is not recorded
+        let key = input
+            .key()
+            .and_then(|key| crate::config::key_name(key.code));
+        self.frames.push((key, delta_time));
+        self.held.push(input.held.clone());
+        self.presses.push(input.presses.clone());
+        self.duration() < MAX_LENGTH
+    }
+
+    /// Feed the recorded frames to the game without drawing.
+    /// Stops early if the game ends.
+    pub fn apply(&self, game: &mut dyn Game) {
+        for (ind, (key, delta_time)) in self.frames.iter().enumerate() {
+            let input = key
+                .as_deref()
+                .and_then(crate::config::parse_key)
+                .map(|code| Input::from_key(KeyEvent::new(code, KeyModifiers::NONE)));
+            let held = self.held.get(ind).cloned().unwrap_or_default();
+            let presses = self.presses.get(ind).cloned().unwrap_or_default();
+            let input = FrameInput::new(input).with_presses(presses).with_held(held);
+            let event = game.update(&input, delta_time);
+            if let UpdateEvent::GameOver = event {
+                break;
+            }
+        }
+        // Setup moves are not shown as points and not recorded in the heatmap
+        _ = game.take_score_events();
+        _ = game.take_sound_effects();
+        _ = game.take_heat_points();
+    }
+}
@@ -0,0 +1,88 @@
+//! Items most programs using the library need, in one import:
+//!
+//! ```
+//! use cl_games::prelude::*;
+//! ```
+//!
+//! Enums and structs that are likely to grow are `#[non_exhaustive]`,
+//! so matching them needs a `_` arm and options are made with their `new`.
+//!
+//! Running a built-in game without a terminal:
+//!
+//! ```
+//! use cl_games::prelude::*;
+//!
+//! let registry = Registry::builtin();
+//! let id = registry.games()[0].id;
+//! let mut options = HeadlessOptions::new(id);
+//! options.frames = 100;
+//! options.seed = Some(7);
+//! let report = run_headless(&options).unwrap();
+//! assert!(report.frames <= 100);
+//! ```
+//!
+//! A game of your own, placed in the middle of the screen:
+//!
+//! ```
+//! use cl_games::prelude::*;
+//! use std::time::Duration;
+//!
+//! struct Counter {
+//!     presses: i64,
+//! }
+//!
+//! impl Game for Counter {
+//!     fn update(&mut self, input: &FrameInput, _delta_time: &Duration) -> UpdateEvent {
+//!         match input.action() {
+//!             Some(Action::Quit) => UpdateEvent::GameOver,
+//!             Some(_) => {
+//!                 self.presses += 1;
+//!                 UpdateEvent::GameContinue
+//!             }
+//!             None => UpdateEvent::GameContinue,
+//!         }
+//!     }
+//!
+//!     fn draw(&self, frame: &mut FrameBuffer, _delta_time: &Duration) {
+//!         let area = Rect::of_frame(frame).centered(12, 1);
+//!         frame.print(Layer::Hud, area.x, area.y, &format!("Presses: {}", self.presses));
+//!     }
+//!
+//!     fn get_score(&self) -> Score {
+//!         Score { value: self.presses }
+//!     }
+//! }
+//!
+//! let mut registry = Registry::builtin();
+//! registry.register(GameEntry {
+//!     name: "Counter",
+//!     id: "counter",
+//!     description: "Press keys",
+//!     par_score: 10,
+//!     create: |_rng, _area| Box::new(Counter { presses: 0 }),
+//! });
+//! ```
+pub use crate::game::{Game, Score, UpdateEvent};
+pub use crate::harness::Harness;
+pub use crate::headless::{
+    run as run_headless, Options as HeadlessOptions, Report as HeadlessReport,
+};
+pub use crate::input::{Action, FrameInput, Input};
+pub use crate::layout::{Direction, Rect, Size};
+pub use crate::point::{GameBasis, Line, PlayArea, Point, ScreenBasis};
+pub use crate::registry::{GameEntry, Registry};
+pub use crate::render::{FrameBuffer, Layer};
+pub use crate::snapshot;
+pub use crate::ui;
+
+#[cfg(feature = "pong")]
+pub use crate::{config::PongConfig, pong::PongGame};
+#[cfg(feature = "snake")]
+pub use crate::{config::SnakeConfig, snake::SnakeGame};
+#[cfg(feature = "invaders")]
+pub use crate::{
+    config::SpaceInvadersConfig,
+    space_invaders::{EnemyPreset, PropsPreset, SpaceInvadersGame},
+};
+#[cfg(feature = "tetris")]
+pub use crate::{config::TetrisConfig, tetris::TetrisGame};
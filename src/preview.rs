@@ -0,0 +1,24 @@
+//! Still pictures of the games for the menu: a game made from a fixed seed
+//! and drawn in a part of the screen, as the versus halves are.
+use crate::frame::{Frame, Lines};
+use crate::layout::Rect;
+use crate::registry::GameEntry;
+use crate::render::FrameBuffer;
+
+/// Seed of the previewed games, so a preview is the same every time.
+pub const SEED: u64 = 0;
+/// Inside of the preview box; smaller previews are not drawn.
+pub const MIN_SIZE: (u16, u16) = (24, 8);
+
+/// Draw the start of the game in a box filling `area`.
+pub fn draw(entry: &GameEntry, frame: &mut FrameBuffer, area: Rect) {
+    let box_frame = Frame::new().title(entry.name).lines(Lines::Single);
+    let inner = box_frame.inner(area);
+    if inner.width < MIN_SIZE.0 || inner.height < MIN_SIZE.1 {
+        return;
+    }
+    box_frame.draw(frame, crate::render::Layer::Hud, area);
+
+    let view = crate::snapshot::of_entry(entry, SEED, inner.width, inner.height);
+    frame.blit(&view, inner.x, inner.y);
+}
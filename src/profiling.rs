@@ -0,0 +1,29 @@
+//! Spans around the phases of the game updates and drawing, built only with
+//! the `profiling` feature. Span ends and their busy time go to the log:
+//!
+//! ```text
+//! cargo run --release --features profiling -- --log trace
+//! grep close <data dir>/cl_games/cl_games.log.*
+//! ```
+//!
+//! Other `tracing` subscribers (flame graphs, Tracy and such) see the spans too.
+
+/// Trace span from here to the end of the block, named like "invaders.enemies".
+/// Nothing without the `profiling` feature.
+macro_rules! profile_scope {
+    ($name:literal) => {
+        #[cfg(feature = "profiling")]
+        let _span = tracing::trace_span!($name).entered();
+    };
+}
+
+pub(crate) use profile_scope;
+
+/// Log span ends with their time, see `logging::init`.
+#[cfg(feature = "profiling")]
+pub const SPAN_EVENTS: tracing_subscriber::fmt::format::FmtSpan =
+    tracing_subscriber::fmt::format::FmtSpan::CLOSE;
+/// Log no span events.
+#[cfg(not(feature = "profiling"))]
+pub const SPAN_EVENTS: tracing_subscriber::fmt::format::FmtSpan =
+    tracing_subscriber::fmt::format::FmtSpan::NONE;
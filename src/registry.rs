@@ -0,0 +1,128 @@
+//! Built-in games known to the menu and the headless mode.
+//!
+//! A new game needs one `register` call in `Registry::builtin`.
+use crate::game::Game;
+use crate::point::PlayArea;
+use rand::rngs::StdRng;
+
+/// How to make a game and how to show it.
+pub struct GameEntry {
+    /// Name in the menu
+    pub name: &'static str,
+    /// Name on the command line
+    pub id: &'static str,
+    pub description: &'static str,
+    /// Score of a good run, scores of different games are compared relative to it
+    pub par_score: i64,
+    /// Game played in the given part of the screen
+    pub create: fn(StdRng, PlayArea) -> Box<dyn Game>,
+}
+
+pub struct Registry {
+    games: Vec<GameEntry>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self { games: vec![] }
+    }
+
+    pub fn register(&mut self, entry: GameEntry) {
+        self.games.push(entry);
+    }
+
+    /// All games compiled in, in the menu order.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+
+        #[cfg(feature = "snake")]
+        registry.register(GameEntry {
+            name: "Snake",
+            id: "snake",
+            description: "Eat apples and don't bite yourself",
+            par_score: 20,
+            create: |rng, area| {
+                Box::new(crate::snake::SnakeGame::new(
+                    crate::point::Point::new(10.0, 10.0),
+                    area,
+                    rng,
+                ))
+            },
+        });
+        #[cfg(feature = "tetris")]
+        registry.register(GameEntry {
+            name: "Tetris",
+            id: "tetris",
+            description: "Fill rows with falling figures",
+            par_score: 2000,
+            create: |rng, _| {
+                let game = crate::tetris::TetrisGame::new(rng);
+                Box::new(if crate::script::is_mutated("tetris.garbage") {
+                    game.with_garbage(crate::tetris::GARBAGE_ROWS)
+                } else {
+                    game
+                })
+            },
+        });
+        #[cfg(feature = "invaders")]
+        registry.register(GameEntry {
+            name: "Space invaders",
+            id: "invaders",
+            description: "Shoot down the wave before it shoots you",
+            par_score: 30,
+            create: |rng, area| {
+                use crate::space_invaders::{EnemyAction, PropsPreset, SpaceInvadersGame};
+
+                let (width, height) = area.screen_size();
+                let props = if crate::script::is_mutated("space_invaders.no_cover") {
+                    PropsPreset::Empty
+                } else {
+                    PropsPreset::Wall
+                };
+                let game = SpaceInvadersGame::new(
+                    height,
+                    width,
+                    crate::space_invaders::EnemyPreset::RandomFire,
+                    props,
+                    rng,
+                );
+                let behavior = crate::script::invader_behavior();
+                Box::new(if behavior.is_empty() {
+                    game
+                } else {
+                    let actions: Vec<EnemyAction> =
+                        behavior.iter().map(|&action| action.into()).collect();
+                    game.with_behavior(&actions)
+                })
+            },
+        });
+        #[cfg(feature = "pong")]
+        registry.register(GameEntry {
+            name: "Pong",
+            id: "pong",
+            description: "Beat the computer plank",
+            par_score: 5,
+            create: |rng, area| Box::new(crate::pong::PongGame::new(area, rng)),
+        });
+
+        registry
+    }
+
+    pub fn games(&self) -> &[GameEntry] {
+        &self.games
+    }
+
+    pub fn get(&self, ind: usize) -> Option<&GameEntry> {
+        self.games.get(ind)
+    }
+
+    pub fn find(&self, id: &str) -> Option<&GameEntry> {
+        self.games.iter().find(|entry| entry.id == id)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,444 @@
+use crossterm::style::{ContentStyle, StyledContent};
+use std::fmt::Display;
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Drawing layers from the bottom to the top.
+/// Upper layers hide lower ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Background,
+    Game,
+    Hud,
+    Overlay,
+}
+
+const LAYERS_COUNT: usize = Layer::Overlay as usize + 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub symbol: char,
+    pub style: ContentStyle,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: ' ',
+            style: ContentStyle::new(),
+        }
+    }
+}
+
+/// Bar like `[####    ]` taking `width` symbols, `fill` is from 0 to 1.
+pub fn bar(fill: f32, width: u16) -> Bar {
+    let inner = width.saturating_sub(2) as usize;
+    let filled = ((fill.clamp(0.0, 1.0) * inner as f32).round() as usize).min(inner);
+    Bar { filled, inner }
+}
+
+/// Symbols the text takes, counted without building a string.
+pub fn width(content: &dyn Display) -> u16 {
+    struct Counter(u16);
+
+    impl std::fmt::Write for Counter {
+        fn write_str(&mut self, text: &str) -> std::fmt::Result {
+            self.0 = self.0.saturating_add(text.chars().count() as u16);
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    _ = std::fmt::write(&mut counter, format_args!("{}", content));
+    counter.0
+}
+
+/// Bar made by `bar`, written without building a string.
+#[derive(Clone, Copy, Debug)]
+pub struct Bar {
+    filled: usize,
+    inner: usize,
+}
+
+impl Display for Bar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{:#<filled$}{:empty$}]",
+            "",
+            "",
+            filled = self.filled,
+            empty = self.inner - self.filled
+        )
+    }
+}
+
+/// Off-screen frame: grid of styled cells in several layers.
+/// Games draw into it and the frame is written to the terminal at once.
+pub struct FrameBuffer {
+    width: u16,
+    height: u16,
+    layers: [Vec<Option<Cell>>; LAYERS_COUNT],
+}
+
+impl FrameBuffer {
+    pub fn new(width: u16, height: u16) -> Self {
+        let size = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            layers: std::array::from_fn(|_| vec![None; size]),
+        }
+    }
+
+    /// Frame of the terminal size.
+    pub fn from_terminal() -> crossterm::Result<Self> {
+        let (width, height) = crossterm::terminal::size()?;
+        Ok(Self::new(width, height))
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Remove everything drawn. Size changes to the given one.
+    pub fn clear(&mut self, width: u16, height: u16) {
+        let size = width as usize * height as usize;
+        self.width = width;
+        self.height = height;
+        for layer in self.layers.iter_mut() {
+            layer.clear();
+            layer.resize(size, None);
+        }
+    }
+
+    pub fn set(&mut self, layer: Layer, x: u16, y: u16, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.layers[layer as usize][y as usize * self.width as usize + x as usize] = Some(cell);
+        }
+    }
+
+    /// Write styled text starting from the given cell.
+    /// Symbols out of the frame are dropped.
+    /// Numbers and `format_args!` are written into the cells without a string.
+    pub fn print_styled<D: Display>(
+        &mut self,
+        layer: Layer,
+        x: u16,
+        y: u16,
+        content: StyledContent<D>,
+    ) {
+        self.print_styled_until(layer, x, y, u16::MAX, content);
+    }
+
+    /// `print_styled` with the symbols from column `end` on dropped.
+    /// Returns the column after the text.
+    pub fn print_styled_until<D: Display>(
+        &mut self,
+        layer: Layer,
+        x: u16,
+        y: u16,
+        end: u16,
+        content: StyledContent<D>,
+    ) -> u16 {
+        use std::fmt::Write;
+
+        let mut cells = CellWriter {
+            frame: self,
+            layer,
+            x: Some(x),
+            y,
+            end,
+            style: *content.style(),
+        };
+        // Only the formatting of `content` can fail, the cells are always written
+        _ = write!(cells, "{}", content.content());
+        cells.x.unwrap_or(u16::MAX)
+    }
+
+    /// Write unstyled text starting from the given cell.
+    pub fn print(&mut self, layer: Layer, x: u16, y: u16, text: &str) {
+        self.print_styled(layer, x, y, StyledContent::new(ContentStyle::new(), text));
+    }
+
+    /// Write unstyled `format_args!` text without building a string.
+    pub fn print_args(&mut self, layer: Layer, x: u16, y: u16, args: std::fmt::Arguments) {
+        self.print_styled(layer, x, y, StyledContent::new(ContentStyle::new(), args));
+    }
+
+    /// Copy everything drawn in `source` with its top left corner at the given cell,
+    /// layer by layer. Cells out of this frame are dropped.
+    pub fn blit(&mut self, source: &FrameBuffer, x: u16, y: u16) {
+        for (layer, cells) in self.layers.iter_mut().zip(source.layers.iter()) {
+            for (ind, cell) in cells.iter().enumerate() {
+                let Some(cell) = cell else {
+                    continue;
+                };
+                let target_x = x as usize + ind % source.width as usize;
+                let target_y = y as usize + ind / source.width as usize;
+                if target_x < self.width as usize && target_y < self.height as usize {
+                    layer[target_y * self.width as usize + target_x] = Some(*cell);
+                }
+            }
+        }
+    }
+
+    /// Topmost cell at the given position.
+    pub fn get(&self, x: u16, y: u16) -> Cell {
+        let ind = y as usize * self.width as usize + x as usize;
+        self.layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer[ind])
+            .unwrap_or_default()
+    }
+
+    /// Symbols of the frame without styles, a line of `width` symbols per row.
+    pub fn lines(&self) -> Vec<String> {
+        (0..self.height)
+            .map(|y| (0..self.width).map(|x| self.get(x, y).symbol).collect())
+            .collect()
+    }
+
+    /// Write the frame to a terminal-like output with a single write and flush.
+    /// Commands are queued in memory first: stdout writes out every kilobyte,
+    /// which showed half-drawn frames on slow terminals.
+    pub fn flush<W: std::io::Write>(&self, out: &mut W) -> crossterm::Result<()> {
+        self.flush_changes(None, out)
+    }
+
+    /// Write only the cells that differ from `previous`, the frame on the terminal now.
+    /// The whole frame is written without it or when the size changed.
+    pub fn flush_changes<W: std::io::Write>(
+        &self,
+        previous: Option<&FrameBuffer>,
+        out: &mut W,
+    ) -> crossterm::Result<()> {
+        let previous = previous
+            .filter(|previous| (previous.width, previous.height) == (self.width, self.height));
+        let mut buffer = Vec::with_capacity(self.width as usize * self.height as usize * 4);
+        self.queue(previous, &mut buffer)?;
+        out.write_all(&buffer)?;
+        out.flush()
+    }
+
+    /// Queue the commands drawing the cells that differ from `previous`, all without it.
+    fn queue<W: std::io::Write>(
+        &self,
+        previous: Option<&FrameBuffer>,
+        out: &mut W,
+    ) -> crossterm::Result<()> {
+        use crossterm::{cursor::MoveTo, queue, style::PrintStyledContent};
+
+        for y in 0..self.height {
+            let is_changed =
+                |x: u16| previous.is_none_or(|previous| previous.get(x, y) != self.get(x, y));
+            let mut x = 0;
+            while x < self.width {
+                if !is_changed(x) {
+                    x += 1;
+                    continue;
+                }
+                queue!(out, MoveTo(x, y))?;
+
+                // Changed cells with the same style are printed together
+                let mut run = String::new();
+                let mut run_style = ContentStyle::new();
+                while x < self.width && is_changed(x) {
+                    let cell = self.get(x, y);
+                    if cell.style != run_style && !run.is_empty() {
+                        queue!(
+                            out,
+                            PrintStyledContent(StyledContent::new(run_style, run.as_str()))
+                        )?;
+                        run.clear();
+                    }
+                    run_style = cell.style;
+                    run.push(cell.symbol);
+                    x += 1;
+                }
+                queue!(
+                    out,
+                    PrintStyledContent(StyledContent::new(run_style, run.as_str()))
+                )?;
+            }
+        }
+        queue!(out, MoveTo(0, 0))
+    }
+}
+
+impl Clone for FrameBuffer {
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            layers: self.layers.clone(),
+        }
+    }
+
+    /// Copy into the cells already there, so copying a frame of the same size allocates nothing.
+    fn clone_from(&mut self, source: &Self) {
+        self.width = source.width;
+        self.height = source.height;
+        for (layer, source) in self.layers.iter_mut().zip(source.layers.iter()) {
+            layer.clone_from(source);
+        }
+    }
+}
+
+/// Symbols printed into a frame one after another from a cell to the right.
+struct CellWriter<'a> {
+    frame: &'a mut FrameBuffer,
+    layer: Layer,
+    /// None past the last column
+    x: Option<u16>,
+    y: u16,
+    /// Column the text is cut at
+    end: u16,
+    style: ContentStyle,
+}
+
+impl std::fmt::Write for CellWriter<'_> {
+    fn write_str(&mut self, text: &str) -> std::fmt::Result {
+        for symbol in text.chars() {
+            let Some(x) = self.x.filter(|&x| x < self.end) else {
+                break;
+            };
+            self.frame.set(
+                self.layer,
+                x,
+                self.y,
+                Cell {
+                    symbol,
+                    style: self.style,
+                },
+            );
+            self.x = x.checked_add(1);
+        }
+        Ok(())
+    }
+}
+
+/// Plain text of the frame without styles, one line per row.
+impl Display for FrameBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct RenderState {
+    /// Latest frame not written yet
+    pending: Option<FrameBuffer>,
+    /// Frame written before the last one, its cells are reused for the next frame
+    spare: Option<FrameBuffer>,
+    /// First failed write, returned by the next call
+    error: Option<std::io::Error>,
+    is_stopped: bool,
+}
+
+/// Writes frames to stdout from its own thread, so a slow terminal (SSH,
+/// Windows console) doesn't stall game updates. When the thread falls behind,
+/// only the latest frame is written.
+pub struct RenderThread {
+    state: Arc<(Mutex<RenderState>, Condvar)>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl RenderThread {
+    pub fn spawn() -> Self {
+        let state = Arc::new((Mutex::new(RenderState::default()), Condvar::new()));
+        let thread_state = Arc::clone(&state);
+        let thread = std::thread::spawn(move || {
+            let (lock, wakeup) = &*thread_state;
+            // Frame on the terminal, only the cells changed since are written
+            let mut written: Option<FrameBuffer> = None;
+            loop {
+                let frame = {
+                    let mut state = lock.lock().expect("render state lock poisoned");
+                    while state.pending.is_none() && !state.is_stopped {
+                        state = wakeup.wait(state).expect("render state lock poisoned");
+                    }
+                    match state.pending.take() {
+                        Some(frame) => frame,
+                        None => break,
+                    }
+                };
+
+                // Other writes to stdout wait for the whole frame
+                let result = frame.flush_changes(written.as_ref(), &mut std::io::stdout().lock());
+                if let Err(err) = result {
+                    let mut state = lock.lock().expect("render state lock poisoned");
+                    state.error.get_or_insert(err);
+                    // Some of the frame may be missing, the next one is written whole
+                    written = None;
+                } else if let Some(old) = written.replace(frame) {
+                    lock.lock().expect("render state lock poisoned").spare = Some(old);
+                }
+            }
+        });
+
+        Self {
+            state,
+            thread: Some(thread),
+        }
+    }
+
+    /// Replace the frame waiting to be written with a copy of the frame.
+    /// The copy goes into a frame already written, so it allocates nothing most of the time.
+    /// Fails with the error of a previous write.
+    pub fn submit(&self, frame: &FrameBuffer) -> crossterm::Result<()> {
+        let (lock, wakeup) = &*self.state;
+        let mut state = lock.lock().expect("render state lock poisoned");
+        if let Some(err) = state.error.take() {
+            return Err(err);
+        }
+        let mut copy = state
+            .pending
+            .take()
+            .or_else(|| state.spare.take())
+            .unwrap_or_else(|| FrameBuffer::new(0, 0));
+        copy.clone_from(frame);
+        state.pending = Some(copy);
+        wakeup.notify_one();
+        Ok(())
+    }
+
+    /// Write the waiting frame and stop the thread.
+    /// Must be called before writing to stdout directly.
+    pub fn finish(mut self) -> crossterm::Result<()> {
+        self.stop();
+        let (lock, _) = &*self.state;
+        match lock
+            .lock()
+            .expect("render state lock poisoned")
+            .error
+            .take()
+        {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn stop(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        let (lock, wakeup) = &*self.state;
+        lock.lock().expect("render state lock poisoned").is_stopped = true;
+        wakeup.notify_one();
+        _ = thread.join();
+    }
+}
+
+impl Drop for RenderThread {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
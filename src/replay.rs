@@ -0,0 +1,216 @@
+use crate::game::Game;
+use crate::input::{self, FrameInput};
+use crate::render::{FrameBuffer, Layer};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// How much of the run is kept for the replay.
+pub const LENGTH: Duration = Duration::from_secs(5);
+/// Replay is played this times slower than the game.
+pub const SLOW_MOTION: u32 = 2;
+pub const REPLAY_BUTTON: crossterm::event::KeyCode = crossterm::event::KeyCode::Char('r');
+/// Game time between the game state snapshots, the frames between them are simulated again.
+pub const KEYFRAME_PERIOD: Duration = Duration::from_secs(1);
+
+/// What happened to the game after its keyframe, in order.
+enum Record {
+    /// Input of the updates up to the next drawn frame.
+    /// Only the first update of them gets its key and presses
+    Input(FrameInput),
+    /// An update with the difficulty level it was made with,
+    /// followed by a chaos event if `is_chaos`
+    Step {
+        delta: Duration,
+        level: Option<f32>,
+        is_chaos: bool,
+    },
+    /// Frame drawn after this much game time since the previous one
+    Frame(Duration),
+}
+
+/// Game state and what happened to it until the next keyframe.
+struct Keyframe {
+    game: Box<dyn Game>,
+    records: Vec<Record>,
+    /// Game time of the frames in `records`
+    duration: Duration,
+}
+
+/// Rolling record of a run: game snapshots now and then
+/// and the inputs to simulate the frames between them.
+pub struct ReplayBuffer {
+    keyframes: VecDeque<Keyframe>,
+    length: Duration,
+    duration: Duration,
+    /// Updates were recorded since the last frame
+    is_frame_open: bool,
+}
+
+impl ReplayBuffer {
+    pub fn new(length: Duration) -> Self {
+        Self {
+            keyframes: VecDeque::new(),
+            length,
+            duration: Duration::from_nanos(0),
+            is_frame_open: false,
+        }
+    }
+
+    /// Remember the update about to be made to the game.
+    /// Games without snapshots are not recorded.
+    pub fn step(
+        &mut self,
+        game: &dyn Game,
+        input: &FrameInput,
+        delta_time: Duration,
+        level: Option<f32>,
+    ) {
+        if !self.is_frame_open {
+            let is_keyframe_due = self
+                .keyframes
+                .back()
+                .is_none_or(|keyframe| keyframe.duration >= KEYFRAME_PERIOD);
+            if is_keyframe_due {
+                if let Some(game) = game.snapshot() {
+                    self.keyframes.push_back(Keyframe {
+                        game,
+                        records: vec![],
+                        duration: Duration::ZERO,
+                    });
+                }
+            }
+            let Some(keyframe) = self.keyframes.back_mut() else {
+                return;
+            };
+            keyframe.records.push(Record::Input(input.clone()));
+            self.is_frame_open = true;
+        }
+
+        if let Some(keyframe) = self.keyframes.back_mut() {
+            keyframe.records.push(Record::Step {
+                delta: delta_time,
+                level,
+                is_chaos: false,
+            });
+        }
+    }
+
+    /// A chaos event happened after the last update.
+    pub fn chaos_event(&mut self) {
+        if let Some(Record::Step { is_chaos, .. }) = self
+            .keyframes
+            .back_mut()
+            .and_then(|keyframe| keyframe.records.last_mut())
+        {
+            *is_chaos = true;
+        }
+    }
+
+    /// The updates since the last frame are drawn, `delta_time` is their time.
+    /// Drops keyframes older than the buffer length.
+    pub fn frame(&mut self, delta_time: Duration) {
+        if !std::mem::take(&mut self.is_frame_open) {
+            return;
+        }
+        let Some(keyframe) = self.keyframes.back_mut() else {
+            return;
+        };
+        keyframe.records.push(Record::Frame(delta_time));
+        keyframe.duration += delta_time;
+        self.duration += delta_time;
+
+        while self.keyframes.len() > 1 && self.duration - self.keyframes[0].duration >= self.length
+        {
+            if let Some(keyframe) = self.keyframes.pop_front() {
+                self.duration -= keyframe.duration;
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.duration.is_zero()
+    }
+
+    /// Draw the last recorded frames in slow motion, simulating them from the oldest keyframe.
+    /// Any key from `rx` stops the replay, the mouse does not.
+    pub fn play(
+        &self,
+        out: &mut std::io::Stdout,
+        rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+    ) -> crossterm::Result<()> {
+        use crossterm::event::{Event, KeyEventKind};
+        use crossterm::style::Stylize;
+
+        let Some(mut game) = self
+            .keyframes
+            .front()
+            .and_then(|first| first.game.snapshot())
+        else {
+            return Ok(());
+        };
+        let mut buffer = FrameBuffer::from_terminal()?;
+        // Frames before it are only simulated
+        let mut to_start = self.duration.saturating_sub(self.length);
+        let mut input = FrameInput::default();
+
+        for record in self.keyframes.iter().flat_map(|keyframe| &keyframe.records) {
+            let delta = match record {
+                Record::Input(frame_input) => {
+                    input = frame_input.clone();
+                    continue;
+                }
+                Record::Step {
+                    delta,
+                    level,
+                    is_chaos,
+                } => {
+                    match level {
+                        Some(level) => {
+                            crate::difficulty::with_level(*level, || game.update(&input, delta))
+                        }
+                        None => game.update(&input, delta),
+                    };
+                    if *is_chaos {
+                        game.chaos_event();
+                    }
+                    input.event = None;
+                    input.presses = input::Presses::default();
+                    // Not played, dropped so they don't pile up
+                    game.take_score_events();
+                    game.take_sound_effects();
+                    continue;
+                }
+                Record::Frame(delta) => delta,
+            };
+            if !to_start.is_zero() {
+                to_start = to_start.saturating_sub(*delta);
+                continue;
+            }
+
+            // The release of the key that started the replay doesn't stop it
+            if rx
+                .try_iter()
+                .any(|event| matches!(event, Event::Key(key) if key.kind != KeyEventKind::Release))
+            {
+                break;
+            }
+
+            let (width, height) = crossterm::terminal::size()?;
+            buffer.clear(width, height);
+            game.draw(&mut buffer, delta);
+
+            let title = crate::i18n::tr(crate::i18n::Text::Replay);
+            buffer.print_styled(
+                Layer::Overlay,
+                width.saturating_sub(title.len() as u16) / 2,
+                0,
+                title.with(crate::theme::palette().danger).bold(),
+            );
+            buffer.flush(out)?;
+
+            std::thread::sleep(*delta * SLOW_MOTION);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+//! Saved run to continue later, stored in `<data dir>/cl_games/save.json`.
+//!
+//! Only one run is kept; saving replaces it and continuing removes it.
+//! Runs interrupted by quitting are saved here too, to be resumed from the menu,
+//! and runs in progress are autosaved, so a closed terminal or a crash loses little.
+use crate::game::Game;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+pub const FILE_NAME: &str = "save.json";
+/// Time played between autosaves of a run.
+pub const AUTOSAVE_PERIOD: Duration = Duration::from_secs(15);
+
+/// State of a game that supports saving.
+#[derive(Serialize, Deserialize)]
+pub enum SavedGame {
+    #[cfg(feature = "snake")]
+    Snake(crate::snake::SnakeGame),
+    #[cfg(feature = "tetris")]
+    Tetris(crate::tetris::TetrisGame),
+    #[cfg(feature = "invaders")]
+    SpaceInvaders(crate::space_invaders::SpaceInvadersGame),
+    #[cfg(feature = "pong")]
+    Pong(crate::pong::PongGame),
+}
+
+impl SavedGame {
+    pub fn into_game(self) -> Box<dyn Game> {
+        match self {
+            #[cfg(feature = "snake")]
+            SavedGame::Snake(game) => Box::new(game),
+            #[cfg(feature = "tetris")]
+            SavedGame::Tetris(game) => Box::new(game),
+            #[cfg(feature = "invaders")]
+            SavedGame::SpaceInvaders(game) => Box::new(game),
+            #[cfg(feature = "pong")]
+            SavedGame::Pong(game) => Box::new(game),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveFile {
+    /// Game name as in the menu
+    pub name: String,
+    /// Score when saved, shown in the menu
+    #[serde(default)]
+    pub score: i64,
+    pub game: SavedGame,
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+impl SaveFile {
+    /// Read the saved run. Missing or broken file means no saved run.
+    pub fn load() -> Option<Self> {
+        let text = std::fs::read_to_string(path()?).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    pub fn store(&self) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path = path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text =
+            serde_json::to_string(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+
+    /// Forget the saved run.
+    pub fn remove() -> std::io::Result<()> {
+        match path() {
+            Some(path) if path.exists() => std::fs::remove_file(path),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Writes saves from its own thread, so a run doesn't stutter on a slow disk.
+/// When the thread falls behind, only the latest save is written.
+pub struct Autosaver {
+    tx: Sender<SaveFile>,
+    /// Returns the last failed write
+    thread: JoinHandle<Option<std::io::Error>>,
+}
+
+impl Autosaver {
+    pub fn spawn() -> Self {
+        let (tx, rx) = channel::<SaveFile>();
+        let thread = std::thread::spawn(move || {
+            let mut error = None;
+            while let Ok(file) = rx.recv() {
+                let file = rx.try_iter().last().unwrap_or(file);
+                error = file.store().err();
+            }
+            error
+        });
+        Self { tx, thread }
+    }
+
+    pub fn submit(&self, file: SaveFile) {
+        // The thread only stops when the sender is dropped
+        _ = self.tx.send(file);
+    }
+
+    /// Write the waiting save and stop the thread.
+    /// Must be called before the save file is written or removed directly.
+    pub fn finish(self) -> std::io::Result<()> {
+        drop(self.tx);
+        match self.thread.join().expect("autosave thread panicked") {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
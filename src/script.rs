@@ -0,0 +1,283 @@
+//! User scripts adjusting the games at load time (`scripting` feature).
+//!
+//! Every `*.rhai` file in the scripts directory is evaluated once on startup.
+//! Scripts only see these functions:
+//!
+//! - `set(name, value)` and `get(name)`: balance values, see [`tweak`]
+//! - `invader_behavior(actions)`: actions every invader repeats instead of the
+//!   built-in ones. An action is `"left"`, `"right"`, `"up"`, `"down"`,
+//!   `"fire"`, `"wait"` or a map with the action and its `chance` in percents
+//!   or `seconds` to wait, see [`invader_behavior`]
+//! - `mutate(name)`: turn on one of the [`MUTATORS`]
+//!
+//! ```text
+//! set("snake.speed", 16.0);
+//! set("tetris.descend_slow", 0.1);
+//! set("pong.enemy_speed", get("snake.speed") * 2.0);
+//! invader_behavior(["right", "down", "left", #{ action: "fire", chance: 30 }]);
+//! mutate("space_invaders.no_cover");
+//! ```
+//!
+//! Games read the values once when they are created. Without the feature
+//! every value keeps its built-in default.
+use std::time::Duration;
+
+/// Environment variable overriding the scripts directory.
+pub const SCRIPTS_DIR_VAR: &str = "CL_GAMES_SCRIPTS";
+pub const DEFAULT_SCRIPTS_DIR: &str = "scripts";
+
+/// Rule changes scripts can turn on with `mutate(name)`.
+pub const MUTATORS: &[&str] = &[
+    // Space invaders start without the wall
+    "space_invaders.no_cover",
+    // Tetris starts with rows of garbage at the bottom
+    "tetris.garbage",
+];
+
+/// What a scripted invader does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvaderMove {
+    Left,
+    Right,
+    Up,
+    Down,
+    Fire,
+    Wait(Duration),
+}
+
+/// Invader action set by a script, taken with the chance in percents.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvaderAction {
+    pub kind: InvaderMove,
+    pub chance: f32,
+}
+
+#[cfg(feature = "scripting")]
+mod engine {
+    use super::{InvaderAction, InvaderMove, MUTATORS};
+    use once_cell::sync::OnceCell;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    const MAX_OPERATIONS: u64 = 100_000;
+    const MAX_CALL_LEVELS: usize = 16;
+
+    /// Everything set by the scripts.
+    #[derive(Default, Clone)]
+    pub struct Scripted {
+        pub tweaks: HashMap<String, f64>,
+        pub invader_behavior: Vec<InvaderAction>,
+        pub mutators: Vec<&'static str>,
+    }
+
+    pub static SCRIPTED: OnceCell<Scripted> = OnceCell::new();
+
+    fn number(value: &rhai::Dynamic) -> Option<f64> {
+        value
+            .as_float()
+            .ok()
+            .or_else(|| value.as_int().ok().map(|value| value as f64))
+    }
+
+    fn invader_action(value: rhai::Dynamic) -> Result<InvaderAction, String> {
+        let (name, chance, seconds) = if value.is_string() {
+            (value.into_string()?, None, None)
+        } else if let Some(map) = value.try_cast::<rhai::Map>() {
+            let name = map
+                .get("action")
+                .and_then(|name| name.clone().into_string().ok())
+                .ok_or("invader action without a name")?;
+            let chance = map
+                .get("chance")
+                .map(|chance| number(chance).ok_or("bad chance"));
+            let seconds = map
+                .get("seconds")
+                .map(|seconds| number(seconds).ok_or("bad seconds"));
+            (name, chance.transpose()?, seconds.transpose()?)
+        } else {
+            return Err("invader action must be a name or a map".to_owned());
+        };
+
+        let chance = chance.unwrap_or(100.0);
+        if !(0.0..=100.0).contains(&chance) {
+            return Err(format!("chance {} is not in percents", chance));
+        }
+        let kind = match name.as_str() {
+            "left" => InvaderMove::Left,
+            "right" => InvaderMove::Right,
+            "up" => InvaderMove::Up,
+            "down" => InvaderMove::Down,
+            "fire" => InvaderMove::Fire,
+            "wait" => InvaderMove::Wait(
+                Duration::try_from_secs_f64(seconds.unwrap_or(1.0)).map_err(|_| "bad seconds")?,
+            ),
+            _ => return Err(format!("unknown invader action {:?}", name)),
+        };
+
+        Ok(InvaderAction {
+            kind,
+            chance: chance as f32,
+        })
+    }
+
+    fn sandboxed_engine(scripted: Rc<RefCell<Scripted>>) -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.disable_symbol("eval");
+        // Scripts must not draw over the game
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+
+        engine.register_fn("set", {
+            let scripted = scripted.clone();
+            move |name: &str, value: f64| {
+                scripted.borrow_mut().tweaks.insert(name.to_owned(), value);
+            }
+        });
+        engine.register_fn("set", {
+            let scripted = scripted.clone();
+            move |name: &str, value: i64| {
+                scripted
+                    .borrow_mut()
+                    .tweaks
+                    .insert(name.to_owned(), value as f64);
+            }
+        });
+        engine.register_fn("get", {
+            let scripted = scripted.clone();
+            move |name: &str| -> rhai::Dynamic {
+                scripted
+                    .borrow()
+                    .tweaks
+                    .get(name)
+                    .map_or(rhai::Dynamic::UNIT, |&value| value.into())
+            }
+        });
+        engine.register_fn("invader_behavior", {
+            let scripted = scripted.clone();
+            move |actions: rhai::Array| -> Result<(), Box<rhai::EvalAltResult>> {
+                if actions.is_empty() {
+                    return Err("invader behavior without actions".into());
+                }
+                let actions = actions
+                    .into_iter()
+                    .map(invader_action)
+                    .collect::<Result<_, _>>()?;
+                scripted.borrow_mut().invader_behavior = actions;
+                Ok(())
+            }
+        });
+        engine.register_fn(
+            "mutate",
+            move |name: &str| -> Result<(), Box<rhai::EvalAltResult>> {
+                let mutator = MUTATORS
+                    .iter()
+                    .find(|&&mutator| mutator == name)
+                    .ok_or_else(|| format!("unknown mutator {:?}", name))?;
+                scripted.borrow_mut().mutators.push(mutator);
+                Ok(())
+            },
+        );
+
+        engine
+    }
+
+    /// Run all scripts from `dir` in name order.
+    /// Returns error messages of failed scripts.
+    pub fn load(dir: &Path) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return vec![];
+        };
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        paths.sort();
+
+        let scripted = Rc::new(RefCell::new(Scripted::default()));
+        let engine = sandboxed_engine(scripted.clone());
+        let mut errors = vec![];
+
+        for path in paths {
+            if let Err(err) = engine.run_file(path.clone()) {
+                errors.push(format!("{}: {}", path.display(), err));
+            }
+        }
+
+        _ = SCRIPTED.set(scripted.borrow().clone());
+
+        errors
+    }
+}
+
+/// Scripts directory: `$CL_GAMES_SCRIPTS` or `./scripts`.
+pub fn scripts_dir() -> std::path::PathBuf {
+    std::env::var_os(SCRIPTS_DIR_VAR)
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::PathBuf::from(DEFAULT_SCRIPTS_DIR))
+}
+
+/// Evaluate user scripts. Must be called once before any game is created.
+/// Returns error messages of failed scripts.
+#[cfg(feature = "scripting")]
+pub fn load_scripts() -> Vec<String> {
+    engine::load(&scripts_dir())
+}
+
+#[cfg(not(feature = "scripting"))]
+pub fn load_scripts() -> Vec<String> {
+    vec![]
+}
+
+/// Value set by scripts, None keeps the built-in one.
+/// Looked up by name, games resolve their values when created.
+#[cfg(feature = "scripting")]
+pub fn tweak(name: &str) -> Option<f32> {
+    engine::SCRIPTED
+        .get()
+        .and_then(|scripted| scripted.tweaks.get(name))
+        .map(|&value| value as f32)
+}
+
+#[cfg(not(feature = "scripting"))]
+pub fn tweak(_name: &str) -> Option<f32> {
+    None
+}
+
+/// Actions every invader repeats, empty keeps the built-in behaviors.
+#[cfg(feature = "scripting")]
+pub fn invader_behavior() -> &'static [InvaderAction] {
+    engine::SCRIPTED
+        .get()
+        .map_or(&[], |scripted| &scripted.invader_behavior)
+}
+
+#[cfg(not(feature = "scripting"))]
+pub fn invader_behavior() -> &'static [InvaderAction] {
+    &[]
+}
+
+/// The mutator, one of the [`MUTATORS`], is turned on by a script.
+#[cfg(feature = "scripting")]
+pub fn is_mutated(name: &str) -> bool {
+    engine::SCRIPTED
+        .get()
+        .is_some_and(|scripted| scripted.mutators.contains(&name))
+}
+
+#[cfg(not(feature = "scripting"))]
+pub fn is_mutated(_name: &str) -> bool {
+    false
+}
+
+/// Duration in seconds set by scripts or `default`.
+pub fn seconds_or(tweak: Option<f32>, default: Duration) -> Duration {
+    tweak
+        .and_then(|seconds| Duration::try_from_secs_f32(seconds.max(0.0)).ok())
+        .unwrap_or(default)
+}
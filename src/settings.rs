@@ -0,0 +1,60 @@
+//! Settings changed in the settings menu, kept in `<data dir>/cl_games/settings.toml`.
+//! They win over the same settings in the config file, unset ones follow it.
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+pub const FILE_NAME: &str = "settings.toml";
+
+static SETTINGS: Lazy<RwLock<Settings>> = Lazy::new(|| RwLock::new(Settings::load()));
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// None means the theme of the config
+    pub theme: Option<crate::theme::Name>,
+    /// None means the colorblind mode of the config
+    pub colorblind: Option<bool>,
+    /// None means the language of the config
+    pub language: Option<crate::i18n::Language>,
+    /// Player name given in the first-run setup
+    pub name: Option<String>,
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+impl Settings {
+    /// Missing or broken file means nothing changed in the menu.
+    pub fn load() -> Self {
+        path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path = path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+}
+
+/// Current settings.
+pub fn get() -> Settings {
+    SETTINGS.read().expect("settings lock poisoned").clone()
+}
+
+/// Change the settings and save them. The change applies even if saving fails.
+pub fn update(change: impl FnOnce(&mut Settings)) -> std::io::Result<()> {
+    let mut settings = SETTINGS.write().expect("settings lock poisoned");
+    change(&mut settings);
+    settings.save()
+}
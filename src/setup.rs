@@ -0,0 +1,143 @@
+//! Setup run on the first launch, when there is neither a config file nor saved data:
+//! the theme, the symbols, the movement keys and the player name.
+//! The picks are written to the config file, the name to the settings.
+use crate::i18n::{format, tr, Text};
+use crate::menu::{Item, Menu};
+use std::sync::mpsc::Receiver;
+
+/// Keys moving in the games and the menus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Controls {
+    Arrows,
+    Wasd,
+    Vim,
+}
+
+impl Controls {
+    /// Keys for up, down, left and right, None for the default arrows.
+    fn keys(self) -> Option<[&'static str; 4]> {
+        match self {
+            Controls::Arrows => None,
+            Controls::Wasd => Some(["w", "s", "a", "d"]),
+            Controls::Vim => Some(["k", "j", "h", "l"]),
+        }
+    }
+}
+
+/// What the player picked.
+#[derive(Clone, Debug)]
+pub struct Choices {
+    pub theme: crate::theme::Name,
+    pub glyphs: crate::glyphs::Mode,
+    pub controls: Controls,
+    pub name: Option<String>,
+}
+
+/// Neither the config file nor the data directory exist.
+pub fn is_first_run() -> bool {
+    let exists = |path: Option<std::path::PathBuf>| path.is_some_and(|path| path.exists());
+    !exists(crate::config::config_path()) && !exists(crate::config::data_dir())
+}
+
+/// Pick one of the items, None if the player skips the setup with Esc.
+fn pick<T: Clone>(
+    out: &mut std::io::Stdout,
+    rx: &Receiver<crossterm::event::Event>,
+    step: usize,
+    title: &str,
+    items: Vec<(String, T)>,
+) -> crossterm::Result<Option<T>> {
+    let header = [format(Text::SetupStep, &[&step]), String::new()];
+    let items = items
+        .into_iter()
+        .map(|(name, value)| Item::Action(name, value))
+        .collect();
+    let picked = Menu::new(title, items).run(out, rx, &header, &mut |_| None)?;
+    Ok(picked.map(|(_, value)| value))
+}
+
+/// Ask the player step by step. None if the setup was skipped.
+pub fn ask(
+    out: &mut std::io::Stdout,
+    rx: &Receiver<crossterm::event::Event>,
+) -> crossterm::Result<Option<Choices>> {
+    use crate::glyphs::{Mode, ASCII, UNICODE};
+
+    let themes = crate::theme::Name::ALL
+        .iter()
+        .map(|&name| (name.title().to_owned(), name))
+        .collect();
+    let Some(theme) = pick(out, rx, 1, tr(Text::SetupColors), themes)? else {
+        return Ok(None);
+    };
+
+    // Symbols of the set in the names, so the player sees which ones show
+    let sample = |glyphs: &crate::glyphs::Glyphs| {
+        let [vertical, horizontal, top_left, top_right, ..] = glyphs.border;
+        format!(
+            "{}{}{}{} {} {}",
+            top_left, horizontal, top_right, vertical, glyphs.block, glyphs.snake_head
+        )
+    };
+    let glyph_modes = vec![
+        (format!("Unicode  {}", sample(&UNICODE)), Mode::Unicode),
+        (format!("ASCII    {}", sample(&ASCII)), Mode::Ascii),
+        (
+            format!("Auto     {}", tr(Text::SetupAutoSymbols)),
+            Mode::Auto,
+        ),
+    ];
+    let Some(glyphs) = pick(out, rx, 2, tr(Text::SetupSymbols), glyph_modes)? else {
+        return Ok(None);
+    };
+
+    let controls = vec![
+        (tr(Text::SetupArrows).to_owned(), Controls::Arrows),
+        ("WASD".to_owned(), Controls::Wasd),
+        ("Vim (hjkl)".to_owned(), Controls::Vim),
+    ];
+    let Some(controls) = pick(out, rx, 3, tr(Text::SetupMovementKeys), controls)? else {
+        return Ok(None);
+    };
+
+    let initial_name = std::env::var("USER").unwrap_or_default();
+    let Some(name) = crate::dialog::ask_text(out, rx, tr(Text::SetupName), &initial_name)? else {
+        return Ok(None);
+    };
+    let name = name.trim();
+
+    Ok(Some(Choices {
+        theme,
+        glyphs,
+        controls,
+        name: (!name.is_empty()).then(|| name.to_owned()),
+    }))
+}
+
+impl Choices {
+    /// Write the config file and the settings.
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut config = toml::Table::new();
+        config.insert("theme".to_owned(), to_value(self.theme)?);
+        config.insert("glyphs".to_owned(), to_value(self.glyphs)?);
+        if let Some(keys) = self.controls.keys() {
+            let names = ["up", "down", "left", "right"];
+            let keys = names
+                .iter()
+                .zip(keys)
+                .map(|(name, key)| (name.to_string(), toml::Value::from(key)))
+                .collect();
+            config.insert("keys".to_owned(), toml::Value::Table(keys));
+        }
+        crate::config::save(&config)?;
+
+        let name = self.name.clone();
+        crate::settings::update(|settings| settings.name = name)
+    }
+}
+
+fn to_value(value: impl serde::Serialize) -> std::io::Result<toml::Value> {
+    use std::io::{Error, ErrorKind};
+
+    toml::Value::try_from(value).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
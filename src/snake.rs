@@ -1,24 +1,57 @@
-use crate::game::{self, EXIT_BUTTON};
-use crate::game::{Game, UpdateEvent};
-use crate::point::{BoundsCollision, GameBasis, Line, Point, ScreenBasis};
-use crossterm::{cursor, execute, style::Stylize, terminal};
+use crate::config;
+use crate::difficulty;
+use crate::effects::ScoreEvent;
+use crate::game::{self, Game, UpdateEvent};
+use crate::hud::Hud;
+use crate::input::{Action, FrameInput};
+use crate::point::{BoundsCollision, Circle, Direction, GameBasis, Line, PlayArea, Point, Rect};
+use crate::profiling::profile_scope;
+use crate::render::{FrameBuffer, Layer};
+use crate::script;
+use crate::sound::Effect;
+use crate::status::{Status, StatusEffects};
+use crate::theme;
+use crossterm::style::Stylize;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 mod apples {
-    use crate::util::MORE_THAN_HALF_CELL;
     pub const MAX: usize = 5;
     pub const SPAWN_RATE: std::time::Duration = std::time::Duration::from_secs(2);
-    pub const RADIUS: f32 = MORE_THAN_HALF_CELL;
+    /// An apple takes a cell, the head eats it on entering
+    pub const RADIUS: f32 = 0.5;
     pub const GROWTH: f32 = 1.0;
+    /// Chance of a new apple being a shield one
+    pub const SHIELD_CHANCE: f64 = 0.1;
 }
-mod snakes {
+pub mod snakes {
     pub(crate) const SPEED: f32 = 12.0;
 
     pub(crate) const WIDTH: f32 = 0.25;
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Apple(Point<GameBasis>);
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppleKind {
+    Plain,
+    /// Gives a `Status::Shield` stack
+    Shield,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Apple(Point<GameBasis>, AppleKind);
 
+impl Apple {
+    fn circle(&self) -> Circle<GameBasis> {
+        Circle::new(self.0, apples::RADIUS)
+    }
+}
+
+/// How long the snake passes through itself after a shield is used.
+pub const PHASING_TIME: std::time::Duration = std::time::Duration::from_millis(500);
+/// Directions closer than this on both axes are the same one.
+const SAME_DIRECTION_EPSILON: f32 = 0.001;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Score(usize);
 
 impl std::ops::AddAssign<i32> for Score {
@@ -27,6 +60,7 @@ impl std::ops::AddAssign<i32> for Score {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Snake {
     pub segments: Vec<Line<GameBasis>>,
 }
@@ -56,132 +90,152 @@ impl Snake {
     pub fn mut_first(&mut self) -> &mut Line<GameBasis> {
         &mut self.segments[0]
     }
-}
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct Input {
-    pub up: bool,
-    pub down: bool,
-    pub left: bool,
-    pub right: bool,
-}
-
-impl Default for Input {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Input {
-    pub fn new() -> Self {
-        Self {
-            up: false,
-            down: false,
-            left: false,
-            right: false,
+    /// Add a segment at the head. A segment going on from the head in its direction
+    /// lengthens the head and an empty head is replaced, so there is a segment
+    /// per turn or wrap on the screen and no more.
+    pub fn push(&mut self, segment: Line<GameBasis>) {
+        let head = *self.head();
+        if head.length() <= f32::EPSILON {
+            *self.mut_head() = segment;
+        } else if head.end.compare(&segment.begin, f32::EPSILON)
+            && head
+                .direction()
+                .compare(&segment.direction(), SAME_DIRECTION_EPSILON)
+        {
+            self.mut_head().end = segment.end;
+        } else {
+            self.segments.push(segment);
         }
     }
 
-    pub fn empty(&self) -> bool {
-        !self.up && !self.down && !self.left && !self.right
-    }
-
-    pub fn as_vec(&self, length: f32) -> Point<GameBasis> {
-        let mut vec = Point::new(0.0, 0.0);
-
-        if self.up {
-            vec.y -= length;
-        }
-        if self.down {
-            vec.y += length;
-        }
-        if self.left {
-            vec.x -= length;
-        }
-        if self.right {
-            vec.x += length;
+    /// Make the snake shorter from the tail, the head is always kept.
+    /// Passed segments are removed at once rather than one by one.
+    pub fn shrink(&mut self, mut length: f32) {
+        let mut removed = 0;
+        while length > f32::EPSILON && removed < self.segments.len() {
+            let is_head = removed + 1 == self.segments.len();
+            let segment = &mut self.segments[removed];
+            if segment.length() > length || is_head {
+                let direction = segment.direction();
+                segment.begin += direction * length.min(segment.length());
+                length = 0.0;
+            } else {
+                length -= segment.length();
+                removed += 1;
+            }
         }
-
-        vec
+        self.segments.drain(..removed);
     }
 }
 
-/// Read the input from the given input stream.
-fn read_to_input(event: &Option<crossterm::event::KeyEvent>) -> Input {
-    use crossterm::event::KeyCode;
-
-    let mut input = Input::new();
-
-    // Handle pressed keys
-    if let Some(key_event) = event {
-        match key_event.code {
-            KeyCode::Up => input.up = true,
-            KeyCode::Down => input.down = true,
-            KeyCode::Left => input.left = true,
-            KeyCode::Right => input.right = true,
-            _ => {}
-        }
+/// Direction from the player action.
+fn read_direction(input: &FrameInput) -> Option<Direction> {
+    match input.action() {
+        Some(Action::MoveUp) => Some(Direction::Up),
+        Some(Action::MoveDown) => Some(Direction::Down),
+        Some(Action::MoveLeft) => Some(Direction::Left),
+        Some(Action::MoveRight) => Some(Direction::Right),
+        _ => None,
     }
-
-    input
 }
 
 impl SnakeGame {
     /// Create a new game instance with the given settings.
     /// Snake starts at the given point and moves right.
     /// Tail is 2 points long.
-    pub fn new(setup: Point<GameBasis>) -> Self {
+    /// Apples are placed in the area with the given random generator.
+    pub fn new(setup: Point<GameBasis>, area: PlayArea, rng: StdRng) -> Self {
         Self {
             snake: Snake::new(setup),
             apples: Vec::new(),
             duration: std::time::Duration::from_millis(2),
-            prev_non_empty_input: Input {
-                up: false,
-                down: false,
-                left: false,
-                right: true, // Start moving right
-            },
+            played: std::time::Duration::ZERO,
+            direction: Direction::Right,
             score: Score(0),
             to_growth: 0.0,
+            heat_points: vec![],
+            score_events: vec![],
+            sound_effects: vec![],
+            status: StatusEffects::default(),
+            apples_spawned: 0,
+            prev_head_end: None,
+            area,
+            tweaks: Tweaks::load(),
+            rng,
         }
     }
 }
 
+/// Values set by scripts, looked up once per game.
+#[derive(Clone, Copy)]
+struct Tweaks {
+    speed: Option<f32>,
+}
+
+impl Tweaks {
+    fn load() -> Self {
+        Self {
+            speed: script::tweak("snake.speed"),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SnakeGame {
     pub snake: Snake,
     pub apples: Vec<Apple>,
-    pub prev_non_empty_input: Input,
+    /// Where the head moves
+    pub direction: Direction,
     pub duration: std::time::Duration,
+    /// Time played, shown on the HUD
+    #[serde(default)]
+    pub played: std::time::Duration,
     pub score: Score,
     pub to_growth: f32,
+    pub heat_points: Vec<Point<GameBasis>>,
+    #[serde(skip)]
+    pub score_events: Vec<ScoreEvent>,
+    #[serde(skip)]
+    pub sound_effects: Vec<Effect>,
+    #[serde(default)]
+    pub status: StatusEffects,
+    #[serde(default)]
+    pub apples_spawned: usize,
+    /// End of the head before the last update
+    #[serde(skip)]
+    prev_head_end: Option<Point<GameBasis>>,
+    /// The snake wraps around its edges
+    #[serde(default = "PlayArea::current")]
+    area: PlayArea,
+    #[serde(skip, default = "Tweaks::load")]
+    tweaks: Tweaks,
+    /// Not saved, loaded games get a new one
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng,
 }
 
 impl Game for SnakeGame {
-    /// Move the snake in the direction of the last non-empty input.
+    /// Move the snake in the direction of the last direction input.
     /// If the snake hits the edge of the screen, it wraps around to the other side.
     ///
     /// Returns true if the snake ate an apple.
-    fn update(
-        &mut self,
-        input: &Option<crossterm::event::KeyEvent>,
-        delta_time: &std::time::Duration,
-    ) -> UpdateEvent {
-        /// Get the terminal size in rectangular characters
-        fn get_terminal_size() -> Point<GameBasis> {
-            let size = terminal::size().expect("Failed to get terminal size");
-            Point::new(size.0 as f32 / 2.0, size.1 as f32)
-        }
+    fn update(&mut self, input: &FrameInput, delta_time: &std::time::Duration) -> UpdateEvent {
+        profile_scope!("snake.update");
         self.duration += *delta_time;
+        self.played += *delta_time;
+        self.status.tick(*delta_time);
 
         // quit
-        if let Some(key) = input {
-            if key.code == EXIT_BUTTON {
-                return UpdateEvent::GameOver;
-            }
+        if input.action() == Some(Action::Quit) {
+            return UpdateEvent::GameOver;
         }
 
         // Check for collisions
-        let is_collided = if self.snake.segments.len() > 2 {
+        let is_collided = if self.status.has(Status::Phasing) {
+            false
+        } else if self.snake.segments.len() > 2 {
+            profile_scope!("snake.collisions");
             let mut is_collided = false;
             for segment_ind in 0..self.snake.segments.len() - 2
             /* last two segments is head and pre-head */
@@ -193,6 +247,12 @@ impl Game for SnakeGame {
                     is_collided = true;
                 }
             }
+            // Shield is used up and the snake gets time to pass through
+            if is_collided && self.status.consume(Status::Shield) {
+                tracing::debug!("snake bite forgiven by a shield");
+                self.status.add(Status::Phasing, Some(PHASING_TIME));
+                is_collided = false;
+            }
             is_collided
         } else {
             false
@@ -203,14 +263,14 @@ impl Game for SnakeGame {
         {
             let mut i = 0;
             while i < self.apples.len() {
-                if self
-                    .snake
-                    .head()
-                    .end
-                    .compare(&self.apples[i].0, apples::RADIUS)
-                {
+                if self.apples[i].circle().contains(&self.snake.head().end) {
                     self.to_growth += apples::GROWTH;
                     self.score += 1;
+                    if self.apples[i].1 == AppleKind::Shield {
+                        self.status.add(Status::Shield, None);
+                    }
+                    self.score_events.push(ScoreEvent::at(1, self.apples[i].0));
+                    self.sound_effects.push(Effect::AppleEaten);
                     self.apples.remove(i);
                 } else {
                     i += 1;
@@ -222,42 +282,36 @@ impl Game for SnakeGame {
         // Zeroes duration if food is spawned
         if self.duration > apples::SPAWN_RATE {
             if self.apples.len() < apples::MAX {
-                /// Check if the given coordinates are on the snake
+                /// Check if an apple at the given coordinates would touch the snake
                 fn is_on_snake(snake: &Snake, coords: Point<GameBasis>) -> bool {
-                    for segment in snake.segments.iter() {
-                        if coords.compare(&segment.end, apples::RADIUS) {
-                            return true;
-                        }
-                    }
-                    false
+                    let apple = Circle::new(coords, apples::RADIUS);
+                    snake
+                        .segments
+                        .iter()
+                        .any(|segment| apple.intersects_line(segment))
                 }
 
                 /// Check if the given coordinates are on an apple
                 fn is_on_apple(coords: Point<GameBasis>, apples: &[Apple]) -> bool {
-                    for apple in apples.iter() {
-                        if coords.compare(&apple.0, apples::RADIUS) {
-                            return true;
-                        }
-                    }
-                    false
+                    apples.iter().any(|apple| apple.circle().contains(&coords))
                 }
 
-                /// Get a random position on the screen (scoreboard excluded)
-                fn random_position_on_screen() -> Point<GameBasis> {
-                    let screen_size = get_terminal_size();
-                    Point::new(
-                        (rand::random::<u32>() % (screen_size.x as u32)) as f32,
-                        ((rand::random::<u32>() + 1) % (screen_size.y as u32)) as f32,
-                    )
-                }
-
-                let mut apple_coords = random_position_on_screen();
-                while is_on_snake(&self.snake, apple_coords)
-                    || is_on_apple(apple_coords, &self.apples)
-                {
-                    apple_coords = random_position_on_screen();
+                // Anywhere in the area, no apple when the board is full
+                let size = self.area.size();
+                let spawn_area = Rect::new(Point::new(0.0, 0.0), size - Point::new(1.0, 1.0));
+                let (snake, apples) = (&self.snake, &self.apples);
+                let apple_coords = spawn_area.random_free_point(&mut self.rng, |coords| {
+                    !is_on_snake(snake, coords) && !is_on_apple(coords, apples)
+                });
+                if let Some(apple_coords) = apple_coords {
+                    let kind = if self.rng.gen_bool(apples::SHIELD_CHANCE) {
+                        AppleKind::Shield
+                    } else {
+                        AppleKind::Plain
+                    };
+                    self.apples.push(Apple(apple_coords, kind));
+                    self.apples_spawned += 1;
                 }
-                self.apples.push(Apple(apple_coords));
             }
 
             self.duration = std::time::Duration::from_secs(0);
@@ -265,47 +319,46 @@ impl Game for SnakeGame {
 
         // Move snake
         // Depends on is_apple_eaten
-        // Modifies self.snake and self.prev_non_empty_input
+        // Modifies self.snake and self.direction
         {
-            let screen_size = get_terminal_size();
-            let real_screen_size: Point<ScreenBasis> = screen_size.into();
-            let input = read_to_input(input);
-            let distance_traveled = snakes::SPEED * delta_time.as_secs_f32();
-
-            let input = if !input.empty()
-                && (input.up && !self.prev_non_empty_input.down
-                    || input.down && !self.prev_non_empty_input.up
-                    || input.left && !self.prev_non_empty_input.right
-                    || input.right && !self.prev_non_empty_input.left)
-            {
-                input
-            } else {
-                self.prev_non_empty_input
+            profile_scope!("snake.movement");
+            self.prev_head_end = Some(self.snake.head().end);
+            let screen_size = self.area.size();
+            let (screen_width, screen_height) = self.area.screen_size();
+            let mut input = read_direction(input);
+            if self.status.has(Status::Inverted) {
+                input = input.map(Direction::opposite);
+            }
+            let distance_traveled = self
+                .tweaks
+                .speed
+                .unwrap_or_else(|| config::get().snake.speed)
+                * difficulty::level()
+                * delta_time.as_secs_f32();
+
+            // The snake can't turn back into itself
+            let direction = match input {
+                Some(direction) if direction != self.direction.opposite() => direction,
+                _ => self.direction,
             };
+            let step = direction.as_vec() * distance_traveled;
 
             // Growth head
             // FIXME bound check
-            if input != self.prev_non_empty_input {
-                let new_head_end = input.as_vec(distance_traveled) + self.snake.head().end;
+            if direction != self.direction {
+                let new_head_end = step + self.snake.head().end;
                 self.snake
-                    .segments
                     .push(Line::new(self.snake.head().end, new_head_end));
             } else {
-                let new_head_end = input.as_vec(distance_traveled) + self.snake.head().end;
-                if matches!(
-                    new_head_end.bounds_check(
-                        real_screen_size.x.round() as u16,
-                        real_screen_size.y.round() as u16,
-                    ),
-                    None
-                ) {
-                    self.snake.mut_head().end += input.as_vec(distance_traveled);
+                let new_head_end = step + self.snake.head().end;
+                if new_head_end
+                    .bounds_check(screen_width, screen_height)
+                    .is_none()
+                {
+                    self.snake.mut_head().end += step;
                 } else {
-                    self.snake.segments.push({
-                        match new_head_end.bounds_check(
-                            real_screen_size.x.round() as u16,
-                            real_screen_size.y.round() as u16,
-                        ) {
+                    self.snake.push({
+                        match new_head_end.bounds_check(screen_width, screen_height) {
                             None => unreachable!("Should have been handled above"),
                             Some(BoundsCollision::Bottom) => {
                                 let begin = Point::new(self.snake.head().end.x, 0.0);
@@ -333,23 +386,16 @@ impl Game for SnakeGame {
             }
 
             // Shrink tail
-            let mut to_shrink = 0.0_f32.max(distance_traveled - self.to_growth);
+            self.snake
+                .shrink(0.0_f32.max(distance_traveled - self.to_growth));
             self.to_growth = 0.0_f32.max(self.to_growth - distance_traveled);
-            while to_shrink > f32::EPSILON {
-                if self.snake.first().length() > to_shrink {
-                    let first_dir = self.snake.first().direction() * -1.0;
-                    self.snake.mut_first().begin -= first_dir * to_shrink;
-                    to_shrink = 0.0;
-                } else {
-                    to_shrink -= self.snake.first().length();
-                    self.snake.segments.remove(0);
-                }
-            }
 
-            self.prev_non_empty_input = input;
+            self.direction = direction;
         };
 
         if is_collided {
+            tracing::debug!(length = self.snake.segments.len(), "snake bit itself");
+            self.heat_points.push(self.snake.head().end);
             UpdateEvent::GameOver
         } else {
             UpdateEvent::GameContinue
@@ -357,26 +403,36 @@ impl Game for SnakeGame {
     }
 
     /// Draw the snake to the screen.
-    fn draw(
+    fn draw(&self, frame: &mut FrameBuffer, delta_time: &std::time::Duration) {
+        self.draw_interpolated(frame, delta_time, 1.0);
+    }
+
+    fn draw_interpolated(
         &self,
-        out: &mut std::io::Stdout,
+        frame: &mut FrameBuffer,
         _delta_time: &std::time::Duration,
-    ) -> crossterm::Result<()> {
-        use cursor::MoveTo;
-        use std::io::Write;
-        use terminal::size;
-
-        let (max_x, _max_y) = size().expect("Failed to get terminal size");
+        alpha: f32,
+    ) {
+        profile_scope!("snake.draw");
+        let palette = theme::palette();
+        let head = crate::glyphs::get().snake_head;
 
         // Draw snake
         {
             // Draw snake body
             {
+                let screen = Rect::screen(frame.width(), frame.height());
                 for segment in self.snake.segments.iter() {
                     use once_cell::sync::Lazy;
                     static EPS: Lazy<f32> = Lazy::new(|| 2.0_f32.hypot(1.0_f32));
-                    let segment_begin: Point<ScreenBasis> = segment.begin.into();
-                    let segment_end: Point<ScreenBasis> = segment.end.into();
+                    // Parts off the screen, e.g. after the terminal shrank, are not drawn
+                    let Some(on_screen) =
+                        screen.clip_line(&Line::new(segment.begin.into(), segment.end.into()))
+                    else {
+                        continue;
+                    };
+                    let segment_begin = on_screen.begin;
+                    let segment_end = on_screen.end;
                     let segment_direction = segment_end - segment_begin;
 
                     // Calculate the unit vector of segment_direction
@@ -398,14 +454,14 @@ impl Game for SnakeGame {
                         1.0
                     };
                     'draw_segment: loop {
-                        execute!(
-                            out,
-                            MoveTo(
-                                segment_point.x.round() as u16,
-                                segment_point.y.round() as u16
-                            )
-                        )?;
-                        write!(out, "{}", "()".green())?;
+                        if let Some(cell) = segment_point.to_cell() {
+                            frame.print_styled(
+                                Layer::Game,
+                                cell.x,
+                                cell.y,
+                                "()".with(palette.player),
+                            );
+                        }
 
                         segment_point += Point::new(
                             segment_direction_unit.x * scale_factor,
@@ -421,74 +477,63 @@ impl Game for SnakeGame {
 
                     // Draw the endpoint of the segment if it was not already drawn
                     if segment_point.distance_to(&segment_end) >= *EPS {
-                        execute!(
-                            out,
-                            MoveTo(segment_end.x.round() as u16, segment_end.y.round() as u16)
-                        )?;
-                        write!(out, "{}", "()".green())?;
+                        if let Some(cell) = segment_end.to_cell() {
+                            frame.print_styled(
+                                Layer::Game,
+                                cell.x,
+                                cell.y,
+                                "()".with(palette.player),
+                            );
+                        }
                     }
                 }
             }
 
             // Draw snake's head
             {
-                let snake_head_on_screen: Point<ScreenBasis> = self.snake.head().end.into();
-
-                execute!(
-                    out,
-                    MoveTo(
-                        snake_head_on_screen.x.round() as u16,
-                        snake_head_on_screen.y.round() as u16
-                    )
-                )?;
-                write!(out, "{}", "❮❯".green())?;
+                // Not across the screen after a wrap
+                let head_end = self.snake.head().end;
+                let head_end = match self.prev_head_end {
+                    Some(prev) if prev.distance_to(&head_end) < 1.0 => prev.lerp(&head_end, alpha),
+                    _ => head_end,
+                };
+                if let Some(cell) = head_end.to_cell() {
+                    frame.print_styled(
+                        Layer::Game,
+                        cell.x,
+                        cell.y,
+                        if self.status.has(Status::Phasing) {
+                            head.with(palette.phasing)
+                        } else if self.status.has(Status::Shield) {
+                            head.with(palette.shield).bold()
+                        } else {
+                            head.with(palette.player)
+                        },
+                    );
+                }
             }
         }
 
         // Draw apples
         {
-            for apple in self.apples.iter().map(|p| Point::<ScreenBasis>::from(p.0)) {
-                execute!(out, MoveTo(apple.x.round() as u16, apple.y.round() as u16))?;
-                write!(out, "{}", "<>".red())?;
+            for Apple(position, kind) in self.apples.iter() {
+                let Some(cell) = position.to_cell() else {
+                    continue;
+                };
+                frame.print_styled(
+                    Layer::Game,
+                    cell.x,
+                    cell.y,
+                    match kind {
+                        AppleKind::Plain => "<>".with(palette.pickup),
+                        AppleKind::Shield => "<>".with(palette.shield),
+                    },
+                );
             }
         }
 
-        // Draw score
-        {
-            fn digits_num(num: usize) -> u16 {
-                if num == 0 {
-                    1
-                } else {
-                    f32::floor(f32::log10(num as f32) + 1.0) as u16
-                }
-            }
-
-            let score_hint = "Score: ";
-            execute!(
-                out,
-                MoveTo(
-                    (max_x - score_hint.len() as u16 - digits_num(self.score.0)) / 2,
-                    0
-                )
-            )?;
-            let score = format!("{}", self.score.0);
-            write!(
-                out,
-                "Score: {}",
-                if self.score.0 < 10 {
-                    score.white()
-                } else if self.score.0 < 40 {
-                    score.green()
-                } else if self.score.0 < 100 {
-                    score.yellow()
-                } else {
-                    score.red()
-                }
-            )?;
-        }
-
-        // Reset cursor
-        execute!(out, MoveTo(0, 0))
+        let (_, height) = self.area.screen_size();
+        self.status.draw(frame, 0, height.saturating_sub(1));
     }
 
     fn get_score(&self) -> game::Score {
@@ -496,4 +541,97 @@ impl Game for SnakeGame {
             value: self.score.0 as i64,
         }
     }
+
+    fn hud(&self, hud: &mut Hud) {
+        let palette = theme::palette();
+        let tier = if self.score.0 < 10 {
+            palette.score_tiers[0]
+        } else if self.score.0 < 40 {
+            palette.score_tiers[1]
+        } else if self.score.0 < 100 {
+            palette.score_tiers[2]
+        } else {
+            palette.score_tiers[3]
+        };
+        hud.colored_score(self.score.0 as i64, tier)
+            .time(self.played);
+    }
+
+    fn resize(&mut self, area: PlayArea) {
+        self.area = area;
+    }
+
+    fn take_heat_points(&mut self) -> Vec<Point<GameBasis>> {
+        std::mem::take(&mut self.heat_points)
+    }
+
+    fn take_score_events(&mut self) -> Vec<ScoreEvent> {
+        std::mem::take(&mut self.score_events)
+    }
+
+    fn take_sound_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.sound_effects)
+    }
+
+    fn chaos_event(&mut self) -> Option<String> {
+        self.status
+            .add(Status::Inverted, Some(crate::chaos::EFFECT_TIME));
+        Some(crate::i18n::tr(crate::i18n::Text::ControlsInverted).to_owned())
+    }
+
+    fn save(&self) -> Option<crate::save::SavedGame> {
+        Some(crate::save::SavedGame::Snake(self.clone()))
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Game>> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn entities(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("segments", self.snake.segments.len()),
+            ("apples", self.apples.len()),
+        ]
+    }
+
+    fn help(&self) -> Vec<String> {
+        use crate::help::key;
+        use crate::i18n::{format, tr, Text};
+
+        let keys = format!(
+            "{}/{}/{}/{}",
+            key(Action::MoveUp),
+            key(Action::MoveDown),
+            key(Action::MoveLeft),
+            key(Action::MoveRight)
+        );
+        vec![
+            format(Text::SnakeTurn, &[&keys]),
+            tr(Text::SnakeApple).to_owned(),
+            tr(Text::SnakeShieldApple).to_owned(),
+            tr(Text::SnakeBite).to_owned(),
+        ]
+    }
+
+    fn summary(&self) -> Vec<String> {
+        use crate::i18n::{format, Text};
+
+        vec![
+            format(Text::ApplesEaten, &[&self.score.0]),
+            format(Text::ApplesSpawned, &[&self.apples_spawned]),
+        ]
+    }
+
+    /// Share of the spawned apples that were eaten
+    fn performance(&self) -> Option<crate::stats::Performance> {
+        if self.apples_spawned == 0 {
+            return None;
+        }
+
+        Some(crate::stats::Performance {
+            name: crate::i18n::Text::SnakePerformance,
+            value: (self.score.0 as f32 / self.apples_spawned as f32).min(1.0),
+            tip: crate::i18n::Text::SnakeTip,
+        })
+    }
 }
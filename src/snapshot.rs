@@ -0,0 +1,76 @@
+//! Frames of games as plain text without styles or escape codes,
+//! to compare boards and layouts with golden files in tests:
+//!
+//! ```
+//! use cl_games::prelude::*;
+//!
+//! let registry = Registry::builtin();
+//! let lines = snapshot::of_entry(&registry.games()[0], 7, 40, 20).lines();
+//! assert_eq!(lines.len(), 20);
+//! assert!(lines.iter().all(|line| line.chars().count() == 40));
+//! ```
+//!
+//! Golden files are written instead of compared with `UPDATE_GOLDEN=1` set,
+//! see `assert_golden`.
+use crate::game::Game;
+use crate::registry::GameEntry;
+use crate::render::FrameBuffer;
+use rand::{rngs::StdRng, SeedableRng};
+use std::path::Path;
+
+/// Environment variable making `assert_golden` write the files.
+pub const UPDATE_VAR: &str = "UPDATE_GOLDEN";
+
+/// Current frame of the game drawn on a screen of the given size.
+/// Games place things by their play area, so make the game for the same size,
+/// as `of_entry` does.
+pub fn frame(game: &dyn Game, width: u16, height: u16) -> FrameBuffer {
+    let mut frame = FrameBuffer::new(width, height);
+    game.draw(&mut frame, &std::time::Duration::ZERO);
+    let mut hud = crate::hud::Hud::new();
+    game.hud(&mut hud);
+    hud.draw(&mut frame);
+    frame
+}
+
+/// First frame of the game made from the seed on a screen of the given size.
+pub fn of_entry(entry: &GameEntry, seed: u64, width: u16, height: u16) -> FrameBuffer {
+    let game = (entry.create)(
+        StdRng::seed_from_u64(seed),
+        crate::hud::play_area(width, height),
+    );
+    frame(game.as_ref(), width, height)
+}
+
+/// Check the lines against the golden file, spaces at the line ends left out.
+/// With `UPDATE_VAR` set the file is written instead.
+#[track_caller]
+pub fn assert_golden(lines: &[String], path: &Path) {
+    let text: String = lines
+        .iter()
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect();
+    if std::env::var_os(UPDATE_VAR).is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).expect("golden directory created");
+        }
+        std::fs::write(path, &text).expect("golden file written");
+        return;
+    }
+    let expected = std::fs::read_to_string(path).unwrap_or_else(|err| {
+        panic!(
+            "{}: {}, run with {}=1 to write it",
+            path.display(),
+            err,
+            UPDATE_VAR
+        )
+    });
+    assert!(
+        text == expected,
+        "frame differs from {}, run with {}=1 to update it\nexpected:\n{}\nactual:\n{}",
+        path.display(),
+        UPDATE_VAR,
+        expected,
+        text
+    );
+}
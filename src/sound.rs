@@ -0,0 +1,121 @@
+//! Sound feedback for game events.
+//!
+//! The terminal bell is used by default, it sounds the same for every event.
+//! With the `rodio` feature and `sound = "audio"` in the config every event
+//! gets its own tone from the audio device.
+use serde::{Deserialize, Serialize};
+
+/// Game event worth a sound.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    LineCleared,
+    AppleEaten,
+    PointScored,
+    PointLost,
+    EnemyHit,
+    GameOver,
+}
+
+/// Where the sounds go, set with `sound` in the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Off,
+    #[default]
+    Bell,
+    /// Tones through the audio device, the bell if there is none
+    #[cfg(feature = "rodio")]
+    Audio,
+}
+
+pub trait Sound {
+    /// Short sound not tied to an event.
+    fn beep(&mut self);
+
+    /// Sound of the event, a beep unless the backend can tell events apart.
+    fn play_effect(&mut self, _effect: Effect) {
+        self.beep();
+    }
+}
+
+pub struct Silent;
+
+impl Sound for Silent {
+    fn beep(&mut self) {}
+}
+
+/// BEL character: most terminals beep or flash the window.
+pub struct Bell;
+
+impl Sound for Bell {
+    fn beep(&mut self) {
+        use std::io::Write;
+
+        let mut out = std::io::stdout();
+        _ = out.write_all(b"\x07");
+        _ = out.flush();
+    }
+}
+
+#[cfg(feature = "rodio")]
+pub struct Audio {
+    /// Sounds stop when the stream is dropped
+    _stream: rodio::OutputStream,
+    handle: rodio::OutputStreamHandle,
+}
+
+#[cfg(feature = "rodio")]
+impl Audio {
+    const VOLUME: f32 = 0.2;
+
+    /// None without an audio device.
+    pub fn new() -> Option<Self> {
+        let (stream, handle) = rodio::OutputStream::try_default().ok()?;
+        Some(Self {
+            _stream: stream,
+            handle,
+        })
+    }
+
+    fn tone(&self, frequency: f32, millis: u64) {
+        use rodio::Source;
+
+        let source = rodio::source::SineWave::new(frequency)
+            .take_duration(std::time::Duration::from_millis(millis))
+            .amplify(Self::VOLUME);
+        _ = self.handle.play_raw(source);
+    }
+}
+
+#[cfg(feature = "rodio")]
+impl Sound for Audio {
+    fn beep(&mut self) {
+        self.tone(440.0, 80);
+    }
+
+    fn play_effect(&mut self, effect: Effect) {
+        // (Hz, ms)
+        let (frequency, millis) = match effect {
+            Effect::LineCleared => (880.0, 120),
+            Effect::AppleEaten => (660.0, 60),
+            Effect::PointScored => (990.0, 100),
+            Effect::PointLost => (220.0, 150),
+            Effect::EnemyHit => (520.0, 40),
+            Effect::GameOver => (110.0, 400),
+        };
+        self.tone(frequency, millis);
+    }
+}
+
+/// Sound of the backend in the config.
+pub fn from_config() -> Box<dyn Sound> {
+    match crate::config::get().sound {
+        Backend::Off => Box::new(Silent),
+        Backend::Bell => Box::new(Bell),
+        #[cfg(feature = "rodio")]
+        Backend::Audio => match Audio::new() {
+            Some(audio) => Box::new(audio),
+            None => Box::new(Bell),
+        },
+    }
+}
@@ -1,45 +1,132 @@
-use crate::game::{Game, Score, UpdateEvent, EXIT_BUTTON};
-use crate::point::{GameBasis, Point, ScreenBasis};
+use crate::collision::SpatialGrid;
+use crate::config;
+use crate::difficulty;
+use crate::effects::ScoreEvent;
+use crate::game::{Game, Score, UpdateEvent};
+use crate::input::{Action, FrameInput};
+use crate::kinematics::Body;
+use crate::point::{Direction, GameBasis, Line, Path, PlayArea, Point, Rect, ScreenBasis};
+use crate::profiling::profile_scope;
+use crate::render::{Cell, FrameBuffer, Layer};
+use crate::script::{self, InvaderMove};
+use crate::sound::Effect;
+use crate::status::{Status, StatusEffects};
+use crate::theme;
+use crate::ui::ProgressBar;
 use crate::util::MORE_THAN_HALF_CELL;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 const FOR_ENEMY_SCORE: usize = 1;
 const FOR_PROP_SCORE: usize = 0;
 const FIRE_BULLET_OFFSET: f32 = 1.0;
-const PLAYER_SPEED: f32 = 1.0;
-const PLAYER_FIRE_RATE: Duration = Duration::from_millis(500);
+pub const PLAYER_SPEED: f32 = 1.0;
+pub const PLAYER_FIRE_RATE: Duration = Duration::from_millis(500);
+/// Held direction keys move the player like key repeat: after a delay, then steadily.
+/// The delay is longer than `input::REPEAT_TIMEOUT`, so a tap is never taken for a hold.
+const HELD_MOVE_DELAY: Duration = Duration::from_millis(150);
+const HELD_MOVE_INTERVAL: Duration = Duration::from_millis(50);
 const GAME_UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+/// Cannon heat added by a shot, the cannon overheats at 1 (see `overheat` in the config).
+pub const HEAT_PER_SHOT: f32 = 0.25;
+/// Cannon heat lost per second.
+const COOLING_RATE: f32 = 0.3;
+/// Overheated cannon can't fire for this time.
+const OVERHEAT_TIME: Duration = Duration::from_secs(2);
+const HEAT_BAR_WIDTH: u16 = 10;
+/// Enemies or bullets a thread takes at least, fewer aren't worth another thread.
+#[cfg(feature = "rayon")]
+const PARALLEL_MIN_LEN: usize = 256;
+
+/// Grid cells of a game cell, so a search looks at a few buckets.
+fn collision_grid() -> SpatialGrid {
+    SpatialGrid::new(1.0)
+}
 
-pub fn is_success(chance: f32) -> bool {
-    let mut rng = rand::thread_rng();
+pub fn is_success(rng: &mut impl Rng, chance: f32) -> bool {
     let random: f32 = rng.gen();
     random < chance / 100.0
 }
 
-#[derive(Clone, Copy, Debug)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "SavedBullet")]
+pub struct Bullet {
+    move_direction: Direction,
+    /// Moves by its velocity each update
+    #[serde(flatten)]
+    body: Body<GameBasis>,
+    /// Position before the last move, None for a new bullet
+    #[serde(skip)]
+    previous: Option<Point<GameBasis>>,
 }
 
-#[derive(Clone, Debug)]
-pub struct Bullet {
+impl Bullet {
+    fn new(move_direction: Direction, position: Point<GameBasis>, speed: f32) -> Self {
+        Self {
+            move_direction,
+            body: Body::new(position, move_direction.as_vec() * speed),
+            previous: None,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.previous = Some(self.body.position);
+        self.body.step(1.0);
+    }
+
+    /// Way of the last move, things anywhere on it are hit.
+    fn path(&self) -> Line<GameBasis> {
+        Line::new(
+            self.previous.unwrap_or(self.body.position),
+            self.body.position,
+        )
+    }
+}
+
+/// Bullet as saved. Older saves have a speed along the direction instead of a velocity.
+#[derive(Deserialize)]
+struct SavedBullet {
     move_direction: Direction,
     position: Point<GameBasis>,
+    #[serde(default)]
+    velocity: Option<Point<GameBasis>>,
+    #[serde(default)]
+    acceleration: Option<Point<GameBasis>>,
+    #[serde(default)]
     speed: f32,
 }
 
-#[derive(Clone, Debug)]
+impl From<SavedBullet> for Bullet {
+    fn from(saved: SavedBullet) -> Self {
+        let velocity = saved
+            .velocity
+            .unwrap_or_else(|| saved.move_direction.as_vec() * saved.speed);
+        let mut body = Body::new(saved.position, velocity);
+        if let Some(acceleration) = saved.acceleration {
+            body = body.with_acceleration(acceleration);
+        }
+        Self {
+            move_direction: saved.move_direction,
+            body,
+            previous: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum EnemyActionType {
     Move(Direction, f32),
+    /// Walk the path from where the action starts, the distance each time.
+    /// The action is over at the end of the path.
+    Follow(Path<GameBasis>, f32),
     Fire(Direction, f32),
     Wait,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EnemyAction {
     action_type: EnemyActionType,
     duration: Duration,
@@ -85,6 +172,16 @@ impl EnemyAction {
         Self::new(EnemyActionType::Wait, duration, chance)
     }
 
+    /// Walk the path by a cell four times a second.
+    /// Shorter steps would end in the enemy's own place, which is taken.
+    pub fn follow(path: Path<GameBasis>, chance: f32) -> Self {
+        Self::new(
+            EnemyActionType::Follow(path, 1.0),
+            Duration::from_millis(250),
+            chance,
+        )
+    }
+
     pub fn fire_down(chance: f32) -> Self {
         Self::new(
             EnemyActionType::Fire(Direction::Down, 1.0),
@@ -94,11 +191,28 @@ impl EnemyAction {
     }
 }
 
-#[derive(Clone, Debug)]
+impl From<script::InvaderAction> for EnemyAction {
+    fn from(action: script::InvaderAction) -> Self {
+        let chance = action.chance;
+        match action.kind {
+            InvaderMove::Left => Self::left(chance),
+            InvaderMove::Right => Self::right(chance),
+            InvaderMove::Up => Self::up(chance),
+            InvaderMove::Down => Self::down(chance),
+            InvaderMove::Fire => Self::fire_down(chance),
+            InvaderMove::Wait(duration) => Self::wait(duration, chance),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EnemyBehavior {
     actions: Vec<EnemyAction>,
     to_next_move: Duration,
     current_action: usize,
+    /// Distance along the path of the current action
+    #[serde(default)]
+    walked: f32,
 }
 
 impl EnemyBehavior {
@@ -110,14 +224,16 @@ impl EnemyBehavior {
             actions,
             to_next_move,
             current_action,
+            walked: 0.0,
         }
     }
 
-    fn current_action(&self) -> EnemyAction {
-        self.actions[self.current_action].clone()
+    fn current_action(&self) -> &EnemyAction {
+        &self.actions[self.current_action]
     }
 
     fn next_action(&mut self) {
+        self.walked = 0.0;
         self.current_action += 1;
         if self.current_action >= self.actions.len() {
             self.current_action = 0;
@@ -134,22 +250,149 @@ impl EnemyBehavior {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Enemy {
     position: Point<GameBasis>,
     behavior: EnemyBehavior,
 }
 
-#[derive(Clone, Debug)]
+/// What taking the current action does, worked out before the enemy changes.
+#[derive(Clone, Copy)]
+enum Step {
+    /// Go to the place, having walked the distance along the path of the action.
+    /// The action is over after it if `is_last`
+    Move {
+        to: Point<GameBasis>,
+        walked: f32,
+        is_last: bool,
+    },
+    Fire(Direction, f32),
+    Wait,
+}
+
+impl Enemy {
+    fn step(&self) -> Step {
+        let behavior = &self.behavior;
+        match &behavior.current_action().action_type {
+            EnemyActionType::Move(direction, speed) => Step::Move {
+                to: direction.moved(self.position, *speed),
+                walked: 0.0,
+                is_last: true,
+            },
+            EnemyActionType::Follow(path, step) => {
+                let walked = behavior.walked + step;
+                // A rest shorter than a step would end in the enemy's own place,
+                // which is taken, so the last step walks it too
+                let length = path.length();
+                let walked = if length - walked < *step {
+                    length
+                } else {
+                    walked
+                };
+                Step::Move {
+                    to: self.position + path.at(walked) - path.at(behavior.walked),
+                    walked,
+                    is_last: walked >= path.length(),
+                }
+            }
+            EnemyActionType::Fire(direction, speed) => Step::Fire(*direction, *speed),
+            EnemyActionType::Wait => Step::Wait,
+        }
+    }
+
+    /// Place taken by the enemy, bullets in it hit the enemy.
+    fn cell(&self) -> Rect<GameBasis> {
+        Rect::around(
+            self.position,
+            Point::new(MORE_THAN_HALF_CELL, MORE_THAN_HALF_CELL),
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Prop {
     position: Point<GameBasis>,
     destroyable: bool,
 }
 
+impl Prop {
+    /// Cell taken by the prop, bullets and ships in it hit the prop.
+    fn cell(&self) -> Rect<GameBasis> {
+        Rect::around(self.position, Point::new(0.5, 0.5))
+    }
+}
+
+/// What enemies keep out of when moving, as it was at the start of a tick.
+struct Surroundings<'a> {
+    screen_size: (u16, u16),
+    enemy_positions: &'a [Point<GameBasis>],
+    enemy_grid: &'a SpatialGrid,
+    props: &'a [Prop],
+    prop_grid: &'a SpatialGrid,
+    player: Point<GameBasis>,
+}
+
+impl Surroundings<'_> {
+    /// An enemy can move to the place.
+    /// The enemy's own place counts as taken, so it doesn't move on the spot.
+    fn is_free(&self, place: Point<GameBasis>) -> bool {
+        let (screen_width, screen_height) = self.screen_size;
+        place.bounds_check(screen_width, screen_height).is_none()
+            && self
+                .enemy_grid
+                .near(place, MORE_THAN_HALF_CELL)
+                .all(|other| !self.enemy_positions[other].compare(&place, MORE_THAN_HALF_CELL))
+            && self
+                .prop_grid
+                .near(place, MORE_THAN_HALF_CELL)
+                .all(|prop| !self.props[prop].cell().contains(&place))
+            && !self.player.compare(&place, MORE_THAN_HALF_CELL)
+    }
+
+    /// The enemy is ready to act and can take its current action, only moves can fail.
+    fn can_act(&self, enemy: &Enemy) -> bool {
+        enemy.behavior.to_next_move.is_zero()
+            && match enemy.step() {
+                Step::Move { to, .. } => self.is_free(to),
+                Step::Fire(..) | Step::Wait => true,
+            }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Player {
     position: Point<GameBasis>,
+    /// Cannon heat from 0 to 1
+    #[serde(default)]
+    heat: f32,
+    #[serde(default)]
+    status: StatusEffects,
 }
 
+impl Player {
+    /// Cool the cannon down.
+    fn cool(&mut self, delta_time: Duration) {
+        if self.status.tick(delta_time).contains(&Status::Overheated) {
+            self.heat = 0.0;
+        } else if !self.is_overheated() {
+            self.heat = (self.heat - COOLING_RATE * delta_time.as_secs_f32()).max(0.0);
+        }
+    }
+
+    /// Heat the cannon up after a shot.
+    fn heat_up(&mut self, heat: f32) {
+        self.heat = (self.heat + heat).min(1.0);
+        if self.heat >= 1.0 {
+            self.status.add(Status::Overheated, Some(OVERHEAT_TIME));
+        }
+    }
+
+    fn is_overheated(&self) -> bool {
+        self.status.has(Status::Overheated)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SpaceInvadersGame {
     score: usize,
     bullets: Vec<Bullet>,
@@ -158,16 +401,79 @@ pub struct SpaceInvadersGame {
     player: Player,
     from_last_update: Duration,
     from_last_fire: Duration,
+    /// Until a held direction key moves the player again
+    #[serde(default)]
+    to_held_move: Duration,
+    heat_points: Vec<Point<GameBasis>>,
+    #[serde(default)]
+    shots_fired: usize,
+    /// Enemies shot down by any bullet
+    #[serde(default)]
+    enemies_hit: usize,
+    #[serde(skip)]
+    score_events: Vec<ScoreEvent>,
+    #[serde(skip)]
+    sound_effects: Vec<Effect>,
+    /// Enemy positions at the start of a tick, kept so ticks don't allocate
+    #[serde(skip)]
+    enemy_positions: Vec<Point<GameBasis>>,
+    /// Enemies and props by place, rebuilt every tick
+    #[serde(skip, default = "collision_grid")]
+    enemy_grid: SpatialGrid,
+    #[serde(skip, default = "collision_grid")]
+    prop_grid: SpatialGrid,
+    /// Enemies near a bullet, in order
+    #[serde(skip)]
+    nearby: Vec<usize>,
+    /// Collision states of a tick by index, kept so ticks don't allocate
+    #[serde(skip)]
+    bullets_collided: Vec<bool>,
+    #[serde(skip)]
+    enemies_collided: Vec<bool>,
+    #[serde(skip)]
+    props_collided: Vec<bool>,
+    /// Whether each enemy can take its current action this tick,
+    /// worked out before any of them acts, see `Surroundings`
+    #[serde(skip)]
+    can_act: Vec<bool>,
+    /// Nothing leaves it but the bullets
+    #[serde(default = "PlayArea::current")]
+    area: PlayArea,
+    #[serde(skip, default = "Tweaks::load")]
+    tweaks: Tweaks,
+    /// Not saved, loaded games get a new one
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng,
+}
+
+/// Values set by scripts, looked up once per game.
+#[derive(Clone, Copy)]
+struct Tweaks {
+    fire_rate: Option<f32>,
+}
+
+impl Tweaks {
+    fn load() -> Self {
+        Self {
+            fire_rate: script::tweak("space_invaders.fire_rate"),
+        }
+    }
 }
 
+/// Enemy formation at the start.
+#[non_exhaustive]
 pub enum EnemyPreset {
     Empty,
     CheckeredLeftRight,
     CheckeredRightDownLeftUp,
     CheckeredLeft,
     RandomFire,
+    /// Rows swooping down in arcs, firing between the swoops
+    Swoop,
 }
 
+/// Cover between the player and the enemies at the start.
+#[non_exhaustive]
 pub enum PropsPreset {
     Empty,
     Wall,
@@ -179,7 +485,10 @@ impl SpaceInvadersGame {
         screen_width: u16,
         enemy_preset: EnemyPreset,
         props_preset: PropsPreset,
+        mut rng: StdRng,
     ) -> Self {
+        // Width of the screen in the game world
+        let columns = Point::<GameBasis>::from_screen_size(screen_width, 0).x as u16;
         Self {
             score: 0,
             bullets: vec![],
@@ -188,7 +497,7 @@ impl SpaceInvadersGame {
                 EnemyPreset::CheckeredLeftRight => {
                     let mut enemies = vec![];
                     for y in 0..5 {
-                        for x in 0..screen_width / 2 / 2 {
+                        for x in 0..columns / 2 {
                             enemies.push(Enemy {
                                 position: Point::new(x as f32 * 2.0 + y as f32 % 2.0, y as f32),
                                 behavior: EnemyBehavior::new(
@@ -204,7 +513,7 @@ impl SpaceInvadersGame {
                 EnemyPreset::CheckeredRightDownLeftUp => {
                     let mut enemies = vec![];
                     for y in 0..5 {
-                        for x in 0..screen_width / 2 / 2 {
+                        for x in 0..columns / 2 {
                             enemies.push(Enemy {
                                 position: Point::new(x as f32 * 2.0 + y as f32 % 2.0, y as f32),
                                 behavior: EnemyBehavior::new(
@@ -225,7 +534,7 @@ impl SpaceInvadersGame {
                 EnemyPreset::CheckeredLeft => {
                     let mut enemies = vec![];
                     for y in 0..5 {
-                        for x in 0..screen_width / 2 / 2 {
+                        for x in 0..columns / 2 {
                             enemies.push(Enemy {
                                 position: Point::new(x as f32 * 2.0 + y as f32 % 2.0, y as f32),
                                 behavior: EnemyBehavior::new(
@@ -241,10 +550,10 @@ impl SpaceInvadersGame {
                 EnemyPreset::RandomFire => {
                     let mut enemies = vec![];
                     for y in 0..8 {
-                        for x in 0..screen_width / 2 / 7 {
+                        for x in 0..columns / 7 {
                             enemies.push(Enemy {
                                 position: Point::new(
-                                    x as f32 * 7.0 + y as f32 + (rand::random::<u8>() % 7) as f32,
+                                    x as f32 * 7.0 + y as f32 + (rng.gen::<u8>() % 7) as f32,
                                     y as f32,
                                 ),
                                 behavior: EnemyBehavior::new(
@@ -262,18 +571,49 @@ impl SpaceInvadersGame {
                     }
                     enemies
                 }
+                EnemyPreset::Swoop => {
+                    // Three rows down and six columns to the right, then back along the top,
+                    // so the enemies don't drift whatever actions are skipped
+                    let mut swoop = Path::arc(
+                        Point::new(3.0, 0.0),
+                        Point::new(0.0, 0.0),
+                        -std::f32::consts::PI,
+                        8,
+                    );
+                    swoop.waypoints.push(Point::new(0.0, 0.0));
+                    let mut enemies = vec![];
+                    for y in 0..4 {
+                        for x in 0..columns.saturating_sub(6) / 8 {
+                            enemies.push(Enemy {
+                                position: Point::new(
+                                    x as f32 * 8.0 + y as f32 % 2.0 * 4.0,
+                                    y as f32,
+                                ),
+                                behavior: EnemyBehavior::new(
+                                    vec![
+                                        EnemyAction::follow(swoop.clone(), 100.0),
+                                        EnemyAction::fire_down(30.0),
+                                    ],
+                                    Duration::from_millis(0),
+                                    0,
+                                ),
+                            });
+                        }
+                    }
+                    enemies
+                }
             },
             props: match props_preset {
                 PropsPreset::Empty => vec![],
                 PropsPreset::Wall => {
                     let mut props = vec![];
-                    for x in 0..screen_width / 2 / 2 {
+                    for x in 0..columns / 2 {
                         props.push(Prop {
                             position: Point::new(x as f32 * 2.0, screen_height as f32 - 3.0),
                             destroyable: false,
                         });
                     }
-                    for x in 0..screen_width / 2 {
+                    for x in 0..columns {
                         for y in 0..3 {
                             props.push(Prop {
                                 position: Point::new(
@@ -293,9 +633,88 @@ impl SpaceInvadersGame {
                     screen_height as f32 - 1.0,
                 )
                 .into(),
+                heat: 0.0,
+                status: StatusEffects::default(),
             },
             from_last_update: Duration::from_nanos(0),
             from_last_fire: Duration::from_nanos(0),
+            to_held_move: Duration::ZERO,
+            heat_points: vec![],
+            shots_fired: 0,
+            enemies_hit: 0,
+            score_events: vec![],
+            sound_effects: vec![],
+            enemy_positions: vec![],
+            enemy_grid: collision_grid(),
+            prop_grid: collision_grid(),
+            nearby: vec![],
+            bullets_collided: vec![],
+            enemies_collided: vec![],
+            props_collided: vec![],
+            can_act: vec![],
+            area: PlayArea::screen(screen_width, screen_height),
+            tweaks: Tweaks::load(),
+            rng,
+        }
+    }
+
+    /// Every enemy repeats the actions instead of the ones of its preset.
+    /// There must be at least one action.
+    pub fn with_behavior(mut self, actions: &[EnemyAction]) -> Self {
+        for enemy in &mut self.enemies {
+            enemy.behavior = EnemyBehavior::new(actions.to_vec(), Duration::ZERO, 0);
+        }
+        self
+    }
+
+    /// Where the bullets in flight are.
+    pub fn bullet_positions(&self) -> impl Iterator<Item = Point<GameBasis>> + '_ {
+        self.bullets.iter().map(|bullet| bullet.body.position)
+    }
+
+    /// Move the player sideways unless something is in the way.
+    /// Returns false if the player stays.
+    fn move_player(&mut self, step: f32, screen_width: u16, screen_height: u16) -> bool {
+        let next_position: Point<GameBasis> =
+            Point::new(self.player.position.x + step, self.player.position.y);
+        let is_blocked = next_position
+            .bounds_check(screen_width, screen_height)
+            .is_some()
+            || self
+                .props
+                .iter()
+                .any(|prop| prop.cell().contains(&next_position))
+            || self
+                .enemies
+                .iter()
+                .any(|enemy| enemy.position.compare(&next_position, MORE_THAN_HALF_CELL));
+        if !is_blocked {
+            self.player.position = next_position;
+        }
+        !is_blocked
+    }
+
+    /// Shoot from the player unless the gun is cooling down or overheated.
+    fn fire(&mut self) {
+        let overheat = config::get().space_invaders.overheat;
+        if self.from_last_fire
+            > script::seconds_or(
+                self.tweaks.fire_rate,
+                config::get().space_invaders.fire_rate(),
+            )
+            && !(overheat && self.player.is_overheated())
+        {
+            self.from_last_fire = Duration::from_nanos(0);
+            if overheat {
+                self.player
+                    .heat_up(config::get().space_invaders.heat_per_shot);
+            }
+            self.shots_fired += 1;
+            self.bullets.push(Bullet::new(
+                Direction::Up,
+                Point::new(self.player.position.x, self.player.position.y - 1.0),
+                1.0,
+            ));
         }
     }
 }
@@ -307,13 +726,96 @@ impl Game for SpaceInvadersGame {
         }
     }
 
-    fn update(
-        &mut self,
-        input: &Option<crossterm::event::KeyEvent>,
-        delta_time: &Duration,
-    ) -> UpdateEvent {
-        let (screen_width, screen_height) =
-            crossterm::terminal::size().expect("Failed to get terminal size");
+    fn resize(&mut self, area: PlayArea) {
+        self.area = area;
+    }
+
+    fn take_heat_points(&mut self) -> Vec<Point<GameBasis>> {
+        std::mem::take(&mut self.heat_points)
+    }
+
+    fn take_score_events(&mut self) -> Vec<ScoreEvent> {
+        std::mem::take(&mut self.score_events)
+    }
+
+    fn take_sound_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.sound_effects)
+    }
+
+    fn chaos_event(&mut self) -> Option<String> {
+        for enemy in self.enemies.iter_mut() {
+            enemy.position.y += 1.0;
+        }
+        Some(crate::i18n::tr(crate::i18n::Text::WaveWarpsDown).to_owned())
+    }
+
+    fn save(&self) -> Option<crate::save::SavedGame> {
+        Some(crate::save::SavedGame::SpaceInvaders(self.clone()))
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Game>> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn counts(&self) -> crate::lifetime::Counts {
+        crate::lifetime::Counts {
+            enemies_destroyed: self.enemies_hit as u64,
+            ..Default::default()
+        }
+    }
+
+    fn entities(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("enemies", self.enemies.len()),
+            ("bullets", self.bullets.len()),
+            ("props", self.props.len()),
+        ]
+    }
+
+    fn help(&self) -> Vec<String> {
+        use crate::help::key;
+        use crate::i18n::{format, tr, Text};
+
+        let sideways = format!("{}/{}", key(Action::MoveLeft), key(Action::MoveRight));
+        vec![
+            format(Text::InvadersMove, &[&sideways]),
+            format(Text::InvadersFire, &[&key(Action::Fire)]),
+            format(Text::InvadersPoints, &[&FOR_ENEMY_SCORE]),
+            tr(Text::InvadersWalls).to_owned(),
+            tr(Text::InvadersEnd).to_owned(),
+        ]
+    }
+
+    fn summary(&self) -> Vec<String> {
+        use crate::i18n::{format, Text};
+
+        let mut lines = vec![
+            format(Text::EnemiesDestroyed, &[&self.enemies_hit]),
+            format(Text::ShotsFired, &[&self.shots_fired]),
+        ];
+        if let Some(performance) = self.performance() {
+            let percent = (performance.value * 100.0).round();
+            lines.push(format(Text::Accuracy, &[&percent]));
+        }
+        lines
+    }
+
+    /// Share of the player shots that hit an enemy
+    fn performance(&self) -> Option<crate::stats::Performance> {
+        if self.shots_fired == 0 {
+            return None;
+        }
+
+        Some(crate::stats::Performance {
+            name: crate::i18n::Text::InvadersPerformance,
+            value: (self.enemies_hit as f32 / self.shots_fired as f32).min(1.0),
+            tip: crate::i18n::Text::InvadersTip,
+        })
+    }
+
+    fn update(&mut self, input: &FrameInput, delta_time: &Duration) -> UpdateEvent {
+        profile_scope!("invaders.update");
+        let (screen_width, screen_height) = self.area.screen_size();
 
         // last update time
         {
@@ -321,10 +823,8 @@ impl Game for SpaceInvadersGame {
         }
 
         // quit
-        if let Some(key) = input {
-            if key.code == EXIT_BUTTON {
-                return UpdateEvent::GameOver;
-            }
+        if input.action() == Some(Action::Quit) {
+            return UpdateEvent::GameOver;
         }
 
         // what not depends on self.last_update_time
@@ -338,155 +838,155 @@ impl Game for SpaceInvadersGame {
 
                 // player fire delta
                 self.from_last_fire += *delta_time;
+                self.player.cool(*delta_time);
             }
 
             // player movement
             // modifies self.player
             {
-                let next_position: Option<Point<GameBasis>> = match input {
-                    Some(crossterm::event::KeyEvent {
-                        code: crossterm::event::KeyCode::Left,
-                        ..
-                    }) => Some(Point::new(
-                        self.player.position.x - PLAYER_SPEED,
-                        self.player.position.y,
-                    )),
-                    Some(crossterm::event::KeyEvent {
-                        code: crossterm::event::KeyCode::Right,
-                        ..
-                    }) => Some(Point::new(
-                        self.player.position.x + PLAYER_SPEED,
-                        self.player.position.y,
-                    )),
-                    Some(crossterm::event::KeyEvent {
-                        code: crossterm::event::KeyCode::Char(' '),
-                        ..
-                    }) => {
-                        if self.from_last_fire > PLAYER_FIRE_RATE {
-                            self.from_last_fire = Duration::from_nanos(0);
-                            self.bullets.push(Bullet {
-                                move_direction: Direction::Up,
-                                position: Point::new(
-                                    self.player.position.x,
-                                    self.player.position.y - 1.0,
-                                ),
-                                speed: 1.0,
-                            });
+                let player_speed = config::get().space_invaders.player_speed;
+                let presses = &input.presses;
+                // Every tap of the frame moves, in the order pressed
+                for (action, count) in presses.iter() {
+                    let step = match action {
+                        Action::MoveLeft => -player_speed,
+                        Action::MoveRight => player_speed,
+                        Action::Fire => {
+                            self.fire();
+                            continue;
+                        }
+                        _ => continue,
+                    };
+                    self.to_held_move = HELD_MOVE_DELAY;
+                    for _ in 0..count {
+                        if !self.move_player(step, screen_width, screen_height) {
+                            break;
                         }
-                        None
                     }
-                    _ => None,
-                };
+                }
 
-                if let Some(next_position) = next_position {
-                    if next_position
-                        .bounds_check(screen_width, screen_height)
-                        .is_none()
-                        && self
-                            .props
-                            .iter()
-                            .all(|prop| !prop.position.compare(&next_position, MORE_THAN_HALF_CELL))
-                        && self.enemies.iter().all(|enemy| {
-                            !enemy.position.compare(&next_position, MORE_THAN_HALF_CELL)
-                        })
-                    {
-                        self.player.position = next_position;
+                // Held keys keep moving and firing while other keys are pressed
+                let held_step = if input.is_held(Action::MoveLeft) {
+                    Some(-player_speed)
+                } else if input.is_held(Action::MoveRight) {
+                    Some(player_speed)
+                } else {
+                    None
+                };
+                let is_move_tapped =
+                    presses.count(Action::MoveLeft) + presses.count(Action::MoveRight) > 0;
+                if let (Some(step), false) = (held_step, is_move_tapped) {
+                    self.to_held_move = self.to_held_move.saturating_sub(*delta_time);
+                    if self.to_held_move.is_zero() {
+                        self.to_held_move = HELD_MOVE_INTERVAL;
+                        self.move_player(step, screen_width, screen_height);
                     }
                 }
+                if input.is_held(Action::Fire) {
+                    self.fire();
+                }
             }
 
             // player bullet collision
-            let is_player_collided = self.bullets.iter().any(|bullet| {
-                self.player
-                    .position
-                    .compare(&bullet.position, MORE_THAN_HALF_CELL)
-            });
+            let player_cell = Rect::around(
+                self.player.position,
+                Point::new(MORE_THAN_HALF_CELL, MORE_THAN_HALF_CELL),
+            );
+            let is_player_collided = self
+                .bullets
+                .iter()
+                .any(|bullet| player_cell.sweep(&bullet.path()).is_some());
+            if is_player_collided {
+                self.heat_points.push(self.player.position);
+            }
 
             is_player_collided
         };
 
         // what depends on self.last_update_time
+        // The rest carries over so the speed doesn't depend on the frame rate
         if self.from_last_update > GAME_UPDATE_INTERVAL {
-            self.from_last_update = Duration::from_nanos(0);
+            self.from_last_update =
+                (self.from_last_update - GAME_UPDATE_INTERVAL).min(GAME_UPDATE_INTERVAL);
 
             // enemies movement
             // modifies self.enemies
             {
-                let mut new_enemies = self.enemies.clone();
-
-                for new_enemy in &mut new_enemies {
-                    let action = new_enemy.behavior.current_action();
-                    let behavior = &mut new_enemy.behavior;
+                profile_scope!("invaders.enemies");
+                // Enemies keep out of the places the others had at the start of the tick
+                self.enemy_positions.clear();
+                self.enemy_positions
+                    .extend(self.enemies.iter().map(|enemy| enemy.position));
+                self.enemy_grid
+                    .rebuild(self.enemy_positions.iter().copied());
+                self.prop_grid
+                    .rebuild(self.props.iter().map(|prop| prop.position));
+
+                // Only the random rolls depend on the order, so where the enemies
+                // can go is worked out first, on all cores with the `rayon` feature
+                let surroundings = Surroundings {
+                    screen_size: (screen_width, screen_height),
+                    enemy_positions: &self.enemy_positions,
+                    enemy_grid: &self.enemy_grid,
+                    props: &self.props,
+                    prop_grid: &self.prop_grid,
+                    player: self.player.position,
+                };
+                self.can_act.clear();
+                #[cfg(feature = "rayon")]
+                self.can_act.par_extend(
+                    self.enemies
+                        .par_iter()
+                        .with_min_len(PARALLEL_MIN_LEN)
+                        .map(|enemy| surroundings.can_act(enemy)),
+                );
+                #[cfg(not(feature = "rayon"))]
+                self.can_act
+                    .extend(self.enemies.iter().map(|enemy| surroundings.can_act(enemy)));
+
+                for (enemy, &can_act) in self.enemies.iter_mut().zip(&self.can_act) {
+                    let step = enemy.step();
+                    let action = enemy.behavior.current_action();
+                    let (duration, chance) = (action.duration, action.chance);
+                    let behavior = &mut enemy.behavior;
                     let start_action_ind = behavior.current_action;
 
                     if behavior.to_next_move.as_nanos() == 0 {
+                        // Only firing follows the difficulty
+                        let chance = match step {
+                            Step::Fire(..) => (chance * difficulty::level()).min(100.0),
+                            _ => chance,
+                        };
                         // 'failures is do-while loop
                         'failures: loop {
-                            if is_success(action.chance)
-                                && match &action.action_type {
-                                    EnemyActionType::Move(direction, speed) => {
-                                        let next_position: Point<GameBasis> = {
-                                            match direction {
-                                                Direction::Up => Point::new(
-                                                    new_enemy.position.x,
-                                                    new_enemy.position.y - speed,
-                                                ),
-                                                Direction::Down => Point::new(
-                                                    new_enemy.position.x,
-                                                    new_enemy.position.y + speed,
-                                                ),
-                                                Direction::Left => Point::new(
-                                                    new_enemy.position.x - speed,
-                                                    new_enemy.position.y,
-                                                ),
-                                                Direction::Right => Point::new(
-                                                    new_enemy.position.x + speed,
-                                                    new_enemy.position.y,
-                                                ),
-                                            }
-                                        };
-                                        if next_position
-                                        .bounds_check(screen_width, screen_height)
-                                        .is_none()
-                                        && self.enemies.iter().all(
-                                            |other| {
-                                                !other
-                                                    .position
-                                                    .compare(&next_position, MORE_THAN_HALF_CELL)
-                                            }, /* check with self will forbid to move on the spot */
-                                        )
-                                        && self.props.iter().all(|prop| {
-                                            !prop
-                                                .position
-                                                .compare(&next_position, MORE_THAN_HALF_CELL)
-                                        })
-                                        && !self
-                                            .player
-                                            .position
-                                            .compare(&next_position, MORE_THAN_HALF_CELL)
-                                    {
-                                        new_enemy.position = next_position;
-                                        true
-                                    } else {
-                                        false
+                            if is_success(&mut self.rng, chance) && can_act {
+                                let is_last = match step {
+                                    Step::Move {
+                                        to,
+                                        walked,
+                                        is_last,
+                                    } => {
+                                        enemy.position = to;
+                                        behavior.walked = walked;
+                                        is_last
                                     }
-                                    }
-                                    EnemyActionType::Fire(direction, speed) => {
-                                        self.bullets.push(Bullet {
-                                            move_direction: *direction,
-                                            position: Point::new(
-                                                new_enemy.position.x,
-                                                new_enemy.position.y + FIRE_BULLET_OFFSET,
+                                    Step::Fire(direction, speed) => {
+                                        self.bullets.push(Bullet::new(
+                                            direction,
+                                            Point::new(
+                                                enemy.position.x,
+                                                enemy.position.y + FIRE_BULLET_OFFSET,
                                             ),
-                                            speed: *speed,
-                                        });
+                                            speed,
+                                        ));
                                         true
                                     }
-                                    EnemyActionType::Wait => true,
+                                    Step::Wait => true,
+                                };
+                                behavior.to_next_move += duration;
+                                if is_last {
+                                    behavior.next_action();
                                 }
-                            {
-                                behavior.to_next_move += action.duration;
-                                behavior.next_action();
                                 break 'failures;
                             }
                             behavior.next_action();
@@ -497,35 +997,24 @@ impl Game for SpaceInvadersGame {
                         }
                     }
                 }
-
-                self.enemies = new_enemies;
             }
 
             // bullets movement
             // modifies bullets
             {
-                for bullet in &mut self.bullets {
-                    let bullet_position = &mut bullet.position;
-                    let bullet_speed = bullet.speed;
-                    match bullet.move_direction {
-                        Direction::Up => {
-                            bullet_position.y -= bullet_speed;
-                        }
-                        Direction::Down => {
-                            bullet_position.y += bullet_speed;
-                        }
-                        Direction::Left => {
-                            bullet_position.x -= bullet_speed;
-                        }
-                        Direction::Right => {
-                            bullet_position.x += bullet_speed;
-                        }
-                    }
-                }
+                profile_scope!("invaders.bullets");
+                #[cfg(feature = "rayon")]
+                self.bullets
+                    .par_iter_mut()
+                    .with_min_len(PARALLEL_MIN_LEN)
+                    .for_each(Bullet::advance);
+                #[cfg(not(feature = "rayon"))]
+                self.bullets.iter_mut().for_each(Bullet::advance);
 
                 // delete out of bounds bullets
                 self.bullets.retain(|bullet| {
                     bullet
+                        .body
                         .position
                         .bounds_check(screen_width, screen_height)
                         .is_none()
@@ -535,14 +1024,21 @@ impl Game for SpaceInvadersGame {
             // enemies, bullets, props collision
             // modifies self.bullets, self.enemies, self.props, self.score
             {
+                profile_scope!("invaders.collisions");
                 // collision states
                 // assigned with self values by index
-                let mut bullets_collision_state: Vec<bool> =
-                    std::iter::repeat(false).take(self.bullets.len()).collect();
-                let mut enemies_collision_state: Vec<bool> =
-                    std::iter::repeat(false).take(self.enemies.len()).collect();
-                let mut props_collision_state: Vec<bool> =
-                    std::iter::repeat(false).take(self.props.len()).collect();
+                let bullets_collision_state = &mut self.bullets_collided;
+                bullets_collision_state.clear();
+                bullets_collision_state.resize(self.bullets.len(), false);
+                let enemies_collision_state = &mut self.enemies_collided;
+                enemies_collision_state.clear();
+                enemies_collision_state.resize(self.enemies.len(), false);
+                let props_collision_state = &mut self.props_collided;
+                props_collision_state.clear();
+                props_collision_state.resize(self.props.len(), false);
+                // Props don't move, enemies moved since the grid was built
+                self.enemy_grid
+                    .rebuild(self.enemies.iter().map(|enemy| enemy.position));
 
                 for (bullet_ind, is_bullet_collided) in
                     bullets_collision_state.iter_mut().enumerate()
@@ -551,41 +1047,66 @@ impl Game for SpaceInvadersGame {
                         continue;
                     };
 
-                    // enemy collision
-                    for (enemy_ind, is_enemy_collided) in
-                        &mut enemies_collision_state.iter_mut().enumerate()
-                    {
-                        if *is_enemy_collided {
-                            continue;
-                        };
+                    // The whole way of the last move, so fast bullets don't pass
+                    // through, and only the first thing on it is hit
+                    let path = self.bullets[bullet_ind].path();
+                    let (center, reach) = (path.begin.lerp(&path.end, 0.5), path.length() / 2.0);
+                    let first_hit = |hit: Option<(f32, usize)>, (at, ind)| match hit {
+                        Some((first_at, _)) if first_at <= at => hit,
+                        _ => Some((at, ind)),
+                    };
 
-                        if self.bullets[bullet_ind]
-                            .position
-                            .compare(&self.enemies[enemy_ind].position, MORE_THAN_HALF_CELL)
-                        {
-                            *is_enemy_collided = true;
-                            *is_bullet_collided = true;
-                            self.score += FOR_ENEMY_SCORE;
-                        }
-                    }
+                    // enemy collision, earlier enemies first on a tie
+                    self.nearby.clear();
+                    self.nearby
+                        .extend(self.enemy_grid.near(center, MORE_THAN_HALF_CELL + reach));
+                    self.nearby.sort_unstable();
+                    let enemy_hit = self
+                        .nearby
+                        .iter()
+                        .filter(|&&enemy_ind| !enemies_collision_state[enemy_ind])
+                        .filter_map(|&enemy_ind| {
+                            let at = self.enemies[enemy_ind].cell().sweep(&path)?;
+                            Some((at, enemy_ind))
+                        })
+                        .fold(None, first_hit);
 
                     // prop collision
-                    for (prop_ind, is_prop_collided) in props_collision_state.iter_mut().enumerate()
-                    {
-                        if *is_prop_collided {
-                            continue;
-                        };
+                    self.nearby.clear();
+                    self.nearby
+                        .extend(self.prop_grid.near(center, MORE_THAN_HALF_CELL + reach));
+                    self.nearby.sort_unstable();
+                    let prop_hit = self
+                        .nearby
+                        .iter()
+                        .filter(|&&prop_ind| !props_collision_state[prop_ind])
+                        .filter_map(|&prop_ind| {
+                            let at = self.props[prop_ind].cell().sweep(&path)?;
+                            Some((at, prop_ind))
+                        })
+                        .fold(None, first_hit);
 
-                        if self.bullets[bullet_ind]
-                            .position
-                            .compare(&self.props[prop_ind].position, MORE_THAN_HALF_CELL)
-                        {
-                            *is_prop_collided = true;
+                    let prop_at = prop_hit.map_or(f32::INFINITY, |(at, _)| at);
+                    match (enemy_hit, prop_hit) {
+                        (Some((enemy_at, enemy_ind)), _) if enemy_at <= prop_at => {
+                            enemies_collision_state[enemy_ind] = true;
+                            *is_bullet_collided = true;
+                            self.score += FOR_ENEMY_SCORE;
+                            self.enemies_hit += 1;
+                            self.sound_effects.push(Effect::EnemyHit);
+                            self.score_events.push(ScoreEvent::at(
+                                FOR_ENEMY_SCORE as i64,
+                                self.enemies[enemy_ind].position,
+                            ));
+                        }
+                        (_, Some((_, prop_ind))) => {
+                            props_collision_state[prop_ind] = true;
                             *is_bullet_collided = true;
                             if self.props[prop_ind].destroyable {
                                 self.score += FOR_PROP_SCORE;
                             }
                         }
+                        _ => {}
                     }
                 }
 
@@ -609,123 +1130,109 @@ impl Game for SpaceInvadersGame {
         }
 
         if is_player_collided || self.enemies.is_empty() {
+            tracing::debug!(
+                is_player_collided,
+                enemies_left = self.enemies.len(),
+                "invaders run over"
+            );
             UpdateEvent::GameOver
         } else {
             UpdateEvent::GameContinue
         }
     }
 
-    fn draw(&self, out: &mut std::io::Stdout, _delta_time: &Duration) -> crossterm::Result<()> {
-        use crossterm::{
-            cursor::MoveTo,
-            execute,
-            style::{Print, Stylize},
-            terminal::size,
-        };
-        use std::io::Write;
+    fn draw(&self, frame: &mut FrameBuffer, _delta_time: &Duration) {
+        profile_scope!("invaders.draw");
+        use crossterm::style::{ContentStyle, Stylize};
 
-        let (max_x, max_y) = size().expect("Failed to get terminal size");
+        // The bottom row of the play area, the cannon row
+        let (width, height) = self.area.screen_size();
+        let bottom_row = crate::layout::Rect::new(0, height.saturating_sub(1), width, 1);
+        let palette = theme::palette();
+        let glyphs = crate::glyphs::get();
 
         // enemies
         {
-            let enemy_rows: Vec<Vec<char>> = {
-                let mut enemy_rows = vec![vec![' '; max_x as usize]; max_y as usize];
-
-                for enemy in &self.enemies {
-                    let enemy_screen_position = enemy.position;
-
-                    let enemy_row = &mut enemy_rows[enemy_screen_position.y as usize];
-
-                    if enemy_screen_position.x.round() as u16 * 2 < max_x {
-                        enemy_row[enemy_screen_position.x as usize * 2] = '◥';
-                        enemy_row[enemy_screen_position.x as usize * 2 + 1] = '◤';
-                    }
+            let style = ContentStyle::new().with(palette.enemy);
+            for enemy in &self.enemies {
+                let Some(cell) = enemy.position.to_cell() else {
+                    continue;
+                };
+                for (dx, &symbol) in glyphs.invader.iter().enumerate() {
+                    frame.set(
+                        Layer::Game,
+                        cell.x + dx as u16,
+                        cell.y,
+                        Cell { symbol, style },
+                    );
                 }
-
-                enemy_rows
-            };
-
-            for (ind, enemy_row) in enemy_rows.iter().enumerate() {
-                execute!(
-                    out,
-                    MoveTo(0, ind as u16),
-                    Print(enemy_row.iter().collect::<String>().red())
-                )?;
             }
         }
 
         // bullets
         {
             for bullet in &self.bullets {
-                let bullet_screen_position = Point::<ScreenBasis>::from(bullet.position);
-
-                execute!(
-                    out,
-                    MoveTo(
-                        bullet_screen_position.x as u16,
-                        bullet_screen_position.y as u16
-                    ),
-                    Print(match bullet.move_direction {
-                        Direction::Up => "<>".green(),
-                        Direction::Left | Direction::Right => "<>".yellow(),
-                        Direction::Down => "<>".red(),
-                    })
-                )?;
+                let Some(cell) = bullet.body.position.to_cell() else {
+                    continue;
+                };
+                frame.print_styled(
+                    Layer::Game,
+                    cell.x,
+                    cell.y,
+                    match bullet.move_direction {
+                        Direction::Up => glyphs.bullets[0].with(palette.player),
+                        Direction::Down => glyphs.bullets[1].with(palette.enemy),
+                        Direction::Left => glyphs.bullets[2].with(palette.warning),
+                        Direction::Right => glyphs.bullets[3].with(palette.warning),
+                    },
+                );
             }
         }
 
         // props
         {
             for prop in &self.props {
-                let prop_screen_position = Point::<ScreenBasis>::from(prop.position);
-
-                execute!(
-                    out,
-                    MoveTo(prop_screen_position.x as u16, prop_screen_position.y as u16),
-                    Print(if prop.destroyable {
-                        "▓▓".green()
+                let Some(cell) = prop.position.to_cell() else {
+                    continue;
+                };
+                frame.print_styled(
+                    Layer::Game,
+                    cell.x,
+                    cell.y,
+                    if prop.destroyable {
+                        glyphs.barrier.with(palette.barrier)
                     } else {
-                        "▓▓".blue()
-                    })
-                )?;
+                        glyphs.wall.with(palette.wall)
+                    },
+                );
             }
         }
 
-        // score
-        {
-            fn digits_num(num: usize) -> u16 {
-                if num == 0 {
-                    1
-                } else {
-                    f32::floor(f32::log10(num as f32) + 1.0) as u16
-                }
-            }
-
-            let score_hint = "Score: ";
-            execute!(
-                out,
-                MoveTo(
-                    max_x - score_hint.len() as u16 - digits_num(self.score),
-                    max_y - 1
-                )
-            )?;
-            write!(out, "Score: {}", self.score)?;
+        // cannon heat
+        if config::get().space_invaders.overheat {
+            const HEAT_LABEL: &str = "Heat ";
+            let style = if self.player.is_overheated() {
+                ContentStyle::new().with(palette.danger).bold()
+            } else if self.player.heat > 0.5 {
+                ContentStyle::new().with(palette.warning)
+            } else {
+                ContentStyle::new()
+            };
+            let bar = ProgressBar::new(self.player.heat, HEAT_BAR_WIDTH)
+                .label(HEAT_LABEL)
+                .style(style)
+                .draw(frame, Layer::Hud, bottom_row);
+            self.player.status.draw(frame, bar.right() + 1, bar.y);
         }
 
         // player
-        {
-            let player_screen_position: Point<ScreenBasis> = self.player.position.into();
-
-            execute!(
-                out,
-                MoveTo(
-                    player_screen_position.x as u16,
-                    player_screen_position.y as u16
-                ),
-                Print("◢◣".green())
-            )?;
+        if let Some(cell) = self.player.position.to_cell() {
+            frame.print_styled(
+                Layer::Game,
+                cell.x,
+                cell.y,
+                glyphs.cannon.with(palette.player),
+            );
         }
-
-        execute!(out, MoveTo(0, 0))
     }
 }
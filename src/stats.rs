@@ -0,0 +1,275 @@
+//! Scores of finished runs, kept in `<data dir>/cl_games/stats.toml`,
+//! the grade of a run against them and the score trends screen.
+use crate::i18n::{format, tr, Text};
+use crate::render::{FrameBuffer, Layer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub const FILE_NAME: &str = "stats.toml";
+/// Older scores are forgotten, grades follow the recent form.
+pub const HISTORY_LENGTH: usize = 100;
+/// Performance below it earns the game's tip.
+const TIP_THRESHOLD: f32 = 0.5;
+/// Share of the score in the grade, the rest is the performance.
+const SCORE_WEIGHT: f32 = 0.7;
+
+/// How well a run was played apart from the score (accuracy, efficiency...).
+#[derive(Clone, Copy, Debug)]
+pub struct Performance {
+    /// What is measured, like "accuracy"
+    pub name: Text,
+    /// From 0 to 1
+    pub value: f32,
+    /// Shown when the value is low
+    pub tip: Text,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Grade {
+    D,
+    C,
+    B,
+    A,
+    S,
+}
+
+impl Grade {
+    fn from_rating(rating: f32) -> Self {
+        match rating {
+            rating if rating >= 0.9 => Grade::S,
+            rating if rating >= 0.75 => Grade::A,
+            rating if rating >= 0.5 => Grade::B,
+            rating if rating >= 0.25 => Grade::C,
+            _ => Grade::D,
+        }
+    }
+}
+
+impl std::fmt::Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Grade of a run with a line of feedback.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub grade: Grade,
+    /// Share of the previous runs with a lower score, None for the first run
+    pub percentile: Option<f32>,
+    pub performance: Option<Performance>,
+    pub feedback: String,
+}
+
+/// Recent scores of every game by name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    games: BTreeMap<String, Vec<i64>>,
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::config::data_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+impl History {
+    /// Missing or broken file means no history.
+    pub fn load() -> Self {
+        path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path = path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+
+    /// Recent scores of the game, oldest first.
+    pub fn scores(&self, game_name: &str) -> &[i64] {
+        self.games.get(game_name).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn add(&mut self, game_name: &str, score: i64) {
+        let scores = self.games.entry(game_name.to_owned()).or_default();
+        scores.push(score);
+        if scores.len() > HISTORY_LENGTH {
+            scores.remove(0);
+        }
+    }
+
+    /// Grade the score against the previous ones. Without them the score
+    /// is compared to the par score, if the game has one.
+    pub fn grade(
+        &self,
+        game_name: &str,
+        score: i64,
+        par_score: Option<i64>,
+        performance: Option<Performance>,
+    ) -> Report {
+        let scores = self
+            .games
+            .get(game_name)
+            .filter(|scores| !scores.is_empty());
+        // Ties count as half lower
+        let percentile = scores.map(|scores| {
+            let lower = scores.iter().filter(|&&other| other < score).count();
+            let equal = scores.iter().filter(|&&other| other == score).count();
+            (lower as f32 + equal as f32 / 2.0) / scores.len() as f32
+        });
+        let score_rating = percentile.unwrap_or_else(|| match par_score {
+            Some(par_score) => (score as f32 / par_score.max(1) as f32).clamp(0.0, 1.0),
+            None => 0.5,
+        });
+        let rating = match performance {
+            Some(performance) => {
+                SCORE_WEIGHT * score_rating + (1.0 - SCORE_WEIGHT) * performance.value
+            }
+            None => score_rating,
+        };
+
+        let is_best = scores.is_some_and(|scores| scores.iter().all(|&other| other < score));
+        let feedback = match (performance, percentile) {
+            (_, Some(_)) if is_best => Text::BestRunYet,
+            (Some(performance), _) if performance.value < TIP_THRESHOLD => performance.tip,
+            (_, None) => Text::FirstRun,
+            (_, Some(percentile)) if percentile >= 0.75 => Text::BetterRun,
+            (_, Some(percentile)) if percentile < 0.25 => Text::BelowUsual,
+            _ => Text::CloseToUsual,
+        };
+
+        Report {
+            grade: Grade::from_rating(rating),
+            percentile,
+            performance,
+            feedback: tr(feedback).to_owned(),
+        }
+    }
+}
+
+/// Show score charts of the recent runs one game at a time.
+/// Left/Right switch games, any other key returns.
+pub fn show(
+    out: &mut std::io::Stdout,
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+) -> crossterm::Result<()> {
+    use crate::input::Action;
+    use crossterm::event::KeyEventKind;
+    use crossterm::terminal;
+
+    let action = |key: &crossterm::event::KeyEvent| crate::config::get().keys.action(key);
+    let history = History::load();
+    let games: Vec<(&String, &Vec<i64>)> = history
+        .games
+        .iter()
+        .filter(|(_, scores)| !scores.is_empty())
+        .collect();
+    let mut current = 0;
+    let mut frame = FrameBuffer::from_terminal()?;
+
+    loop {
+        let (width, height) = terminal::size()?;
+        frame.clear(width, height);
+
+        match games.get(current) {
+            None => frame.print(Layer::Hud, 0, 0, tr(Text::NoRunsRecorded)),
+            Some((name, scores)) => {
+                frame.print(
+                    Layer::Hud,
+                    0,
+                    0,
+                    &format!("{} ({}/{})", name, current + 1, games.len()),
+                );
+                frame.print(
+                    Layer::Hud,
+                    0,
+                    height.saturating_sub(1),
+                    tr(Text::BrowseHint),
+                );
+                draw_scores(
+                    &mut frame,
+                    scores,
+                    (0, 1),
+                    (width, height.saturating_sub(2)),
+                );
+            }
+        }
+        frame.flush(out)?;
+
+        let key = crate::input::recv_key(rx);
+        match key {
+            key if key.kind == KeyEventKind::Release => {}
+            key if action(&key) == Some(Action::MoveLeft) && !games.is_empty() => {
+                current = (current + games.len() - 1) % games.len();
+            }
+            key if action(&key) == Some(Action::MoveRight) && !games.is_empty() => {
+                current = (current + 1) % games.len();
+            }
+            _ => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Summary line and a bar chart of the scores that fit, labeled with the highest
+/// and the lowest score.
+fn draw_scores(
+    frame: &mut FrameBuffer,
+    scores: &[i64],
+    (x, y): (u16, u16),
+    (width, height): (u16, u16),
+) {
+    // Labels are at most as wide as the widest score
+    let label_width = scores
+        .iter()
+        .chain([&0])
+        .map(|score| score.to_string().len())
+        .max()
+        .unwrap_or(1) as u16
+        + 1;
+    let columns = width.saturating_sub(label_width);
+    let shown = &scores[scores.len().saturating_sub(columns as usize)..];
+    // Same scale as the chart
+    let high = shown.iter().copied().max().unwrap_or(0).max(0);
+    let low = shown.iter().copied().min().unwrap_or(0).min(0);
+
+    let average = shown.iter().sum::<i64>() as f32 / shown.len().max(1) as f32;
+    frame.print(
+        Layer::Hud,
+        x,
+        y,
+        &format(
+            Text::LastRuns,
+            &[
+                &shown.len(),
+                shown.iter().max().unwrap_or(&0),
+                &format!("{:.1}", average),
+                shown.last().unwrap_or(&0),
+            ],
+        ),
+    );
+
+    let chart_height = height.saturating_sub(2);
+    if chart_height == 0 {
+        return;
+    }
+    let chart_y = y + 2;
+    frame.print(Layer::Hud, x, chart_y, &high.to_string());
+    frame.print(Layer::Hud, x, chart_y + chart_height - 1, &low.to_string());
+    let values: Vec<f32> = shown.iter().map(|&score| score as f32).collect();
+    crate::chart::draw_bars(
+        frame,
+        Layer::Hud,
+        (x + label_width, chart_y),
+        (columns, chart_height),
+        &values,
+    );
+}
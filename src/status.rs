@@ -0,0 +1,167 @@
+//! Timed effects on game entities (shields, inverted controls, shrunk planks...)
+//! with stacking rules and HUD icons.
+use crate::render::{FrameBuffer, Layer};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Status {
+    /// Snake: the next collision is forgiven
+    Shield,
+    /// Snake: collisions are ignored, lets it pass through itself after a shield is used
+    Phasing,
+    /// Snake: controls are swapped (chaos mode)
+    Inverted,
+    /// Pong: the player plank is shorter (chaos mode)
+    Shrunk,
+    /// Space invaders: the cannon can't fire
+    Overheated,
+}
+
+/// What adding an active status does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stacking {
+    /// Time starts over
+    Refresh,
+    /// Time is added to the time left
+    Extend,
+    /// One more stack, up to the given count
+    Stack(u32),
+}
+
+impl Status {
+    pub fn stacking(self) -> Stacking {
+        match self {
+            Status::Shield => Stacking::Stack(3),
+            Status::Inverted => Stacking::Extend,
+            Status::Phasing | Status::Shrunk | Status::Overheated => Stacking::Refresh,
+        }
+    }
+
+    pub fn icon(self) -> crossterm::style::StyledContent<&'static str> {
+        use crossterm::style::Stylize;
+
+        let palette = crate::theme::palette();
+        let [shield, phasing, inverted, shrunk, overheated] = crate::glyphs::get().statuses;
+        match self {
+            Status::Shield => shield.with(palette.shield).bold(),
+            Status::Phasing => phasing.with(palette.phasing),
+            Status::Inverted => inverted.with(palette.warning),
+            Status::Shrunk => shrunk.with(palette.danger),
+            Status::Overheated => overheated.with(palette.danger).bold(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct Effect {
+    status: Status,
+    /// None for effects lasting until used
+    left: Option<Duration>,
+    stacks: u32,
+}
+
+/// Active effects of an entity.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StatusEffects {
+    active: Vec<Effect>,
+}
+
+impl StatusEffects {
+    /// Apply the status for the given time, None for until used.
+    /// Already active statuses follow their `Stacking`.
+    pub fn add(&mut self, status: Status, duration: Option<Duration>) {
+        let Some(effect) = self
+            .active
+            .iter_mut()
+            .find(|effect| effect.status == status)
+        else {
+            self.active.push(Effect {
+                status,
+                left: duration,
+                stacks: 1,
+            });
+            return;
+        };
+
+        match status.stacking() {
+            Stacking::Refresh => effect.left = duration,
+            Stacking::Extend => {
+                effect.left = match (effect.left, duration) {
+                    (Some(left), Some(duration)) => Some(left + duration),
+                    _ => None,
+                }
+            }
+            Stacking::Stack(max) => {
+                effect.stacks = (effect.stacks + 1).min(max);
+                effect.left = duration;
+            }
+        }
+    }
+
+    pub fn has(&self, status: Status) -> bool {
+        self.stacks(status) > 0
+    }
+
+    pub fn stacks(&self, status: Status) -> u32 {
+        self.active
+            .iter()
+            .find(|effect| effect.status == status)
+            .map_or(0, |effect| effect.stacks)
+    }
+
+    /// Use one stack up. Returns false if the status was not active.
+    pub fn consume(&mut self, status: Status) -> bool {
+        let Some(ind) = self
+            .active
+            .iter()
+            .position(|effect| effect.status == status)
+        else {
+            return false;
+        };
+        self.active[ind].stacks -= 1;
+        if self.active[ind].stacks == 0 {
+            self.active.remove(ind);
+        }
+        true
+    }
+
+    /// Advance the time. Returns the statuses that ran out.
+    pub fn tick(&mut self, delta_time: Duration) -> Vec<Status> {
+        for effect in self.active.iter_mut() {
+            if let Some(left) = &mut effect.left {
+                *left = left.saturating_sub(delta_time);
+            }
+        }
+
+        let mut expired = vec![];
+        self.active.retain(|effect| {
+            let is_expired = effect.left.is_some_and(|left| left.is_zero());
+            if is_expired {
+                expired.push(effect.status);
+            }
+            !is_expired
+        });
+        expired
+    }
+
+    /// Icons of the active statuses in a row like `◈2 ⇄3s`.
+    pub fn draw(&self, frame: &mut FrameBuffer, x: u16, y: u16) {
+        let mut x = x;
+        for effect in self.active.iter() {
+            frame.print_styled(Layer::Hud, x, y, effect.status.icon());
+            x += 1;
+
+            if effect.stacks > 1 {
+                frame.print_args(Layer::Hud, x, y, format_args!("{}", effect.stacks));
+                x += crate::util::digits(effect.stacks as u64);
+            }
+            if let Some(left) = effect.left {
+                let seconds = left.as_secs_f32().ceil() as u32;
+                frame.print_args(Layer::Hud, x, y, format_args!("{}s", seconds));
+                x += crate::util::digits(seconds as u64) + 1;
+            }
+            x += 1;
+        }
+    }
+}
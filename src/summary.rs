@@ -0,0 +1,224 @@
+//! Screen shown when a run is over: the score, how the run went,
+//! and a menu to play again, watch the replay, return to the menu or quit.
+//! A new personal best is celebrated with confetti unless effects are reduced.
+use crate::frame::Frame;
+use crate::i18n::{format, tr, Text};
+use crate::input::Action;
+use crate::layout::Rect;
+use crate::render::{FrameBuffer, Layer};
+use std::time::Duration;
+
+/// What the player picked on the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Next {
+    Restart,
+    Menu,
+    Quit,
+}
+
+/// Items of the game over menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Item {
+    PlayAgain,
+    Replay,
+    BackToMenu,
+    Quit,
+}
+
+impl Item {
+    fn name(self) -> String {
+        match self {
+            Item::PlayAgain => tr(Text::PlayAgain).to_owned(),
+            Item::Replay => format(Text::WatchReplay, &[&crate::replay::LENGTH.as_secs()]),
+            Item::BackToMenu => tr(Text::BackToMenu).to_owned(),
+            Item::Quit => tr(Text::Quit).to_owned(),
+        }
+    }
+}
+
+pub struct Summary {
+    pub game_name: String,
+    pub score: i64,
+    /// Pauses excluded
+    pub played: Duration,
+    /// Best score before the run, None if it is the first one
+    pub previous_best: Option<i64>,
+    /// Seed the game was made from, to play the same game again
+    pub seed: Option<u64>,
+    /// Lines from the game, see `Game::summary`
+    pub details: Vec<String>,
+    /// Grade, scores chart, saving errors and such
+    pub notes: Vec<String>,
+}
+
+impl Summary {
+    pub fn is_new_best(&self) -> bool {
+        self.previous_best.is_some_and(|best| self.score > best)
+    }
+}
+
+/// Show the summary until the player picks what's next in its menu:
+/// Up/Down and Enter, `r` for the replay, Esc goes back to the menu.
+/// Play again is offered if `can_restart`, the replay if there is one.
+pub fn show(
+    out: &mut std::io::Stdout,
+    rx: &std::sync::mpsc::Receiver<crossterm::event::Event>,
+    summary: &Summary,
+    replay: &crate::replay::ReplayBuffer,
+    can_restart: bool,
+) -> crossterm::Result<Next> {
+    use crossterm::event::KeyCode;
+    use crossterm::style::Stylize;
+    use crossterm::terminal;
+
+    let palette = crate::theme::palette();
+    let score_line = format(Text::ScoreLine, &[&summary.score]);
+    let best_line = if summary.is_new_best() {
+        let delta = format_delta(summary.score - summary.previous_best.unwrap_or_default());
+        format(Text::NewBest, &[&delta])
+    } else if let Some(best) = summary.previous_best {
+        format(Text::PersonalBest, &[&best])
+    } else {
+        String::new()
+    };
+    let played = crate::lifetime::format_time(summary.played.as_secs_f64());
+    let mut lines = vec![format(Text::Time, &[&played])];
+    lines.extend(summary.seed.map(|seed| format(Text::Seed, &[&seed])));
+    lines.push(String::new());
+    lines.extend(summary.details.iter().cloned());
+    if !summary.details.is_empty() {
+        lines.push(String::new());
+    }
+    lines.extend(summary.notes.iter().cloned());
+    let items: Vec<Item> = [
+        (Item::PlayAgain, can_restart),
+        (Item::Replay, !replay.is_empty()),
+        (Item::BackToMenu, true),
+        (Item::Quit, true),
+    ]
+    .into_iter()
+    .filter_map(|(item, is_shown)| is_shown.then_some(item))
+    .collect();
+    let item_lines: Vec<String> = items
+        .iter()
+        .map(|item| format!(" {} ", item.name()))
+        .collect();
+
+    let text_width = [&score_line, &best_line]
+        .into_iter()
+        .chain(&lines)
+        .chain(&item_lines)
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let title = format(Text::GameOverTitle, &[&summary.game_name]);
+    let mut frame = FrameBuffer::from_terminal()?;
+    let mut selected = 0;
+    let mut confetti =
+        (summary.is_new_best() && !crate::config::get().reduced_effects).then(|| {
+            crate::effects::Confetti::new(frame.width(), frame.height(), &mut rand::thread_rng())
+        });
+    // Keys pressed while playing don't pick anything
+    while rx.try_recv().is_ok() {}
+
+    loop {
+        let (width, height) = terminal::size()?;
+        frame.clear(width, height);
+        // Score and best score lines, a gap, the rest, a gap and the menu
+        let area =
+            Rect::of_frame(&frame).centered(text_width + 4, (lines.len() + items.len()) as u16 + 6);
+        if let Some(confetti) = &confetti {
+            confetti.draw(&mut frame);
+        }
+        let inside = Frame::new()
+            .title(&title)
+            .draw(&mut frame, Layer::Hud, area);
+        // Blank inside, the confetti falls behind the box
+        for y in inside.y..inside.bottom() {
+            frame.print(Layer::Hud, inside.x, y, &" ".repeat(inside.width as usize));
+        }
+        let inner = inside.shrink(1, 0, 1, 0);
+        frame.print_styled(Layer::Hud, inner.x, inner.y, score_line.as_str().bold());
+        frame.print_styled(
+            Layer::Hud,
+            inner.x,
+            inner.y + 1,
+            best_line.as_str().with(palette.winning),
+        );
+        for (y, line) in lines.iter().enumerate() {
+            frame.print(Layer::Hud, inner.x, inner.y + 3 + y as u16, line);
+        }
+        let menu_y = inner.y + 4 + lines.len() as u16;
+        for (ind, line) in item_lines.iter().enumerate() {
+            let y = menu_y + ind as u16;
+            if ind == selected {
+                frame.print_styled(Layer::Hud, inner.x, y, line.as_str().reverse());
+            } else {
+                frame.print(Layer::Hud, inner.x, y, line);
+            }
+        }
+        frame.flush(out)?;
+
+        // Any key stops the confetti without picking anything,
+        // but not the release of the key that ended the run
+        if let Some(effect) = &mut confetti {
+            use crossterm::event::{Event, KeyEventKind};
+
+            let frame_time = crate::config::get().between_frames();
+            let is_skipped = matches!(
+                rx.recv_timeout(frame_time),
+                Ok(Event::Key(key)) if key.kind != KeyEventKind::Release
+            );
+            if crate::term::is_quit_requested() {
+                return Ok(Next::Quit);
+            }
+            effect.advance(frame_time);
+            if is_skipped || effect.is_finished() {
+                confetti = None;
+            }
+            continue;
+        }
+
+        let key = crate::input::recv_key(rx);
+        if crate::term::is_quit_requested() {
+            return Ok(Next::Quit);
+        }
+        let picked = match (crate::config::get().keys.action(&key), key.code) {
+            (Some(Action::MoveUp), _) => {
+                selected = (selected + items.len() - 1) % items.len();
+                None
+            }
+            (Some(Action::MoveDown), _) => {
+                selected = (selected + 1) % items.len();
+                None
+            }
+            (Some(Action::Quit), _) => Some(Item::BackToMenu),
+            (_, KeyCode::Enter) => Some(items[selected]),
+            (_, code) if code == crate::replay::REPLAY_BUTTON => {
+                items.contains(&Item::Replay).then_some(Item::Replay)
+            }
+            _ => None,
+        };
+        match picked {
+            Some(Item::PlayAgain) => return Ok(Next::Restart),
+            Some(Item::Replay) => replay.play(out, rx)?,
+            Some(Item::BackToMenu) => return Ok(Next::Menu),
+            Some(Item::Quit) => return Ok(Next::Quit),
+            None => {}
+        }
+    }
+}
+
+/// Score difference with its sign and commas between the thousands, like `+2,300`.
+fn format_delta(delta: i64) -> String {
+    let digits = delta.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (ind, digit) in digits.chars().enumerate() {
+        if ind > 0 && (digits.len() - ind).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!("{}{}", sign, grouped)
+}
@@ -0,0 +1,121 @@
+//! Opt-in log of finished runs for players to analyze themselves,
+//! one JSON object per line. It is only written to the local file.
+//!
+//! Turned on with `enabled = true` in the `[telemetry]` section of the config,
+//! written to its `path` or to `<data dir>/cl_games/telemetry.jsonl`.
+//!
+//! ```text
+//! cl_games stats export [<file>]
+//! ```
+//!
+//! writes the runs as one JSON array to the file or to stdout.
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const COMMAND: &str = "stats";
+pub const FILE_NAME: &str = "telemetry.jsonl";
+
+/// Settings the run was played with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSettings {
+    pub frame_rate: u32,
+    pub chaos: bool,
+    /// Level at the end of the run, None without dynamic difficulty
+    pub difficulty: Option<f32>,
+    pub theme: crate::theme::Name,
+}
+
+/// One finished run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Seconds since the Unix epoch at the end of the run
+    pub finished_at: u64,
+    pub game: String,
+    pub score: i64,
+    /// Played time in seconds, pauses excluded
+    pub duration: f32,
+    pub grade: Option<crate::stats::Grade>,
+    /// Week of the challenge, like "2026-W42"
+    pub challenge: Option<String>,
+    pub settings: RunSettings,
+}
+
+impl RunRecord {
+    /// Record of a run finished now.
+    pub fn new(game: &str, score: i64, duration: Duration, settings: RunSettings) -> Self {
+        Self {
+            finished_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            game: game.to_owned(),
+            score,
+            duration: duration.as_secs_f32(),
+            grade: None,
+            challenge: None,
+            settings,
+        }
+    }
+}
+
+/// Log file: the configured one or `<data dir>/cl_games/telemetry.jsonl`.
+pub fn path() -> Option<PathBuf> {
+    crate::config::get()
+        .telemetry
+        .path
+        .clone()
+        .or_else(|| crate::config::data_dir().map(|dir| dir.join(FILE_NAME)))
+}
+
+/// Add the run to the log if telemetry is on.
+pub fn record(run: &RunRecord) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+
+    if !crate::config::get().telemetry.enabled {
+        return Ok(());
+    }
+    let path = path().ok_or_else(|| Error::new(ErrorKind::NotFound, "no data directory"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let line = serde_json::to_string(run).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Logged runs, oldest first. Broken lines are skipped.
+pub fn load() -> Vec<RunRecord> {
+    path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| {
+            text.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Run `stats` subcommand with the arguments following it.
+pub fn run_command(args: &[String]) -> Result<(), String> {
+    match args {
+        [command] if command == "export" => {
+            export(&mut std::io::stdout()).map_err(|err| err.to_string())
+        }
+        [command, file] if command == "export" => {
+            let mut file =
+                std::fs::File::create(file).map_err(|err| format!("{}: {}", file, err))?;
+            export(&mut file).map_err(|err| err.to_string())
+        }
+        _ => Err(format!("usage: cl_games {} export [<file>]", COMMAND)),
+    }
+}
+
+fn export(out: &mut impl Write) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, &load())?;
+    writeln!(out)
+}
@@ -0,0 +1,71 @@
+//! Terminal state of the whole program.
+//!
+//! Raw mode makes keys reach the games right away and without echo;
+//! the alternate screen keeps game frames out of the scrollback.
+use crossterm::{cursor, event, execute, terminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by Ctrl+C: everything is left the quickest way that loses nothing.
+static QUIT_REQUESTED: AtomicBool = AtomicBool::new(false);
+/// Set if the terminal sends key releases (kitty keyboard protocol).
+static KEY_RELEASES: AtomicBool = AtomicBool::new(false);
+
+pub fn request_quit() {
+    QUIT_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+pub fn is_quit_requested() -> bool {
+    QUIT_REQUESTED.load(Ordering::SeqCst)
+}
+
+pub fn reports_key_releases() -> bool {
+    KEY_RELEASES.load(Ordering::SeqCst)
+}
+
+/// Must be called before anything reads events: asking the terminal
+/// about the keyboard protocol reads the answer from them.
+pub fn enter(out: &mut std::io::Stdout) -> crossterm::Result<()> {
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+        execute!(
+            out,
+            event::PushKeyboardEnhancementFlags(
+                event::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | event::KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            )
+        )?;
+        KEY_RELEASES.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+/// Mouse events are sent to the program until `leave`.
+pub fn capture_mouse(out: &mut std::io::Stdout) -> crossterm::Result<()> {
+    execute!(out, event::EnableMouseCapture)
+}
+
+/// Give the terminal back as it was before `enter`.
+pub fn leave(out: &mut std::io::Stdout) -> crossterm::Result<()> {
+    if KEY_RELEASES.swap(false, Ordering::SeqCst) {
+        execute!(out, event::PopKeyboardEnhancementFlags)?;
+    }
+    execute!(
+        out,
+        event::DisableMouseCapture,
+        cursor::Show,
+        terminal::LeaveAlternateScreen
+    )?;
+    terminal::disable_raw_mode()
+}
+
+/// Restore the terminal before the panic message is printed,
+/// otherwise it is garbled by raw mode and lost with the alternate screen.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        _ = leave(&mut std::io::stdout());
+        default_hook(info);
+    }));
+}
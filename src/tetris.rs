@@ -1,32 +1,152 @@
-use crate::game::{Game, Score, UpdateEvent, EXIT_BUTTON};
-use crate::point::{GameBasis, Point, ScreenBasis};
-use colored::Colorize;
-use crossterm::style::Stylize;
+use crate::config;
+use crate::effects::ScoreEvent;
+use crate::game::{Game, Score, UpdateEvent};
+use crate::input::{Action, FrameInput};
+use crate::point::{Cell, GameBasis, Point, ScreenBasis, Transform};
+use crate::profiling::profile_scope;
+use crate::render::{FrameBuffer, Layer};
+use crate::script;
+use crate::sound::Effect;
+use crossterm::style::{StyledContent, Stylize};
 use once_cell::sync::Lazy;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use strum::EnumCount;
 use strum_macros::{EnumCount, FromRepr};
 
 const HEIGHT: usize = 20;
 const WIDTH: usize = 10;
-const TO_DESCEND_SLOW: Duration = Duration::from_millis(200);
-const TO_DESCEND_FAST: Duration = Duration::from_millis(50);
+pub const TO_DESCEND_SLOW: Duration = Duration::from_millis(200);
+pub const TO_DESCEND_FAST: Duration = Duration::from_millis(50);
 const MINIMUM_USER_INPUT_DISTANCE: Duration = Duration::from_millis(125);
 const INIT_FIGURE_POS: Point<GameBasis> = Point::new(3.0, 0.0);
 const LOSE_LINE: f32 = 1.0;
 const BORDER_WIDTH: usize = 2; // in symbols!
 const BORDER_HEIGHT: usize = 1;
+/// Rows filled at the start by the `tetris.garbage` mutator
+pub const GARBAGE_ROWS: usize = 4;
 
 mod next_fig_frame {
-    pub const FROM_BOARD_INDENT: usize = 2;
-    pub const INDENT: usize = super::BORDER_WIDTH * 2 + super::WIDTH * 2 + FROM_BOARD_INDENT;
-    //                                                               ^^^ cells have 2-symbols width
-    pub const WIDTH: usize = 5;
-    pub const HEIGHT: usize = 5;
-    pub const INDENT_UP: usize = 2;
+    pub const FROM_BOARD_INDENT: u16 = 2;
+    /// Inside the border, in cells
+    pub const WIDTH: u16 = 5;
+    pub const HEIGHT: u16 = 4;
 }
 
+type Cells = [[Option<Color>; WIDTH]; HEIGHT];
+/// Row mask with every cell taken
+const FULL_ROW: u16 = (1 << WIDTH) - 1;
+
+/// Placed cells: the colors for drawing and a bit mask per row for the checks.
+/// Saved as the colors only, the masks are made from them.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "Cells", into = "Cells")]
+pub struct Board {
+    cells: Cells,
+    /// Bit `x` is set if the cell in column `x` is taken
+    rows: [u16; HEIGHT],
+}
+
+impl Board {
+    pub const WIDTH: usize = WIDTH;
+    pub const HEIGHT: usize = HEIGHT;
+
+    pub fn new() -> Self {
+        Self {
+            cells: [[None; WIDTH]; HEIGHT],
+            rows: [0; HEIGHT],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Color> {
+        self.cells[y][x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: Option<Color>) {
+        self.cells[y][x] = cell;
+        match cell {
+            Some(_) => self.rows[y] |= 1 << x,
+            None => self.rows[y] &= !(1 << x),
+        }
+    }
+
+    pub fn is_taken(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & (1 << x) != 0
+    }
+
+    /// Mask of the taken cells of the row.
+    pub fn row_mask(&self, y: usize) -> u16 {
+        self.rows[y]
+    }
+
+    pub fn is_row_full(&self, y: usize) -> bool {
+        self.rows[y] == FULL_ROW
+    }
+
+    /// Cells on the board and free.
+    pub fn fits(&self, points: &[Point<GameBasis>]) -> bool {
+        points.iter().all(|point| {
+            let (x, y) = (point.x.round(), point.y.round());
+            (0.0..WIDTH as f32).contains(&x)
+                && (0.0..HEIGHT as f32).contains(&y)
+                && !self.is_taken(x as usize, y as usize)
+        })
+    }
+
+    /// Remove `count` rows ending with `bottom`, the rows above fall in their place.
+    pub fn clear_rows(&mut self, bottom: usize, count: usize) {
+        let top = bottom + 1 - count;
+        self.cells.copy_within(0..top, count);
+        self.rows.copy_within(0..top, count);
+        self.cells[..count].fill([None; WIDTH]);
+        self.rows[..count].fill(0);
+    }
+
+    /// Cells moved a column aside, the ones leaving the board come in on the other side.
+    pub fn shifted(&self, to_right: bool) -> Self {
+        let mut cells = self.cells;
+        for row in cells.iter_mut() {
+            if to_right {
+                row.rotate_right(1);
+            } else {
+                row.rotate_left(1);
+            }
+        }
+        Self::from(cells)
+    }
+
+    /// Rows from the top, a color per taken cell.
+    pub fn rows(&self) -> impl Iterator<Item = &[Option<Color>; WIDTH]> {
+        self.cells.iter()
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Cells> for Board {
+    fn from(cells: Cells) -> Self {
+        let mut board = Self::new();
+        for (y, row) in cells.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                board.set(x, y, cell);
+            }
+        }
+        board
+    }
+}
+
+impl From<Board> for Cells {
+    fn from(board: Board) -> Self {
+        board.cells
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
 enum UserInput {
     Left,
     Right,
@@ -34,7 +154,7 @@ enum UserInput {
     None,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum Color {
     Cyan,
     Blue,
@@ -45,13 +165,13 @@ pub enum Color {
     Red,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Figure {
     pub figure_type: FigureType,
     pub rotation: f32, // in radians
 }
 
-#[derive(Clone, Copy, FromRepr, EnumCount, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, FromRepr, EnumCount, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FigureType {
     Square,
     Line,
@@ -186,21 +306,15 @@ impl Figure {
         position: Point<GameBasis>,
     ) -> [Point<GameBasis>; 4] {
         let (points, pivot) = self.figure_type.get_points_and_pivot();
-        let mut points = *points;
-        for point in points.iter_mut() {
-            let x = point.x - pivot.x;
-            let y = point.y - pivot.y;
-            let x_new = x * rotation.cos() - y * rotation.sin();
-            let y_new = x * rotation.sin() + y * rotation.cos();
-            point.x = x_new + pivot.x + position.x;
-            point.y = y_new + pivot.y + position.y;
-        }
-        points
+        let transform =
+            Transform::rotation_around(rotation, *pivot).then(&Transform::translation(position));
+        points.map(|point| transform.apply(point))
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TetrisGame {
-    pub board: [[Option<Color>; WIDTH]; HEIGHT],
+    pub board: Board,
     pub current_figure: Figure,
     pub current_figure_position: Point<GameBasis>,
     pub next_figure: Figure,
@@ -208,61 +322,111 @@ pub struct TetrisGame {
     pub to_descend: Duration,
     pub from_prev_descend: Duration,
     pub is_tetris_was_last: bool,
+    /// Cells of placed figures
+    pub heat_points: Vec<Point<GameBasis>>,
+    #[serde(default)]
+    pub figures_placed: usize,
+    #[serde(default)]
+    pub lines_cleared: usize,
+    #[serde(skip)]
+    pub score_events: Vec<ScoreEvent>,
+    #[serde(skip)]
+    pub sound_effects: Vec<Effect>,
 
     last_user_input: UserInput,
     from_last_user_input: Duration,
+    #[serde(skip, default = "Tweaks::load")]
+    tweaks: Tweaks,
+    /// Not saved, loaded games get a new one
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng,
+}
+
+/// Values set by scripts, looked up once per game.
+#[derive(Clone, Copy)]
+struct Tweaks {
+    descend_slow: Option<f32>,
+    descend_fast: Option<f32>,
+}
+
+impl Tweaks {
+    fn load() -> Self {
+        Self {
+            descend_slow: script::tweak("tetris.descend_slow"),
+            descend_fast: script::tweak("tetris.descend_fast"),
+        }
+    }
+
+    fn descend_slow(&self) -> Duration {
+        script::seconds_or(self.descend_slow, config::get().tetris.descend_slow())
+    }
+
+    fn descend_fast(&self) -> Duration {
+        script::seconds_or(self.descend_fast, config::get().tetris.descend_fast())
+    }
 }
 
 impl TetrisGame {
-    pub fn new() -> Self {
+    /// Figures are picked with the given random generator.
+    pub fn new(mut rng: StdRng) -> Self {
+        let tweaks = Tweaks::load();
         Self {
-            board: [[None; WIDTH]; HEIGHT],
-            current_figure: Self::gen_figure(),
+            board: Board::new(),
+            current_figure: Self::gen_figure(&mut rng),
             current_figure_position: INIT_FIGURE_POS,
-            next_figure: Self::gen_figure(),
+            next_figure: Self::gen_figure(&mut rng),
             score: 0,
-            to_descend: TO_DESCEND_SLOW,
+            to_descend: tweaks.descend_slow(),
             from_prev_descend: Duration::new(0, 0),
             is_tetris_was_last: false,
+            heat_points: vec![],
+            figures_placed: 0,
+            lines_cleared: 0,
+            score_events: vec![],
+            sound_effects: vec![],
 
             last_user_input: UserInput::None,
             from_last_user_input: Duration::new(0, 0),
+            tweaks,
+            rng,
+        }
+    }
+
+    /// Fill the bottom rows but a random cell in each.
+    pub fn with_garbage(mut self, rows: usize) -> Self {
+        for y in HEIGHT - rows.min(HEIGHT)..HEIGHT {
+            let hole = self.rng.gen_range(0..WIDTH);
+            for x in (0..WIDTH).filter(|&x| x != hole) {
+                self.board.set(x, y, Some(Color::Red));
+            }
         }
+        self
     }
 
-    pub fn gen_figure() -> Figure {
+    pub fn gen_figure(rng: &mut impl Rng) -> Figure {
         Figure::new(
-            FigureType::from_repr(rand::thread_rng().gen_range(0..FigureType::COUNT))
+            FigureType::from_repr(rng.gen_range(0..FigureType::COUNT))
                 .unwrap_or(FigureType::Square),
             0.0,
         )
     }
-
-    fn is_line_ready(&self, row_num: usize) -> bool {
-        self.board[row_num].iter().all(|&c| c.is_some())
-    }
 }
 
 impl Default for TetrisGame {
     fn default() -> Self {
-        Self::new()
+        Self::new(StdRng::from_entropy())
     }
 }
 
 impl Game for TetrisGame {
-    fn update(
-        &mut self,
-        input: &Option<crossterm::event::KeyEvent>,
-        delta_time: &std::time::Duration,
-    ) -> UpdateEvent {
+    fn update(&mut self, input: &FrameInput, delta_time: &std::time::Duration) -> UpdateEvent {
+        profile_scope!("tetris.update");
         self.from_prev_descend += *delta_time;
         self.from_last_user_input += *delta_time;
 
         // quit
-        if let Some(key) = input {
-            if key.code == EXIT_BUTTON {
-                return UpdateEvent::GameOver;
-            }
+        if input.action() == Some(Action::Quit) {
+            return UpdateEvent::GameOver;
         }
 
         // Game over handle (TODO rework all update function, because it looks strange)
@@ -274,74 +438,86 @@ impl Game for TetrisGame {
                     self.current_figure_position,
                 )
                 .iter()
-                .any(|p| self.board[p.y.round() as usize][p.x.round() as usize].is_some())
+                .any(|p| {
+                    self.board
+                        .is_taken(p.x.round() as usize, p.y.round() as usize)
+                })
             {
+                tracing::debug!(score = self.score, "new figure overlaps the board");
                 return UpdateEvent::GameOver;
             }
         }
 
         // Input handling
         let (mut new_position, new_rotation) = {
-            use crossterm::event::KeyCode;
-
             let mut new_rotation = self.current_figure.rotation;
             let mut new_position = self.current_figure_position;
 
-            if let Some(input) = input {
-                // Rotate and move
-                if self.from_last_user_input > MINIMUM_USER_INPUT_DISTANCE {
-                    match input.code {
-                        KeyCode::Left => {
-                            new_position.x -= 1.0;
-                            self.last_user_input = UserInput::Left;
+            let presses = &input.presses;
+            // Rotate and move, every tap of the frame counts
+            if !presses.is_empty() && self.from_last_user_input > MINIMUM_USER_INPUT_DISTANCE {
+                let mut is_first_tap = true;
+                for (action, count) in presses.iter() {
+                    for _ in 0..count {
+                        let (mut position, mut rotation) = (new_position, new_rotation);
+                        match action {
+                            Action::MoveLeft => {
+                                position.x -= 1.0;
+                                self.last_user_input = UserInput::Left;
+                            }
+                            Action::MoveRight => {
+                                position.x += 1.0;
+                                self.last_user_input = UserInput::Right;
+                            }
+                            Action::MoveUp | Action::Rotate => {
+                                rotation += std::f32::consts::PI / 2.0;
+                                self.last_user_input = UserInput::Rotate;
+                            }
+                            _ => break,
                         }
-                        KeyCode::Right => {
-                            new_position.x += 1.0;
-                            self.last_user_input = UserInput::Right;
-                        }
-                        KeyCode::Up => {
-                            new_rotation += std::f32::consts::PI / 2.0;
-                            self.last_user_input = UserInput::Rotate;
+                        // The first tap is checked with the descend below,
+                        // later ones stop where the figure doesn't fit
+                        if !is_first_tap
+                            && !self.board.fits(
+                                &self
+                                    .current_figure
+                                    .applied_rotation_and_position(rotation, position),
+                            )
+                        {
+                            break;
                         }
-                        _ => {}
+                        is_first_tap = false;
+                        new_position = position;
+                        new_rotation = rotation;
                     }
-                    self.from_last_user_input = Duration::new(0, 0);
-                }
-                // Descend faster
-                if input.code == KeyCode::Down {
-                    self.to_descend = TO_DESCEND_FAST;
-                } else {
-                    self.to_descend = TO_DESCEND_SLOW;
                 }
+                self.from_last_user_input = Duration::new(0, 0);
             }
+            // Descend faster while Down is held
+            self.to_descend =
+                if presses.count(Action::MoveDown) > 0 || input.is_held(Action::MoveDown) {
+                    self.tweaks.descend_fast()
+                } else {
+                    self.tweaks.descend_slow()
+                };
 
             (new_position, new_rotation)
         };
 
         // Apply descend (modifies new_position)
+        // The rest carries over so the speed doesn't depend on the frame rate
         if self.from_prev_descend > self.to_descend {
             new_position.y += 1.0;
-            self.from_prev_descend = Duration::new(0, 0);
+            self.from_prev_descend =
+                (self.from_prev_descend - self.to_descend).min(self.to_descend);
         }
 
         // Check if the figure can be moved to the new position
-        let can_move = {
-            let mut can_move = true;
-            for point in self
+        let can_move = self.board.fits(
+            &self
                 .current_figure
-                .applied_rotation_and_position(new_rotation, new_position)
-                .iter()
-            {
-                if point.x.round() < 0.0
-                    || point.x.round() >= WIDTH as f32
-                    || point.y.round() >= HEIGHT as f32
-                    || self.board[point.y.round() as usize][point.x.round() as usize].is_some()
-                {
-                    can_move = false;
-                }
-            }
-            can_move
-        };
+                .applied_rotation_and_position(new_rotation, new_position),
+        );
 
         // Move the figure if possible
         if can_move {
@@ -359,7 +535,9 @@ impl Game for TetrisGame {
             .iter()
             .any(|p| {
                 p.y.round() as usize >= HEIGHT - 1
-                    || self.board[p.y.round() as usize + 1][p.x.round() as usize].is_some()
+                    || self
+                        .board
+                        .is_taken(p.x.round() as usize, p.y.round() as usize + 1)
             }) {
             for p in self
                 .current_figure
@@ -369,15 +547,20 @@ impl Game for TetrisGame {
                 )
                 .iter()
             {
-                self.board[p.y.round() as usize][p.x.round() as usize] =
-                    Some(self.current_figure.figure_type.get_color());
+                self.board.set(
+                    p.x.round() as usize,
+                    p.y.round() as usize,
+                    Some(self.current_figure.figure_type.get_color()),
+                );
+                self.heat_points.push(*p);
             }
 
+            self.figures_placed += 1;
             self.current_figure = self.next_figure;
             self.current_figure_position = INIT_FIGURE_POS;
-            self.next_figure = Self::gen_figure();
+            self.next_figure = Self::gen_figure(&mut self.rng);
             self.from_prev_descend = Duration::new(0, 0);
-            self.to_descend = TO_DESCEND_SLOW;
+            self.to_descend = self.tweaks.descend_slow();
 
             true
         } else {
@@ -386,21 +569,21 @@ impl Game for TetrisGame {
 
         // Check for cleared lines
         {
+            profile_scope!("tetris.lines");
             let mut curr_base_line = HEIGHT - 1_usize;
 
             while curr_base_line > LOSE_LINE.round() as usize {
-                let mut lines_in_row = 0;
-
-                if !self.is_line_ready(curr_base_line) {
+                if !self.board.is_row_full(curr_base_line) {
                     curr_base_line -= 1;
                     continue;
                 }
 
-                while self.is_line_ready(curr_base_line - lines_in_row) {
-                    lines_in_row += 1;
-                }
+                let lines_in_row = (0..=curr_base_line)
+                    .rev()
+                    .take_while(|&row| self.board.is_row_full(row))
+                    .count();
 
-                self.score += if lines_in_row >= 4 {
+                let points = if lines_in_row >= 4 {
                     if self.is_tetris_was_last {
                         300 * lines_in_row
                     } else {
@@ -411,13 +594,20 @@ impl Game for TetrisGame {
                     self.is_tetris_was_last = false;
                     100 * lines_in_row
                 };
-
-                for col in 0..WIDTH {
-                    for row in (0..=curr_base_line - lines_in_row).rev() {
-                        self.board[row + lines_in_row][col] = self.board[row][col];
-                    }
-                }
-
+                self.score += points;
+                self.lines_cleared += lines_in_row;
+                tracing::debug!(lines = lines_in_row, points, "lines cleared");
+                self.sound_effects.push(Effect::LineCleared);
+                // Over the middle of the cleared lines
+                self.score_events.push(ScoreEvent {
+                    points: points as i64,
+                    x: (BORDER_WIDTH + WIDTH) as u16,
+                    y: (curr_base_line + 1 - lines_in_row / 2) as u16,
+                });
+
+                self.board.clear_rows(curr_base_line, lines_in_row);
+
+                // The row fallen in was above the full ones, so it is not full
                 curr_base_line -= 1;
             }
         }
@@ -433,53 +623,64 @@ impl Game for TetrisGame {
                 .iter()
                 .all(|p| p.y < LOSE_LINE)
         {
-            print!("{:?}", self.current_figure_position);
-            print!(
-                "{:?}",
-                self.current_figure.applied_rotation_and_position(
-                    self.current_figure.rotation,
-                    self.current_figure_position
-                )
-            );
+            tracing::debug!(score = self.score, "figure stuck over the lose line");
             UpdateEvent::GameOver
         } else {
             UpdateEvent::GameContinue
         }
     }
 
-    fn draw(
-        &self,
-        out: &mut std::io::Stdout,
-        _delta_time: &std::time::Duration,
-    ) -> crossterm::Result<()> {
-        use crossterm::{cursor::MoveTo, execute};
-        use std::io::Write;
+    fn draw(&self, frame: &mut FrameBuffer, _delta_time: &std::time::Duration) {
+        profile_scope!("tetris.draw");
+        use crate::layout::{Direction, Rect, Size};
+        use crate::ui::{Frame, Label};
+
+        // Screen regions
+        let [board_column, _, side] = Rect::of_frame(frame).split(
+            Direction::Horizontal,
+            [
+                Size::Fixed((BORDER_WIDTH * 2 + WIDTH * 2) as u16),
+                Size::Fixed(next_fig_frame::FROM_BOARD_INDENT),
+                Size::Flex(1),
+            ],
+        );
+        let [board, _] = board_column.split(
+            Direction::Vertical,
+            [Size::Fixed((HEIGHT + BORDER_HEIGHT) as u16), Size::Flex(1)],
+        );
+        let [_, next_title, next_frame, _] = side.split(
+            Direction::Vertical,
+            [
+                Size::Fixed(1),
+                Size::Fixed(1),
+                Size::Fixed(next_fig_frame::HEIGHT + BORDER_HEIGHT as u16 * 2),
+                Size::Flex(1),
+            ],
+        );
+        let [next_frame, _] = next_frame.split(
+            Direction::Horizontal,
+            [
+                Size::Fixed(next_fig_frame::WIDTH * 2 + BORDER_WIDTH as u16 * 2),
+                Size::Flex(1),
+            ],
+        );
 
-        // Draw the board
-        {
-            // Draw cells
-            {
-                for (y, row) in self.board.iter().enumerate() {
-                    execute!(out, MoveTo(0, y as u16))?;
-                    write!(out, " ║")?;
-                    for &cell in row.iter() {
-                        match cell {
-                            None => write!(out, "  ")?,
-                            Some(col) => draw_with_color(out, "██", col)?,
-                        }
-                    }
-                    write!(out, "║ ")?;
+        // Draw the board, it is open at the top
+        let cells = Frame::new()
+            .padded()
+            .open_top()
+            .draw(frame, Layer::Game, board);
+        for (y, row) in self.board.rows().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if let Some(col) = cell {
+                    frame.print_styled(
+                        Layer::Game,
+                        cells.x + x as u16 * 2,
+                        cells.y + y as u16,
+                        figure_cell(col),
+                    );
                 }
             }
-            // Draw border
-            {
-                execute!(out, MoveTo(0, HEIGHT as u16))?;
-                write!(out, " ╚")?;
-                for _ in 0..WIDTH {
-                    write!(out, "══")?;
-                }
-                write!(out, "╝ ")?;
-            }
         }
 
         // Draw the current figure
@@ -492,147 +693,49 @@ impl Game for TetrisGame {
                 )
                 .iter()
             {
-                execute!(
-                    out,
-                    MoveTo(
-                        BORDER_WIDTH as u16 + point.x.round() as u16 * 2,
-                        point.y.round() as u16
-                    )
-                )?;
-                draw_with_color(out, "██", self.current_figure.figure_type.get_color())?;
-            }
-        }
-
-        // Draw score
-        {
-            fn digits_num(num: usize) -> u16 {
-                if num == 0 {
-                    1
-                } else {
-                    f32::floor(f32::log10(num as f32) + 1.0) as u16
+                // Cells of the board, offset by where the board is drawn
+                if let Some(cell) = board_cell(point) {
+                    frame.print_styled(
+                        Layer::Game,
+                        cells.x + cell.x,
+                        cells.y + cell.y,
+                        figure_cell(self.current_figure.figure_type.get_color()),
+                    );
                 }
             }
-
-            let score_hint = "Score: ";
-            execute!(
-                out,
-                MoveTo(
-                    (WIDTH as u16 * 2 + BORDER_WIDTH as u16 * 2
-                        - score_hint.len() as u16
-                        - digits_num(self.score))
-                        / 2,
-                    HEIGHT as u16 + 2
-                )
-            )?;
-
-            let score = format!("{}", self.score);
-            write!(
-                out,
-                "Score: {}",
-                if self.score < 1_000 {
-                    score.white()
-                } else if self.score < 10_000 {
-                    score.green()
-                } else if self.score < 50_000 {
-                    score.yellow()
-                } else {
-                    score.red()
-                }
-            )?;
         }
 
         // Draw next figure
         {
             // Title
-            {
-                execute!(
-                    out,
-                    MoveTo(
-                        next_fig_frame::INDENT as u16 + 1,
-                        next_fig_frame::INDENT_UP as u16 - 1
-                    )
-                )?;
-                write!(out, "Next figure:")?;
-            }
+            Label::new(crate::i18n::tr(crate::i18n::Text::NextFigure)).draw(
+                frame,
+                Layer::Hud,
+                next_title.shrink(1, 0, 0, 0),
+            );
             // Draw border
-            {
-                // Up
-                {
-                    execute!(
-                        out,
-                        MoveTo(
-                            next_fig_frame::INDENT as u16,
-                            next_fig_frame::INDENT_UP as u16
-                        )
-                    )?;
-                    write!(out, " ╔")?;
-                    for _ in 0..next_fig_frame::WIDTH {
-                        write!(out, "══")?;
-                    }
-                    write!(out, "╗ ")?;
-                }
-
-                // Left and right
-                {
-                    for row in 0..next_fig_frame::HEIGHT {
-                        execute!(
-                            out,
-                            MoveTo(
-                                next_fig_frame::INDENT as u16,
-                                (next_fig_frame::INDENT_UP + BORDER_HEIGHT + row) as u16
-                            )
-                        )?;
-                        write!(out, " ║")?;
-                        execute!(
-                            out,
-                            MoveTo(
-                                (next_fig_frame::INDENT + BORDER_WIDTH + next_fig_frame::WIDTH * 2)
-                                    as u16,
-                                (next_fig_frame::INDENT_UP + BORDER_HEIGHT + row) as u16
-                            )
-                        )?;
-                        write!(out, "║ ")?;
-                    }
-                }
-
-                // Down
-                {
-                    execute!(
-                        out,
-                        MoveTo(
-                            next_fig_frame::INDENT as u16,
-                            (next_fig_frame::INDENT_UP + next_fig_frame::HEIGHT) as u16
-                        )
-                    )?;
-                    write!(out, " ╚")?;
-                    for _ in 0..next_fig_frame::WIDTH {
-                        write!(out, "══")?;
-                    }
-                    write!(out, "╝ ")?;
-                }
-            }
+            let next_cells = Frame::new().padded().draw(frame, Layer::Hud, next_frame);
             // Draw figure
             {
-                for point in self
+                // Rotated figures fit around the second cell of the second row
+                for cell in self
                     .next_figure
                     .applied_rotation_and_position(
                         std::f32::consts::PI / 2.0,
-                        Point::new(
-                            (next_fig_frame::INDENT + BORDER_WIDTH + next_fig_frame::WIDTH / 2)
-                                as f32
-                                / 2.0,
-                            (next_fig_frame::INDENT_UP + next_fig_frame::HEIGHT / 2) as f32,
-                        ),
+                        Point::new((next_cells.x + 2) as f32 / 2.0, (next_cells.y + 1) as f32),
                     )
-                    .map(Point::<ScreenBasis>::from)
+                    .iter()
+                    .filter_map(board_cell)
                 {
-                    execute!(out, MoveTo(point.x.round() as u16, point.y.round() as u16))?;
-                    draw_with_color(out, "██", self.next_figure.figure_type.get_color())?;
+                    frame.print_styled(
+                        Layer::Hud,
+                        cell.x,
+                        cell.y,
+                        figure_cell(self.next_figure.figure_type.get_color()),
+                    );
                 }
             }
         }
-
-        execute!(out, MoveTo(0, 0))
     }
 
     fn get_score(&self) -> Score {
@@ -640,18 +743,149 @@ impl Game for TetrisGame {
             value: self.score as i64,
         }
     }
-}
 
-pub fn draw_with_color(out: &mut std::io::Stdout, s: &str, col: Color) -> crossterm::Result<()> {
-    use std::io::Write;
+    fn hud(&self, hud: &mut crate::hud::Hud) {
+        let palette = crate::theme::palette();
+        let tier = if self.score < 1_000 {
+            palette.score_tiers[0]
+        } else if self.score < 10_000 {
+            palette.score_tiers[1]
+        } else if self.score < 50_000 {
+            palette.score_tiers[2]
+        } else {
+            palette.score_tiers[3]
+        };
+        hud.colored_score(self.score as i64, tier);
+    }
+
+    fn take_heat_points(&mut self) -> Vec<Point<GameBasis>> {
+        std::mem::take(&mut self.heat_points)
+    }
+
+    fn take_score_events(&mut self) -> Vec<ScoreEvent> {
+        std::mem::take(&mut self.score_events)
+    }
 
-    match col {
-        Color::Cyan => write!(out, "{}", Colorize::cyan(s)),
-        Color::Blue => write!(out, "{}", Colorize::blue(s)),
-        Color::Orange => write!(out, "{}", Colorize::truecolor(s, 0xFF, 0xA5, 0x00)),
-        Color::Yellow => write!(out, "{}", Colorize::yellow(s)),
-        Color::Green => write!(out, "{}", Colorize::green(s)),
-        Color::Purple => write!(out, "{}", Colorize::purple(s)),
-        Color::Red => write!(out, "{}", Colorize::red(s)),
+    fn take_sound_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.sound_effects)
     }
+
+    /// Shift placed cells one column aside, wrapping around.
+    /// Nothing happens if the current figure is in the way.
+    fn chaos_event(&mut self) -> Option<String> {
+        let to_right = self.rng.gen_bool(0.5);
+        let board = self.board.shifted(to_right);
+
+        let is_blocked = self
+            .current_figure
+            .applied_rotation_and_position(
+                self.current_figure.rotation,
+                self.current_figure_position,
+            )
+            .iter()
+            .any(|p| {
+                let (x, y) = (p.x.round(), p.y.round());
+                (0.0..WIDTH as f32).contains(&x)
+                    && (0.0..HEIGHT as f32).contains(&y)
+                    && board.is_taken(x as usize, y as usize)
+            });
+        if is_blocked {
+            return None;
+        }
+        self.board = board;
+
+        let text = if to_right {
+            crate::i18n::Text::BoardShiftsRight
+        } else {
+            crate::i18n::Text::BoardShiftsLeft
+        };
+        Some(crate::i18n::tr(text).to_owned())
+    }
+
+    fn save(&self) -> Option<crate::save::SavedGame> {
+        Some(crate::save::SavedGame::Tetris(self.clone()))
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Game>> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn counts(&self) -> crate::lifetime::Counts {
+        crate::lifetime::Counts {
+            lines_cleared: self.lines_cleared as u64,
+            ..Default::default()
+        }
+    }
+
+    fn entities(&self) -> Vec<(&'static str, usize)> {
+        let cells = (0..Board::HEIGHT)
+            .map(|y| self.board.row_mask(y).count_ones() as usize)
+            .sum();
+        vec![("cells", cells)]
+    }
+
+    fn help(&self) -> Vec<String> {
+        use crate::help::key;
+        use crate::i18n::{format, tr, Text};
+
+        let rotate = match crate::config::get().keys.rotate {
+            Some(_) => format!("{}/{}", key(Action::MoveUp), key(Action::Rotate)),
+            None => key(Action::MoveUp),
+        };
+        let sideways = format!("{}/{}", key(Action::MoveLeft), key(Action::MoveRight));
+        vec![
+            format(Text::TetrisMove, &[&sideways]),
+            format(Text::TetrisRotate, &[&rotate]),
+            format(Text::TetrisDrop, &[&key(Action::MoveDown)]),
+            tr(Text::TetrisLines).to_owned(),
+            tr(Text::TetrisTwice).to_owned(),
+            tr(Text::TetrisTop).to_owned(),
+        ]
+    }
+
+    fn summary(&self) -> Vec<String> {
+        use crate::i18n::{format, Text};
+
+        vec![
+            format(Text::LinesCleared, &[&self.lines_cleared]),
+            format(Text::FiguresPlaced, &[&self.figures_placed]),
+        ]
+    }
+
+    /// Share of the placed cells that were cleared
+    fn performance(&self) -> Option<crate::stats::Performance> {
+        if self.figures_placed == 0 {
+            return None;
+        }
+        // A figure has 4 cells
+        let cleared = (self.lines_cleared * WIDTH) as f32 / (self.figures_placed * 4) as f32;
+
+        Some(crate::stats::Performance {
+            name: crate::i18n::Text::TetrisPerformance,
+            value: cleared.min(1.0),
+            tip: crate::i18n::Text::TetrisTip,
+        })
+    }
+}
+
+/// Screen cell of a point on the board. Board cells are two characters wide
+/// like the glyphs, whatever the cell aspect.
+fn board_cell(point: &Point<GameBasis>) -> Option<Cell> {
+    Point::<ScreenBasis>::new(point.x * 2.0, point.y).to_cell()
+}
+
+/// Cell of the figure with the color in the current theme and glyphs.
+pub fn figure_cell(col: Color) -> StyledContent<&'static str> {
+    // Figure order of the palette and the glyphs
+    let ind = match col {
+        Color::Cyan => 0,
+        Color::Blue => 1,
+        Color::Orange => 2,
+        Color::Yellow => 3,
+        Color::Green => 4,
+        Color::Purple => 5,
+        Color::Red => 6,
+    };
+
+    crate::glyphs::get().figures[ind].with(crate::theme::palette().figures[ind])
 }
@@ -0,0 +1,100 @@
+//! Single-line text field edited with the keyboard, for names, seeds and such.
+use crate::render::{FrameBuffer, Layer};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Stylize;
+
+#[derive(Clone, Debug, Default)]
+pub struct TextInput {
+    text: Vec<char>,
+    /// Index of the symbol the cursor is before
+    cursor: usize,
+    /// Longest text in symbols, None for no limit
+    max_length: Option<usize>,
+}
+
+impl TextInput {
+    /// Field with the given text and the cursor after it.
+    pub fn new(text: &str) -> Self {
+        let text: Vec<char> = text.chars().collect();
+        Self {
+            cursor: text.len(),
+            text,
+            max_length: None,
+        }
+    }
+
+    /// Longer text is cut, typing stops at the limit.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.text.truncate(max_length);
+        self.cursor = self.cursor.min(max_length);
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Edit with the key: typing inserts at the cursor, Backspace and Delete remove
+    /// around it, Left/Right/Home/End move it. Returns false for other keys.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+        match key.code {
+            // Shortcuts like Ctrl+C are not text
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                if self.max_length.is_none_or(|max| self.text.len() < max) {
+                    self.text.insert(self.cursor, c);
+                    self.cursor += 1;
+                }
+            }
+            KeyCode::Backspace => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                    self.text.remove(self.cursor);
+                }
+            }
+            KeyCode::Delete => {
+                if self.cursor < self.text.len() {
+                    self.text.remove(self.cursor);
+                }
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.text.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Draw the field `width` symbols wide with the cursor as a reversed symbol.
+    /// Text that doesn't fit scrolls to keep the cursor in sight.
+    pub fn draw(&self, frame: &mut FrameBuffer, layer: Layer, (x, y): (u16, u16), width: u16) {
+        let width = width as usize;
+        if width == 0 {
+            return;
+        }
+        // The cursor after the text takes a symbol too
+        let start = (self.cursor + 1).saturating_sub(width);
+        for column in 0..width {
+            let ind = start + column;
+            let symbol = self.text.get(ind).copied().unwrap_or(' ');
+            let x = x + column as u16;
+            if ind == self.cursor {
+                frame.print_styled(layer, x, y, symbol.reverse());
+            } else {
+                frame.print(layer, x, y, &symbol.to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Color themes: draw code takes colors from the palette of the current theme
+//! by their role instead of naming them.
+//!
+//! The theme is set with `theme` in the config or in the settings menu,
+//! the menu choice wins (see src/settings.rs).
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Name {
+    #[default]
+    Classic,
+    Monochrome,
+    Solarized,
+}
+
+impl Name {
+    pub const ALL: [Name; 3] = [Name::Classic, Name::Monochrome, Name::Solarized];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Name::Classic => "Classic",
+            Name::Monochrome => "Monochrome",
+            Name::Solarized => "Solarized",
+        }
+    }
+
+    pub fn palette(self) -> &'static Palette {
+        match self {
+            Name::Classic => &CLASSIC,
+            Name::Monochrome => &MONOCHROME,
+            Name::Solarized => &SOLARIZED,
+        }
+    }
+}
+
+/// Colors by role.
+#[derive(Debug)]
+pub struct Palette {
+    /// What the player controls: the snake, the cannon and its shots
+    pub player: Color,
+    /// Invaders and their shots
+    pub enemy: Color,
+    /// Plain apples
+    pub pickup: Color,
+    /// Shields, shield apples and the shielded snake
+    pub shield: Color,
+    /// Passing through things
+    pub phasing: Color,
+    /// Something to keep an eye on: sideways shots, a hot cannon
+    pub warning: Color,
+    /// Something gone wrong: an overheated cannon, a lost game
+    pub danger: Color,
+    /// Walls that can be shot through
+    pub barrier: Color,
+    /// Walls that can't
+    pub wall: Color,
+    /// Leading or positive scores
+    pub winning: Color,
+    /// Negative scores
+    pub losing: Color,
+    /// Scores from low to high
+    pub score_tiers: [Color; 4],
+    /// Tetris figures: line, L mirrored, L, square, Z mirrored, T, Z
+    pub figures: [Color; 7],
+    /// Heatmap cells from the coldest to the hottest
+    pub heat: [Color; 5],
+}
+
+/// Colors the games had before themes.
+pub const CLASSIC: Palette = Palette {
+    player: Color::Green,
+    enemy: Color::Red,
+    pickup: Color::Red,
+    shield: Color::Cyan,
+    phasing: Color::Magenta,
+    warning: Color::Yellow,
+    danger: Color::Red,
+    barrier: Color::Green,
+    wall: Color::Blue,
+    winning: Color::Green,
+    losing: Color::Red,
+    score_tiers: [Color::White, Color::Green, Color::Yellow, Color::Red],
+    figures: [
+        Color::DarkCyan,
+        Color::DarkBlue,
+        Color::Rgb {
+            r: 0xFF,
+            g: 0xA5,
+            b: 0x00,
+        },
+        Color::DarkYellow,
+        Color::DarkGreen,
+        Color::DarkMagenta,
+        Color::DarkRed,
+    ],
+    heat: [
+        Color::DarkBlue,
+        Color::Cyan,
+        Color::Green,
+        Color::Yellow,
+        Color::Red,
+    ],
+};
+
+/// Shades of grey, for terminals without colors.
+pub const MONOCHROME: Palette = Palette {
+    player: Color::White,
+    enemy: Color::Grey,
+    pickup: Color::White,
+    shield: Color::White,
+    phasing: Color::DarkGrey,
+    warning: Color::Grey,
+    danger: Color::White,
+    barrier: Color::Grey,
+    wall: Color::DarkGrey,
+    winning: Color::White,
+    losing: Color::DarkGrey,
+    score_tiers: [Color::DarkGrey, Color::Grey, Color::Grey, Color::White],
+    figures: [
+        Color::White,
+        Color::Grey,
+        Color::DarkGrey,
+        Color::White,
+        Color::Grey,
+        Color::DarkGrey,
+        Color::White,
+    ],
+    heat: [
+        Color::DarkGrey,
+        Color::DarkGrey,
+        Color::Grey,
+        Color::Grey,
+        Color::White,
+    ],
+};
+
+/// Accent colors of Ethan Schoonover's Solarized.
+pub const SOLARIZED: Palette = Palette {
+    player: GREEN,
+    enemy: RED,
+    pickup: ORANGE,
+    shield: CYAN,
+    phasing: VIOLET,
+    warning: YELLOW,
+    danger: RED,
+    barrier: GREEN,
+    wall: BLUE,
+    winning: GREEN,
+    losing: RED,
+    score_tiers: [BASE0, GREEN, YELLOW, ORANGE],
+    figures: [CYAN, BLUE, ORANGE, YELLOW, GREEN, MAGENTA, RED],
+    heat: [BLUE, CYAN, GREEN, YELLOW, RED],
+};
+
+const BASE0: Color = rgb(0x839496);
+const YELLOW: Color = rgb(0xb58900);
+const ORANGE: Color = rgb(0xcb4b16);
+const RED: Color = rgb(0xdc322f);
+const MAGENTA: Color = rgb(0xd33682);
+const VIOLET: Color = rgb(0x6c71c4);
+const BLUE: Color = rgb(0x268bd2);
+const CYAN: Color = rgb(0x2aa198);
+const GREEN: Color = rgb(0x859900);
+
+const fn rgb(hex: u32) -> Color {
+    Color::Rgb {
+        r: (hex >> 16) as u8,
+        g: (hex >> 8) as u8,
+        b: hex as u8,
+    }
+}
+
+/// Theme picked in the settings menu, or the one in the config.
+pub fn current() -> Name {
+    crate::settings::get()
+        .theme
+        .unwrap_or(crate::config::get().theme)
+}
+
+/// Palette of the current theme.
+pub fn palette() -> &'static Palette {
+    current().palette()
+}
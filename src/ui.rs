@@ -0,0 +1,197 @@
+//! Widgets drawn into screen regions: labels, centered text, list menus and
+//! progress bars, with `Frame` for boxes. A widget draws into the rectangle it
+//! is given and cuts what doesn't fit, `layout` places the rectangles.
+pub use crate::frame::{Frame, Lines};
+use crate::layout::Rect;
+use crate::render::{FrameBuffer, Layer};
+use crossterm::style::{Attribute, ContentStyle, StyledContent};
+use std::fmt::Display;
+
+/// Where a line goes in the width of its region.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Line of text made of parts in their own styles.
+#[derive(Clone, Debug, Default)]
+pub struct Label {
+    parts: Vec<(String, ContentStyle)>,
+    align: Align,
+}
+
+impl Label {
+    pub fn new(text: impl Display) -> Self {
+        Self::default().and(text)
+    }
+
+    pub fn styled(text: impl Display, style: ContentStyle) -> Self {
+        Self::default().and_styled(text, style)
+    }
+
+    /// Text after the parts before it.
+    pub fn and(self, text: impl Display) -> Self {
+        self.and_styled(text, ContentStyle::new())
+    }
+
+    pub fn and_styled(mut self, text: impl Display, style: ContentStyle) -> Self {
+        self.parts.push((text.to_string(), style));
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Symbols taken by the whole text.
+    pub fn width(&self) -> u16 {
+        self.parts
+            .iter()
+            .map(|(text, _)| text.chars().count() as u16)
+            .sum()
+    }
+
+    /// Draw the text in the top row of `area` and return the place it takes.
+    /// The end is cut if it doesn't fit.
+    pub fn draw(&self, frame: &mut FrameBuffer, layer: Layer, area: Rect) -> Rect {
+        if area.height == 0 {
+            return Rect::new(area.x, area.y, 0, 0);
+        }
+        let width = self.width().min(area.width);
+        let x = match self.align {
+            Align::Left => area.x,
+            Align::Center => area.centered(width, 1).x,
+            Align::Right => area.right() - width,
+        };
+
+        let mut left = width as usize;
+        let mut part_x = x;
+        for (text, style) in &self.parts {
+            let shown: String = text.chars().take(left).collect();
+            let count = shown.chars().count();
+            frame.print_styled(layer, part_x, area.y, StyledContent::new(*style, shown));
+            part_x += count as u16;
+            left -= count;
+        }
+        Rect::new(x, area.y, width, 1)
+    }
+}
+
+/// Lines one under another, each centered, in the middle of the region.
+#[derive(Clone, Debug, Default)]
+pub struct CenteredText {
+    lines: Vec<Label>,
+}
+
+impl CenteredText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn line(mut self, line: Label) -> Self {
+        self.lines.push(line.align(Align::Center));
+        self
+    }
+
+    /// Symbols taken by the longest line and the lines count.
+    pub fn size(&self) -> (u16, u16) {
+        let width = self.lines.iter().map(Label::width).max().unwrap_or(0);
+        (width, self.lines.len() as u16)
+    }
+
+    pub fn draw(&self, frame: &mut FrameBuffer, layer: Layer, area: Rect) {
+        let top = area.centered(area.width, self.lines.len() as u16).y;
+        for (ind, line) in self.lines.iter().enumerate() {
+            let row = area.shrink(0, top - area.y + ind as u16, 0, 0);
+            line.draw(frame, layer, row);
+        }
+    }
+}
+
+/// Numbered items in a box, the selected one highlighted.
+#[derive(Clone, Debug)]
+pub struct ListMenu<'a> {
+    title: &'a str,
+    items: &'a [String],
+    selected: usize,
+}
+
+impl<'a> ListMenu<'a> {
+    pub fn new(title: &'a str, items: &'a [String], selected: usize) -> Self {
+        Self {
+            title,
+            items,
+            selected,
+        }
+    }
+
+    /// Size of the box around the items, with room for the title
+    /// with a line on both sides.
+    pub fn size(&self) -> (u16, u16) {
+        let width = self
+            .items
+            .iter()
+            .map(|item| item.chars().count())
+            .chain([self.title.chars().count() + 4])
+            .max()
+            .unwrap_or(0) as u16;
+        (width + 2, self.items.len() as u16 + 2)
+    }
+
+    /// Draw the box in `area` and return its inside.
+    pub fn draw(&self, frame: &mut FrameBuffer, layer: Layer, area: Rect) -> Rect {
+        let inner = Frame::new().title(self.title).draw(frame, layer, area);
+        for (ind, item) in self.items.iter().enumerate() {
+            let mut style = ContentStyle::new();
+            if ind == self.selected {
+                style.attributes.set(Attribute::Reverse);
+            }
+            let row = inner.shrink(0, ind as u16, 0, 0);
+            Label::styled(item, style).draw(frame, layer, row);
+        }
+        inner
+    }
+}
+
+/// Bar like `Heat [####    ]` filled from 0 to 1, with an optional label before it.
+#[derive(Clone, Debug)]
+pub struct ProgressBar<'a> {
+    fill: f32,
+    width: u16,
+    label: &'a str,
+    style: ContentStyle,
+}
+
+impl<'a> ProgressBar<'a> {
+    /// Bar `width` symbols wide, the label not included.
+    pub fn new(fill: f32, width: u16) -> Self {
+        Self {
+            fill,
+            width,
+            label: "",
+            style: ContentStyle::new(),
+        }
+    }
+
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Style of the label and the bar.
+    pub fn style(mut self, style: ContentStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Draw the bar in the top row of `area` and return the place it takes.
+    pub fn draw(&self, frame: &mut FrameBuffer, layer: Layer, area: Rect) -> Rect {
+        Label::styled(self.label, self.style)
+            .and_styled(crate::render::bar(self.fill, self.width), self.style)
+            .draw(frame, layer, area)
+    }
+}
@@ -1 +1,36 @@
+use std::sync::Mutex;
+
 pub const MORE_THAN_HALF_CELL: f32 = 0.5 + f32::EPSILON;
+
+/// Symbols of the number written in decimal.
+pub fn digits(num: u64) -> u16 {
+    num.checked_ilog10().map_or(1, |log| log as u16 + 1)
+}
+
+static SCREEN_SIZE: Mutex<Option<(u16, u16)>> = Mutex::new(None);
+
+/// Use a fixed screen size instead of the terminal one (e.g. without a terminal).
+pub fn set_screen_size(width: u16, height: u16) {
+    *SCREEN_SIZE.lock().expect("screen size lock poisoned") = Some((width, height));
+}
+
+/// Run `f` with a fixed screen size, e.g. for a game drawn in a part of the screen.
+/// The previous size is restored afterwards.
+pub fn with_screen_size<T>(width: u16, height: u16, f: impl FnOnce() -> T) -> T {
+    let prev = SCREEN_SIZE
+        .lock()
+        .expect("screen size lock poisoned")
+        .replace((width, height));
+    let result = f();
+    *SCREEN_SIZE.lock().expect("screen size lock poisoned") = prev;
+    result
+}
+
+/// Screen size in symbols: the fixed one or the terminal one.
+pub fn screen_size() -> (u16, u16) {
+    let size = *SCREEN_SIZE.lock().expect("screen size lock poisoned");
+    match size {
+        Some(size) => size,
+        None => crossterm::terminal::size().expect("Failed to get terminal size"),
+    }
+}
@@ -0,0 +1,298 @@
+//! Two games side by side on one keyboard.
+//!
+//! With the default keys the left player uses WASD and Space, the right player
+//! uses the arrows and Enter. Esc ends the match, p pauses both games.
+//! Scores of different games are compared relative to their `par_score`.
+use crate::config;
+use crate::game::{Game, UpdateEvent, EXIT_BUTTON, PAUSE_BUTTON};
+use crate::i18n::{tr, Text};
+use crate::input::{self, FrameInput, HeldKeys, Input, Presses};
+use crate::point::{Cell, GameBasis, PlayArea, Point, ScreenBasis};
+use crate::registry::GameEntry;
+use crate::render::{FrameBuffer, Layer};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use rand::{rngs::StdRng, SeedableRng};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Player owning the key and the key as the games expect it.
+fn route(key: KeyEvent) -> Option<(Side, KeyEvent)> {
+    let (side, code) = match key.code {
+        KeyCode::Char('w') => (Side::Left, KeyCode::Up),
+        KeyCode::Char('a') => (Side::Left, KeyCode::Left),
+        KeyCode::Char('s') => (Side::Left, KeyCode::Down),
+        KeyCode::Char('d') => (Side::Left, KeyCode::Right),
+        KeyCode::Char(' ') => (Side::Left, KeyCode::Char(' ')),
+        KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => (Side::Right, key.code),
+        KeyCode::Enter => (Side::Right, KeyCode::Char(' ')),
+        _ => return None,
+    };
+    Some((side, KeyEvent { code, ..key }))
+}
+
+struct Player {
+    name: &'static str,
+    par_score: i64,
+    game: Box<dyn Game>,
+    /// The player's part of the screen
+    area: PlayArea,
+    is_over: bool,
+}
+
+impl Player {
+    fn new(entry: &GameEntry, area: PlayArea) -> Self {
+        Self {
+            name: entry.name,
+            par_score: entry.par_score,
+            game: (entry.create)(StdRng::from_entropy(), area),
+            area,
+            is_over: false,
+        }
+    }
+
+    /// Score in percents of the par score.
+    fn relative_score(&self) -> i64 {
+        self.game.get_score().value * 100 / self.par_score.max(1)
+    }
+}
+
+/// Players' parts of the screen: halves without the divider and the header.
+fn viewports(width: u16, height: u16) -> [PlayArea; 2] {
+    let (view_width, view_height) = (width.saturating_sub(1) / 2, height.saturating_sub(1));
+    [0, view_width + 1].map(|x| {
+        let origin = Point::<ScreenBasis>::from(Cell::new(x, 1)).into();
+        let size = Point::<GameBasis>::from_screen_size(view_width, view_height);
+        PlayArea::new(origin, size.x, size.y)
+    })
+}
+
+/// Play a match until both games are over or Esc is pressed.
+pub fn play(
+    out: &mut std::io::Stdout,
+    rx: &Receiver<Event>,
+    left: &GameEntry,
+    right: &GameEntry,
+) -> crossterm::Result<()> {
+    use crossterm::{execute, terminal};
+
+    let result = run(out, rx, left, right);
+    execute!(
+        out,
+        terminal::Clear(terminal::ClearType::All),
+        crossterm::cursor::MoveTo(0, 0)
+    )?;
+
+    result
+}
+
+fn run(
+    out: &mut std::io::Stdout,
+    rx: &Receiver<Event>,
+    left: &GameEntry,
+    right: &GameEntry,
+) -> crossterm::Result<()> {
+    let mut frame = FrameBuffer::from_terminal()?;
+    // Games keep the size they were made for
+    let [left_area, right_area] = viewports(frame.width(), frame.height());
+    let mut players = [Player::new(left, left_area), Player::new(right, right_area)];
+    let mut views = players.each_ref().map(|player| {
+        let (width, height) = player.area.screen_size();
+        FrameBuffer::new(width, height)
+    });
+    let mut held = [(); 2].map(|_| HeldKeys::default());
+    let mut sound = crate::sound::from_config();
+    let mut is_paused = false;
+    // Esc asks first, the match is frozen until the answer
+    let mut is_quit_asked = false;
+    let mut prev_time = Instant::now();
+    let mut delta_filter = crate::pacing::DeltaFilter::default();
+    let renderer = crate::render::RenderThread::spawn();
+
+    'match_loop: loop {
+        let current_time = Instant::now();
+        let delta = delta_filter.filter(current_time - prev_time);
+        prev_time = current_time;
+
+        // Each player gets their first key of the frame and the count of all presses
+        let mut inputs: [Option<Input>; 2] = [None, None];
+        let mut presses: [Presses; 2] = Default::default();
+        loop {
+            let key = match rx.try_recv() {
+                Ok(Event::Key(key)) => key,
+                // One mouse can't be shared
+                Ok(_) => continue,
+                Err(TryRecvError::Disconnected) => panic!("stdin disconnected"),
+                Err(TryRecvError::Empty) => break,
+            };
+            if let Some((side, key)) = route(key) {
+                held[side as usize].update(&key, input::default_action(key.code));
+            }
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            if crate::term::is_quit_requested() {
+                break 'match_loop;
+            }
+            if is_quit_asked {
+                match crate::dialog::answer(&key) {
+                    Some(true) => break 'match_loop,
+                    Some(false) => is_quit_asked = false,
+                    None => {}
+                }
+                continue;
+            }
+            match key.code {
+                EXIT_BUTTON if is_paused => break 'match_loop,
+                EXIT_BUTTON => is_quit_asked = true,
+                PAUSE_BUTTON => is_paused = !is_paused,
+                _ => {
+                    if let Some((side, key)) = route(key) {
+                        let input = Input::from_default_key(key);
+                        if let Some(action) = input.action {
+                            presses[side as usize].add(action);
+                        }
+                        inputs[side as usize].get_or_insert(input);
+                    }
+                }
+            }
+        }
+
+        if !is_paused && !is_quit_asked {
+            let players_input = inputs.into_iter().zip(presses).zip(held.iter());
+            for (player, ((input, presses), held)) in players.iter_mut().zip(players_input) {
+                if player.is_over {
+                    continue;
+                }
+                let input = FrameInput::new(input)
+                    .with_presses(presses)
+                    .with_held(held.actions());
+                let event = player.game.update(&input, &delta);
+                player.is_over = matches!(event, UpdateEvent::GameOver);
+                for effect in player.game.take_sound_effects() {
+                    sound.play_effect(effect);
+                }
+            }
+        }
+
+        draw(&mut frame, &mut views, &players, &delta)?;
+        if is_paused {
+            let y = frame.height() / 2;
+            print_centered(&mut frame, y, tr(Text::VersusPaused));
+        }
+        if is_quit_asked {
+            crate::dialog::draw_question(&mut frame, tr(Text::EndMatch));
+        }
+        renderer.submit(&frame)?;
+
+        if players.iter().all(|player| player.is_over) {
+            break 'match_loop;
+        }
+
+        let frame_time = current_time.elapsed();
+        std::thread::sleep(config::get().between_frames().saturating_sub(frame_time));
+    }
+
+    renderer.finish()?;
+
+    // Show the result until a key is pressed
+    let [left, right] = &players;
+    let result = match left.relative_score().cmp(&right.relative_score()) {
+        std::cmp::Ordering::Greater => tr(Text::LeftWins),
+        std::cmp::Ordering::Less => tr(Text::RightWins),
+        std::cmp::Ordering::Equal => tr(Text::Draw),
+    };
+    draw(&mut frame, &mut views, &players, &Duration::ZERO)?;
+    let y = frame.height() / 2;
+    print_centered(&mut frame, y, result);
+    print_centered(&mut frame, y + 1, tr(Text::AnyKeyForMenu));
+    frame.flush(out)?;
+
+    if crate::term::is_quit_requested() {
+        return Ok(());
+    }
+    // Keys pressed while playing must not skip the result
+    std::thread::sleep(Duration::from_millis(750));
+    while rx.try_recv().is_ok() {}
+    crate::input::recv_key(rx);
+
+    Ok(())
+}
+
+fn draw(
+    frame: &mut FrameBuffer,
+    views: &mut [FrameBuffer; 2],
+    players: &[Player; 2],
+    delta_time: &Duration,
+) -> crossterm::Result<()> {
+    use crossterm::style::Stylize;
+
+    let (width, height) = crossterm::terminal::size()?;
+    frame.clear(width, height);
+
+    let leader = players
+        .iter()
+        .map(Player::relative_score)
+        .max()
+        .expect("two players");
+    for (player, view) in players.iter().zip(views.iter_mut()) {
+        let (view_width, view_height) = (view.width(), view.height());
+        view.clear(view_width, view_height);
+        player.game.draw(view, delta_time);
+        if player.is_over {
+            let text = tr(Text::VersusGameOver);
+            view.print_styled(
+                Layer::Overlay,
+                view_width.saturating_sub(text.chars().count() as u16) / 2,
+                view_height / 2,
+                text.with(crate::theme::palette().danger).bold(),
+            );
+        }
+
+        let origin = player.area.origin_cell();
+        let x = origin.x;
+        frame.blit(view, x, origin.y);
+
+        let header = format!(
+            "{}: {} ({}%)",
+            player.name,
+            player.game.get_score().value,
+            player.relative_score()
+        );
+        if player.relative_score() == leader {
+            frame.print_styled(
+                Layer::Hud,
+                x,
+                0,
+                header.with(crate::theme::palette().winning).bold(),
+            );
+        } else {
+            frame.print(Layer::Hud, x, 0, &header);
+        }
+    }
+
+    // Divider between the viewports
+    let divider_x = views[0].width();
+    let divider = crate::glyphs::get().divider;
+    for y in 0..height {
+        frame.print(Layer::Hud, divider_x, y, divider);
+    }
+
+    Ok(())
+}
+
+fn print_centered(frame: &mut FrameBuffer, y: u16, text: &str) {
+    use crossterm::style::Stylize;
+
+    frame.print_styled(
+        Layer::Overlay,
+        frame.width().saturating_sub(text.chars().count() as u16) / 2,
+        y,
+        text.bold().reverse(),
+    );
+}
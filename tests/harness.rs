@@ -0,0 +1,73 @@
+//! Scripted runs: a game set up in a known state, keys pressed at given times
+//! and the outcome checked, see src/harness.rs.
+#![cfg(all(feature = "snake", feature = "tetris"))]
+
+use cl_games::game::Game;
+use cl_games::harness::Harness;
+use cl_games::point::{Line, PlayArea, Point};
+use cl_games::registry::Registry;
+use cl_games::snake::SnakeGame;
+use cl_games::tetris::{Board, Color, Figure, FigureType, TetrisGame};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::Duration;
+
+const SEED: u64 = 7;
+const SCREEN_SIZE: (u16, u16) = (40, 24);
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn harness(game: impl Game + 'static) -> Harness {
+    Harness::new(Box::new(game), SCREEN_SIZE.0, SCREEN_SIZE.1)
+}
+
+#[test]
+fn tetris_square_clears_two_lines() {
+    let mut game = TetrisGame::new(StdRng::seed_from_u64(SEED));
+    // The square falls into the gap the bottom rows leave under its start
+    for y in Board::HEIGHT - 2..Board::HEIGHT {
+        for x in (0..Board::WIDTH).filter(|x| !(3..=4).contains(x)) {
+            game.board.set(x, y, Some(Color::Cyan));
+        }
+    }
+    game.current_figure = Figure::new(FigureType::Square, 0.0);
+
+    let mut harness = harness(game);
+    harness.wait(Duration::from_secs(8));
+    harness.assert_running();
+    harness.assert_score(200);
+}
+
+#[test]
+fn snake_running_into_itself_ends_the_game() {
+    let area = PlayArea::screen(SCREEN_SIZE.0, SCREEN_SIZE.1);
+    let mut game = SnakeGame::new(Point::new(2.0, 10.0), area, StdRng::seed_from_u64(SEED));
+    game.snake.segments = vec![Line::new(Point::new(2.0, 10.0), Point::new(14.0, 10.0))];
+
+    let mut harness = harness(game);
+    let step = Duration::from_millis(100);
+    harness.play(&[(key(KeyCode::Down), step), (key(KeyCode::Left), step)]);
+    harness.assert_running();
+    harness.play(&[(key(KeyCode::Up), Duration::from_millis(500))]);
+    harness.assert_game_over();
+}
+
+#[test]
+fn same_seed_and_keys_play_the_same() {
+    let registry = Registry::builtin();
+    let script = [
+        (key(KeyCode::Left), Duration::from_millis(300)),
+        (key(KeyCode::Up), Duration::from_millis(700)),
+        (key(KeyCode::Right), Duration::from_secs(2)),
+    ];
+    for entry in registry.games() {
+        let [first, second] = [(); 2].map(|_| {
+            let mut harness = Harness::from_entry(entry, SEED, SCREEN_SIZE.0, SCREEN_SIZE.1);
+            harness.play(&script);
+            harness.lines()
+        });
+        assert_eq!(first, second, "{} differs between runs", entry.name);
+    }
+}
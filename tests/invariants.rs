@@ -0,0 +1,137 @@
+//! Rules that hold whatever the player presses: random seeds and key presses
+//! are fed to the games frame by frame and the state is checked after every update.
+#![cfg(all(feature = "snake", feature = "tetris", feature = "invaders"))]
+
+use cl_games::game::Game;
+use cl_games::input::{FrameInput, Input};
+use cl_games::point::{PlayArea, Point};
+use cl_games::snake::SnakeGame;
+use cl_games::space_invaders::{EnemyPreset, PropsPreset, SpaceInvadersGame};
+use cl_games::tetris::{Board, Figure, FigureType, TetrisGame};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::Duration;
+
+/// Every test plays on a screen of the same size.
+const SCREEN_SIZE: (u16, u16) = cl_games::headless::SCREEN_SIZE;
+const FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+/// Keys that play. Exit, pause and suspend are left out.
+const KEYS: [KeyCode; 5] = [
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Char(' '),
+];
+
+/// Frames with a key or none, up to a minute of play.
+fn frames() -> impl Strategy<Value = Vec<Option<Input>>> {
+    let key = proptest::option::of(0..KEYS.len()).prop_map(|ind| {
+        ind.map(|ind| Input::from_default_key(KeyEvent::new(KEYS[ind], KeyModifiers::NONE)))
+    });
+    proptest::collection::vec(key, 1..3600)
+}
+
+/// Update the game with the frames until it's over, checking after every update.
+fn play<G: Game>(game: &mut G, frames: &[Option<Input>], mut check: impl FnMut(&G)) {
+    for input in frames {
+        let event = game.update(&FrameInput::new(*input), &FRAME_TIME);
+        check(game);
+        if let cl_games::game::UpdateEvent::GameOver = event {
+            break;
+        }
+    }
+}
+
+/// Check that the score never goes down.
+fn score_not_decreasing<G: Game>() -> impl FnMut(&G) {
+    let mut previous = 0;
+    move |game: &G| {
+        let score = game.get_score().value;
+        assert!(
+            score >= previous,
+            "score went from {} to {}",
+            previous,
+            score
+        );
+        previous = score;
+    }
+}
+
+/// A line lying on the top row can't be turned up over the board.
+#[test]
+fn tetris_line_does_not_rotate_over_the_top() {
+    let mut game = TetrisGame::new(StdRng::seed_from_u64(0));
+    game.current_figure = Figure::new(FigureType::Line, 0.0);
+    let up = Input::from_default_key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+    // Past the time between moves, before the first descend
+    let frames = [None, None, None, None, None, None, None, None, Some(up)];
+    play(&mut game, &frames, |_| {});
+
+    assert_eq!(game.current_figure.rotation, 0.0);
+    let figure = game
+        .current_figure
+        .applied_rotation_and_position(game.current_figure.rotation, game.current_figure_position);
+    assert!(figure.iter().all(|point| point.y.round() >= 0.0));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn snake_score_never_decreases(seed: u64, frames in frames()) {
+        let rng = StdRng::seed_from_u64(seed);
+        let area = PlayArea::screen(SCREEN_SIZE.0, SCREEN_SIZE.1);
+        let mut game = SnakeGame::new(Point::new(5.0, 5.0), area, rng);
+        play(&mut game, &frames, score_not_decreasing());
+    }
+
+    #[test]
+    fn tetris_score_never_decreases(seed: u64, frames in frames()) {
+        let mut game = TetrisGame::new(StdRng::seed_from_u64(seed));
+        play(&mut game, &frames, score_not_decreasing());
+    }
+
+    #[test]
+    fn tetris_figure_stays_on_the_board(seed: u64, frames in frames()) {
+        let mut game = TetrisGame::new(StdRng::seed_from_u64(seed));
+        let (width, height) = (Board::WIDTH as f32, Board::HEIGHT as f32);
+        play(&mut game, &frames, |game| {
+            let figure = game
+                .current_figure
+                .applied_rotation_and_position(game.current_figure.rotation, game.current_figure_position);
+            for point in figure {
+                assert!(
+                    (0.0..width).contains(&point.x.round()) && (0.0..height).contains(&point.y.round()),
+                    "figure cell at {}, {}", point.x, point.y
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn invaders_score_never_decreases(seed: u64, frames in frames()) {
+        let (width, height) = SCREEN_SIZE;
+        let rng = StdRng::seed_from_u64(seed);
+        let mut game =
+            SpaceInvadersGame::new(height, width, EnemyPreset::RandomFire, PropsPreset::Wall, rng);
+        play(&mut game, &frames, score_not_decreasing());
+    }
+
+    #[test]
+    fn invaders_bullets_stay_on_the_screen(seed: u64, frames in frames()) {
+        let (width, height) = SCREEN_SIZE;
+        let rng = StdRng::seed_from_u64(seed);
+        let mut game =
+            SpaceInvadersGame::new(height, width, EnemyPreset::RandomFire, PropsPreset::Wall, rng);
+        play(&mut game, &frames, |game| {
+            for position in game.bullet_positions() {
+                assert!(
+                    position.bounds_check(width, height).is_none(),
+                    "bullet at {}, {}", position.x, position.y
+                );
+            }
+        });
+    }
+}
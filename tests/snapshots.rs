@@ -0,0 +1,74 @@
+//! Golden frames of games in known states, see src/snapshot.rs.
+//! `UPDATE_GOLDEN=1 cargo test --test snapshots` writes them anew.
+#![cfg(all(feature = "snake", feature = "tetris", feature = "invaders"))]
+
+use cl_games::harness::Harness;
+use cl_games::registry::Registry;
+use cl_games::snapshot;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const SEED: u64 = 7;
+const SCREEN_SIZE: (u16, u16) = (60, 24);
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+/// Game of the registry after the script, the seed and screen fixed.
+fn played(id: &str, script: &[(KeyEvent, Duration)]) -> Harness {
+    let registry = Registry::builtin();
+    let entry = registry
+        .games()
+        .iter()
+        .find(|entry| entry.id == id)
+        .expect("game registered");
+    let mut harness = Harness::from_entry(entry, SEED, SCREEN_SIZE.0, SCREEN_SIZE.1);
+    harness.play(script);
+    harness.assert_running();
+    harness
+}
+
+fn golden(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.txt", name))
+}
+
+#[test]
+fn tetris_frame() {
+    let harness = played(
+        "tetris",
+        &[
+            (key(KeyCode::Left), Duration::from_secs(1)),
+            (key(KeyCode::Up), Duration::from_secs(8)),
+            (key(KeyCode::Right), Duration::from_secs(2)),
+        ],
+    );
+    snapshot::assert_golden(&harness.lines(), &golden("tetris"));
+}
+
+#[test]
+fn snake_frame() {
+    let harness = played(
+        "snake",
+        &[
+            (key(KeyCode::Down), Duration::from_millis(500)),
+            (key(KeyCode::Right), Duration::from_secs(3)),
+        ],
+    );
+    snapshot::assert_golden(&harness.lines(), &golden("snake"));
+}
+
+#[test]
+fn invaders_frame() {
+    let harness = played(
+        "invaders",
+        &[
+            (key(KeyCode::Char(' ')), Duration::from_millis(300)),
+            (key(KeyCode::Left), Duration::from_secs(1)),
+        ],
+    );
+    snapshot::assert_golden(&harness.lines(), &golden("invaders"));
+}